@@ -233,55 +233,72 @@ app.post('/api/chat/:tokenAddress', chatLimiter, requireWalletSig, async (req, r
 // ══════════════════════════════════════════
 // TRADES & PRICE HISTORY
 // ══════════════════════════════════════════
-
-// Record a trade
-app.post('/api/trade/:tokenAddress', async (req, res) => {
-  const { wallet, tx_sig, trade_type, sol_amount, token_amount, price_lamports } = req.body;
-  if (!wallet || !tx_sig || !trade_type) return res.status(400).json({ error: 'Missing fields' });
-  try {
-    await pool.query(`
-      INSERT INTO trades (token_address, tx_sig, wallet, trade_type, sol_amount, token_amount, price_lamports)
-      VALUES ($1, $2, $3, $4, $5, $6, $7)
-      ON CONFLICT(tx_sig) DO NOTHING
-    `, [req.params.tokenAddress, tx_sig, wallet, trade_type, sol_amount || 0, token_amount || 0, price_lamports || 0]);
-    res.json({ ok: true });
-  } catch(e) { res.status(500).json({ error: e.message }); }
-});
+// Written by sames-indexer (indexer/) from on-chain program events;
+// the API only reads them.
 
 // Get trades for a token
 app.get('/api/trades/:tokenAddress', async (req, res) => {
   const limit = Math.min(parseInt(req.query.limit) || 100, 500);
   try {
     const { rows } = await pool.query(`
-      SELECT t.*, p.username, p.pfp_url FROM trades t
-      LEFT JOIN profiles p ON t.wallet = p.wallet
-      WHERE t.token_address = $1
-      ORDER BY t.created_at ASC LIMIT $2
+      SELECT * FROM (
+        SELECT t.signature AS tx_sig, t.wallet, t.trade_type, t.sol_amount, t.token_amount,
+               t.price_lamports, t.created_at, t.slot, t.event_index, p.username, p.pfp_url
+        FROM chain_trades t
+        LEFT JOIN profiles p ON t.wallet = p.wallet
+        WHERE t.launch_pool = $1
+        ORDER BY t.slot DESC, t.event_index DESC LIMIT $2
+      ) recent ORDER BY slot ASC, event_index ASC
     `, [req.params.tokenAddress, limit]);
     res.json(rows);
   } catch(e) { res.status(500).json({ error: e.message }); }
 });
 
-// Record price snapshot
-app.post('/api/snapshot/:tokenAddress', async (req, res) => {
-  const { price_lamports, tokens_sold, sol_collected } = req.body;
+// Get price history for a token (one point per trade)
+app.get('/api/prices/:tokenAddress', async (req, res) => {
+  const limit = Math.min(parseInt(req.query.limit) || 200, 1000);
   try {
-    await pool.query(`
-      INSERT INTO price_snapshots (token_address, price_lamports, tokens_sold, sol_collected)
-      VALUES ($1, $2, $3, $4)
-    `, [req.params.tokenAddress, price_lamports, tokens_sold || 0, sol_collected || 0]);
-    res.json({ ok: true });
+    const { rows } = await pool.query(`
+      SELECT * FROM (
+        SELECT price_lamports, tokens_sold, sol_collected, created_at, slot, event_index
+        FROM chain_trades
+        WHERE launch_pool = $1
+        ORDER BY slot DESC, event_index DESC LIMIT $2
+      ) recent ORDER BY slot ASC, event_index ASC
+    `, [req.params.tokenAddress, limit]);
+    res.json(rows);
   } catch(e) { res.status(500).json({ error: e.message }); }
 });
 
-// Get price history for a token
-app.get('/api/prices/:tokenAddress', async (req, res) => {
+// Get OHLC candles for a token; interval is 60, 300, 3600 or 86400 seconds
+app.get('/api/candles/:tokenAddress', async (req, res) => {
+  const interval = parseInt(req.query.interval) || 300;
   const limit = Math.min(parseInt(req.query.limit) || 200, 1000);
   try {
     const { rows } = await pool.query(`
-      SELECT * FROM price_snapshots
-      WHERE token_address = $1
-      ORDER BY created_at ASC LIMIT $2
+      SELECT * FROM (
+        SELECT bucket_start, open, high, low, close, volume_lamports, trades
+        FROM price_candles
+        WHERE launch_pool = $1 AND interval_secs = $2
+        ORDER BY bucket_start DESC LIMIT $3
+      ) recent ORDER BY bucket_start ASC
+    `, [req.params.tokenAddress, interval, limit]);
+    res.json(rows);
+  } catch(e) { res.status(500).json({ error: e.message }); }
+});
+
+// Get the largest holders of a launch's token
+app.get('/api/holders/:tokenAddress', async (req, res) => {
+  const limit = Math.min(parseInt(req.query.limit) || 50, 500);
+  try {
+    const { rows } = await pool.query(`
+      SELECT h.owner, SUM(h.amount) AS amount, p.username, p.pfp_url
+      FROM holder_balances h
+      JOIN launches l ON l.mint = h.mint
+      LEFT JOIN profiles p ON h.owner = p.wallet
+      WHERE l.launch_pool = $1 AND h.amount > 0
+      GROUP BY h.owner, p.username, p.pfp_url
+      ORDER BY amount DESC LIMIT $2
     `, [req.params.tokenAddress, limit]);
     res.json(rows);
   } catch(e) { res.status(500).json({ error: e.message }); }
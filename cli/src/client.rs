@@ -0,0 +1,177 @@
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{json, Value};
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_client::rpc_response::RpcSimulateTransactionResult;
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+
+/// What the commands need from a cluster. [`RpcClient`] provides it against
+/// a node; the tests provide it over an in-process bank.
+pub trait Chain {
+    fn latest_blockhash(&self) -> Result<Hash>;
+    fn send_and_confirm(&self, tx: &Transaction) -> Result<Signature>;
+    fn simulate(&self, tx: &Transaction) -> Result<RpcSimulateTransactionResult>;
+    fn account(&self, address: &Pubkey) -> Result<Option<Account>>;
+    fn minimum_balance_for_rent_exemption(&self, size: usize) -> Result<u64>;
+    /// The SAMES program's accounts that pass `config`'s filters.
+    fn program_accounts(&self, config: RpcProgramAccountsConfig) -> Result<Vec<(Pubkey, Account)>>;
+}
+
+impl Chain for RpcClient {
+    fn latest_blockhash(&self) -> Result<Hash> {
+        Ok(self.get_latest_blockhash()?)
+    }
+
+    fn send_and_confirm(&self, tx: &Transaction) -> Result<Signature> {
+        Ok(self.send_and_confirm_transaction(tx)?)
+    }
+
+    fn simulate(&self, tx: &Transaction) -> Result<RpcSimulateTransactionResult> {
+        Ok(self.simulate_transaction(tx)?.value)
+    }
+
+    fn account(&self, address: &Pubkey) -> Result<Option<Account>> {
+        Ok(self.get_account_with_commitment(address, self.commitment())?.value)
+    }
+
+    fn minimum_balance_for_rent_exemption(&self, size: usize) -> Result<u64> {
+        Ok(self.get_minimum_balance_for_rent_exemption(size)?)
+    }
+
+    fn program_accounts(&self, config: RpcProgramAccountsConfig) -> Result<Vec<(Pubkey, Account)>> {
+        Ok(self.get_program_accounts_with_config(&sames::ID, config)?)
+    }
+}
+
+/// A cluster plus the signing keypair.
+pub struct Client {
+    pub chain: Box<dyn Chain>,
+    pub payer: Keypair,
+    dry_run: bool,
+}
+
+impl Client {
+    pub fn new(url: &str, keypair: &str, dry_run: bool) -> Result<Self> {
+        let rpc = RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed());
+        Ok(Self::with_chain(Box::new(rpc), read_keypair(keypair)?, dry_run))
+    }
+
+    pub fn with_chain(chain: Box<dyn Chain>, payer: Keypair, dry_run: bool) -> Self {
+        Self { chain, payer, dry_run }
+    }
+
+    pub fn pubkey(&self) -> Pubkey {
+        self.payer.pubkey()
+    }
+
+    /// Sign with the payer (and `signers`) and send, or simulate with --dry-run.
+    pub fn send(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<Value> {
+        let mut all: Vec<&Keypair> = vec![&self.payer];
+        all.extend_from_slice(signers);
+        let blockhash = self.chain.latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(instructions, Some(&self.pubkey()), &all, blockhash);
+
+        if self.dry_run {
+            let result = self.chain.simulate(&tx)?;
+            return Ok(json!({
+                "simulated": true,
+                "error": result.err.map(|err| err.to_string()),
+                "units_consumed": result.units_consumed,
+                "logs": result.logs.unwrap_or_default(),
+            }));
+        }
+        let signature = self.chain.send_and_confirm(&tx)?;
+        Ok(json!({ "signature": signature.to_string() }))
+    }
+
+    /// Fetch and decode a program account.
+    pub fn account<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T> {
+        self.optional_account(address)?.ok_or_else(|| anyhow!("fetching {address}: account not found"))
+    }
+
+    /// Fetch and decode a program account, `None` if it doesn't exist.
+    pub fn optional_account<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<Option<T>> {
+        let account = self.chain.account(address)
+            .with_context(|| format!("fetching {address}"))?;
+        account
+            .map(|account| {
+                T::try_deserialize(&mut account.data.as_slice()).map_err(|err| anyhow!("decoding {address}: {err}"))
+            })
+            .transpose()
+    }
+
+    /// Every `T` account of the program whose data has `key` at `offset`.
+    pub fn accounts_with<T: AccountDeserialize + Discriminator>(
+        &self,
+        offset: usize,
+        key: &Pubkey,
+    ) -> Result<Vec<(Pubkey, T)>> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, T::DISCRIMINATOR.to_vec())),
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(offset, key.to_bytes().to_vec())),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        self.chain.program_accounts(config)?
+            .into_iter()
+            .map(|(address, account)| {
+                let decoded = T::try_deserialize(&mut account.data.as_slice())
+                    .map_err(|err| anyhow!("decoding {address}: {err}"))?;
+                Ok((address, decoded))
+            })
+            .collect()
+    }
+
+    /// Addresses of the program's `T` accounts whose data is `size` bytes:
+    /// accounts still in an older layout, which `T` can't decode. With
+    /// `key_at`, only those with that key at that offset.
+    pub fn addresses_sized<T: Discriminator>(
+        &self,
+        size: usize,
+        key_at: Option<(usize, &Pubkey)>,
+    ) -> Result<Vec<Pubkey>> {
+        let mut filters = vec![
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, T::DISCRIMINATOR.to_vec())),
+            RpcFilterType::DataSize(size as u64),
+        ];
+        if let Some((offset, key)) = key_at {
+            filters.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(offset, key.to_bytes().to_vec())));
+        }
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: Some(UiDataSliceConfig { offset: 0, length: 0 }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let accounts = self.chain.program_accounts(config)?;
+        Ok(accounts.into_iter().map(|(address, _)| address).collect())
+    }
+}
+
+pub fn read_keypair(path: &str) -> Result<Keypair> {
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => format!("{}/{rest}", std::env::var("HOME").unwrap_or_default()),
+        None => path.to_string(),
+    };
+    match read_keypair_file(&path) {
+        Ok(keypair) => Ok(keypair),
+        Err(err) => bail!("reading keypair {path}: {err}"),
+    }
+}
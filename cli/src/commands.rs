@@ -0,0 +1,295 @@
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use sames::state::{
+    BuyerRecord, LaunchPool, LaunchStatus, LegacyBuyerRecord, LegacyMarketRegistry, MarketEntry, MarketKind,
+    MarketRegistry, ProtocolConfig, VestingSchedule, ENTRY_PRICE_SCALE,
+};
+use sames_sdk::{instructions, pda, quote};
+use solana_sdk::native_token::sol_to_lamports;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::client::{read_keypair, Client};
+use crate::{Command, MarketKindArg};
+
+/// Offset of `launch_pool` in a BuyerRecord (after the discriminator).
+const BUYER_RECORD_LAUNCH_POOL_OFFSET: usize = 8;
+
+pub fn run(client: &Client, command: Command) -> Result<Value> {
+    let me = client.pubkey();
+    match command {
+        Command::Create {
+            name, symbol, supply, price, decimals, floor_after_graduation,
+            vesting_cliff, vesting_duration, creator_bps, creator_cliff, creator_duration, mint_keypair,
+        } => {
+            let mint_keypair = match mint_keypair {
+                Some(path) => read_keypair(&path.to_string_lossy())?,
+                None => Keypair::new(),
+            };
+            let mint = mint_keypair.pubkey();
+            let rent = client.chain.minimum_balance_for_rent_exemption(instructions::mint_space())?;
+
+            let mut ixs = instructions::create_mint(&me, &mint, decimals, rent);
+            ixs.push(instructions::create_launch(&me, &mint, sames::instruction::CreateLaunch {
+                token_name: name,
+                token_symbol: symbol,
+                total_supply: supply,
+                price_lamports: price,
+                floor_after_graduation,
+                presale_vesting: VestingSchedule { cliff_seconds: vesting_cliff, duration_seconds: vesting_duration },
+                creator_allocation_bps: creator_bps,
+                creator_vesting: VestingSchedule { cliff_seconds: creator_cliff, duration_seconds: creator_duration },
+            }));
+            ixs.push(instructions::initialize_extra_account_meta_list(&me, &mint));
+
+            let mut result = client.send(&ixs, &[&mint_keypair])?;
+            result["mint"] = json!(mint.to_string());
+            result["launch_pool"] = json!(pda::launch_pool(&mint).0.to_string());
+            Ok(result)
+        }
+
+        Command::BuyPresale { mint, sol } => {
+            client.send(&[instructions::buy_presale(&me, &mint, lamports(sol)?)], &[])
+        }
+
+        Command::Finalize { mint, buyers } => {
+            let launch_pool = pda::launch_pool(&mint).0;
+            let buyers = if buyers.is_empty() {
+                client.accounts_with::<BuyerRecord>(BUYER_RECORD_LAUNCH_POOL_OFFSET, &launch_pool)?
+                    .into_iter()
+                    .filter(|(_, record)| record.sol_deposited > 0 && record.tokens_allocated == 0)
+                    .map(|(_, record)| record.buyer)
+                    .collect()
+            } else {
+                buyers
+            };
+
+            let mut results = Vec::new();
+            for buyer in buyers {
+                let ixs = [
+                    instructions::create_token_account(&me, &buyer, &mint),
+                    instructions::finalize_launch(&me, &mint, &buyer),
+                ];
+                let mut result = client.send(&ixs, &[])
+                    .with_context(|| format!("finalizing {buyer}"))?;
+                result["buyer"] = json!(buyer.to_string());
+                results.push(result);
+            }
+            Ok(Value::Array(results))
+        }
+
+        Command::Claim { mint, creator } => {
+            let claim = if creator {
+                instructions::claim_creator_allocation(&me, &mint)
+            } else {
+                instructions::claim_vested(&me, &mint)
+            };
+            client.send(&[instructions::create_token_account(&me, &me, &mint), claim], &[])
+        }
+
+        Command::StartCurve { mint } => {
+            client.send(&[instructions::start_bonding_curve(&me, &mint)], &[])
+        }
+
+        Command::Buy { mint, sol } => {
+            let ixs = [
+                instructions::create_token_account(&me, &me, &mint),
+                instructions::buy_curve(&me, &mint, lamports(sol)?),
+            ];
+            client.send(&ixs, &[])
+        }
+
+        Command::Sell { mint, amount } => {
+            client.send(&[instructions::sell_curve(&me, &mint, amount)], &[])
+        }
+
+        Command::Graduate { mint } => client.send(&[instructions::graduate(&me, &mint)], &[]),
+
+        Command::RegisterMarket { mint, address, kind, token_reserve, sol_reserve } => {
+            let kind = match kind {
+                MarketKindArg::BondingCurveVault => MarketKind::BondingCurveVault,
+                MarketKindArg::Cpmm => MarketKind::Cpmm,
+                MarketKindArg::Orderbook => MarketKind::Orderbook,
+            };
+            let market = MarketEntry {
+                address,
+                kind,
+                token_reserve: token_reserve.unwrap_or_default(),
+                sol_reserve: sol_reserve.unwrap_or_default(),
+            };
+            if !market.is_valid() {
+                bail!("Cpmm markets need --token-reserve and --sol-reserve; other kinds take neither");
+            }
+            client.send(&[instructions::add_market(&me, &mint, market)], &[])
+        }
+
+        Command::ShowLaunch { mint } => {
+            let address = pda::launch_pool(&mint).0;
+            let pool: LaunchPool = client.account(&address)?;
+            Ok(launch_json(&address, &pool))
+        }
+
+        Command::ShowBuyer { mint, buyer } => {
+            let buyer = buyer.unwrap_or(me);
+            let address = pda::buyer_record(&pda::launch_pool(&mint).0, &buyer).0;
+            let record: BuyerRecord = client.account(&address)?;
+            Ok(buyer_json(&address, &record))
+        }
+
+        Command::Quote { mint, buy_sol, sell_amount, seller } => {
+            let launch_pool = pda::launch_pool(&mint).0;
+            let pool: LaunchPool = client.account(&launch_pool)?;
+            if pool.status != LaunchStatus::BondingCurve {
+                bail!("launch is in {:?}, not on the bonding curve", pool.status);
+            }
+            if let Some(sol) = buy_sol {
+                let quote = quote::buy(&pool, lamports(sol)?)
+                    .context("the curve can't fill this buy")?;
+                Ok(json!({
+                    "side": "buy",
+                    "tokens_out": quote.tokens_out,
+                    "sol_in": quote.sol_in,
+                    "fee": quote.fee,
+                    "price_before": quote.price_before,
+                    "price_after": quote.price_after,
+                    "price_impact_bps": quote.price_impact_bps,
+                }))
+            } else {
+                let amount = sell_amount.expect("clap requires --buy-sol or --sell-amount");
+                let seller = seller.unwrap_or(me);
+                // Without a record the seller bought on a market: no floor,
+                // and only unassigned curve inventory to sell against.
+                let record: Option<BuyerRecord> =
+                    client.optional_account(&pda::buyer_record(&launch_pool, &seller).0)?;
+                let entry_price = record.as_ref().map_or(0, |record| record.entry_price);
+                let inventory = record.as_ref().map_or(0, |record| record.curve_inventory());
+                let unassigned = record.as_ref().map_or(pool.curve_tokens_unassigned, |record| {
+                    record.unassigned_allowance().min(pool.curve_tokens_unassigned)
+                });
+                let sellable = inventory.saturating_add(unassigned);
+                if amount > sellable {
+                    bail!("{seller} can sell at most {sellable} tokens back to the curve");
+                }
+                let config: ProtocolConfig = client.account(&pda::protocol_config().0)?;
+                let quote = quote::sell(&pool, config.params.platform_fee_bps, amount, entry_price)
+                    .context("the curve can't absorb this sell")?;
+                Ok(json!({
+                    "side": "sell",
+                    "tokens_in": quote.tokens_in,
+                    "gross": quote.gross,
+                    "fee": quote.fee,
+                    "sol_out": quote.sol_out,
+                    "price_before": quote.price_before,
+                    "price_after": quote.price_after,
+                    "price_impact_bps": quote.price_impact_bps,
+                    "entry_price": entry_price_json(entry_price),
+                    "blocked_by_floor": quote.blocked_by_floor,
+                }))
+            }
+        }
+
+        Command::ReleaseInventory { mint } => {
+            let launch_pool = pda::launch_pool(&mint).0;
+            let mut results = Vec::new();
+            for (_, record) in client.accounts_with::<BuyerRecord>(BUYER_RECORD_LAUNCH_POOL_OFFSET, &launch_pool)? {
+                let released = record.unreleased_inventory();
+                if released == 0 {
+                    continue;
+                }
+                let mut result = client.send(&[instructions::release_curve_inventory(&record.buyer, &mint)], &[])
+                    .with_context(|| format!("releasing {}", record.buyer))?;
+                result["buyer"] = json!(record.buyer.to_string());
+                result["released"] = json!(released);
+                results.push(result);
+            }
+            Ok(Value::Array(results))
+        }
+
+        Command::MigrateRecords => {
+            let mut results = Vec::new();
+            for record in client.addresses_sized::<BuyerRecord>(LegacyBuyerRecord::SIZE, None)? {
+                let mut result = client.send(&[instructions::migrate_buyer_record(&me, &record)], &[])
+                    .with_context(|| format!("migrating {record}"))?;
+                result["buyer_record"] = json!(record.to_string());
+                results.push(result);
+            }
+            // A registry's extra rent is paid by its authority, the
+            // launch creator, so only the signer's own are migrated.
+            let mine = Some((8 + 32, &me));
+            for registry in client.addresses_sized::<MarketRegistry>(LegacyMarketRegistry::SIZE, mine)? {
+                let mut result = client.send(&[instructions::migrate_market_registry(&me, &registry)], &[])
+                    .with_context(|| format!("migrating {registry}"))?;
+                result["market_registry"] = json!(registry.to_string());
+                results.push(result);
+            }
+            Ok(Value::Array(results))
+        }
+    }
+}
+
+fn lamports(sol: f64) -> Result<u64> {
+    if !sol.is_finite() || sol <= 0.0 {
+        bail!("SOL amount must be positive");
+    }
+    Ok(sol_to_lamports(sol))
+}
+
+/// Fixed-point entry price as decimal lamports per base unit.
+fn entry_price_json(price: u128) -> Value {
+    let digits = ENTRY_PRICE_SCALE.ilog10() as usize;
+    json!(format!("{}.{:0digits$}", price / ENTRY_PRICE_SCALE, price % ENTRY_PRICE_SCALE))
+}
+
+fn vesting_json(schedule: &VestingSchedule) -> Value {
+    json!({ "cliff_seconds": schedule.cliff_seconds, "duration_seconds": schedule.duration_seconds })
+}
+
+fn launch_json(address: &Pubkey, pool: &LaunchPool) -> Value {
+    json!({
+        "address": address.to_string(),
+        "mint": pool.mint.to_string(),
+        "creator": pool.creator.to_string(),
+        "name": pool.token_name,
+        "symbol": pool.token_symbol,
+        "status": format!("{:?}", pool.status),
+        "total_supply": pool.total_supply,
+        "price_lamports": pool.price_lamports,
+        "slope_scaled": pool.slope_scaled,
+        "spot_price": quote::spot_price(pool),
+        "start_time": pool.start_time,
+        "end_time": pool.end_time,
+        "total_sol_collected": pool.total_sol_collected,
+        "buyer_count": pool.buyer_count,
+        "tokens_sold_curve": pool.tokens_sold_curve,
+        "curve_sol_collected": pool.curve_sol_collected,
+        "curve_tokens_unassigned": pool.curve_tokens_unassigned,
+        "graduation_threshold": pool.graduation_threshold,
+        "floor_after_graduation": pool.floor_after_graduation,
+        "presale_vesting": vesting_json(&pool.presale_vesting),
+        "creator_allocation": pool.creator_allocation(),
+        "creator_vesting": vesting_json(&pool.creator_vesting),
+        "creator_tokens_claimed": pool.creator_tokens_claimed,
+        "paused": pool.paused,
+    })
+}
+
+fn buyer_json(address: &Pubkey, record: &BuyerRecord) -> Value {
+    json!({
+        "address": address.to_string(),
+        "launch_pool": record.launch_pool.to_string(),
+        "buyer": record.buyer.to_string(),
+        "sol_deposited": record.sol_deposited,
+        "entry_price": entry_price_json(record.entry_price),
+        "tokens_allocated": record.tokens_allocated,
+        "tokens_vesting": record.tokens_vesting,
+        "curve_sol_spent": record.curve_sol_spent,
+        "curve_tokens_bought": record.curve_tokens_bought,
+        "tokens_sold": record.tokens_sold,
+        "tokens_received": record.tokens_received,
+        "tokens_sent": record.tokens_sent,
+        "curve_tokens_received": record.curve_tokens_received,
+        "holdings": record.holdings(),
+        "liquid_holdings": record.liquid_holdings(),
+        "curve_inventory": record.curve_inventory(),
+    })
+}
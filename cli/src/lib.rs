@@ -0,0 +1,167 @@
+//! Operate SAMES launches from the command line: argument parsing here,
+//! the commands in [`commands`], the cluster behind [`client::Chain`].
+
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use solana_sdk::pubkey::Pubkey;
+
+pub mod client;
+pub mod commands;
+pub mod output;
+
+#[derive(Parser)]
+#[command(name = "sames-cli", about = "Operate SAMES launches from the command line")]
+pub struct Cli {
+    /// JSON-RPC endpoint.
+    #[arg(long, short = 'u', global = true, env = "SAMES_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    pub url: String,
+    /// Keypair that signs and pays for transactions.
+    #[arg(long, short = 'k', global = true, env = "SAMES_KEYPAIR", default_value = "~/.config/solana/id.json")]
+    pub keypair: String,
+    /// Print results as JSON.
+    #[arg(long, global = true)]
+    pub json: bool,
+    /// Simulate transactions instead of sending them.
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Create a mint and its launch, and register the transfer hook.
+    Create {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        symbol: String,
+        /// Total supply in base units.
+        #[arg(long)]
+        supply: u64,
+        /// Presale price in lamports per base unit.
+        #[arg(long)]
+        price: u64,
+        #[arg(long, default_value_t = 6)]
+        decimals: u8,
+        /// Keep the price floor after graduation.
+        #[arg(long)]
+        floor_after_graduation: bool,
+        #[arg(long, default_value_t = 0)]
+        vesting_cliff: u32,
+        #[arg(long, default_value_t = 0)]
+        vesting_duration: u32,
+        /// Creator allocation in basis points of supply.
+        #[arg(long, default_value_t = 0)]
+        creator_bps: u16,
+        #[arg(long, default_value_t = 0)]
+        creator_cliff: u32,
+        #[arg(long, default_value_t = 0)]
+        creator_duration: u32,
+        /// Mint keypair file; a fresh keypair is generated if omitted.
+        #[arg(long)]
+        mint_keypair: Option<PathBuf>,
+    },
+    /// Deposit SOL into a presale.
+    BuyPresale {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        sol: f64,
+    },
+    /// Allocate presale tokens (creator). Defaults to every unallocated buyer.
+    Finalize {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long = "buyer")]
+        buyers: Vec<Pubkey>,
+    },
+    /// Claim vested presale tokens, or the creator allocation with --creator.
+    Claim {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        creator: bool,
+    },
+    /// Open the bonding curve (creator).
+    StartCurve {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Buy on the bonding curve.
+    Buy {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        sol: f64,
+    },
+    /// Sell back to the bonding curve.
+    Sell {
+        #[arg(long)]
+        mint: Pubkey,
+        /// Amount in base units.
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Graduate a launch that reached its threshold.
+    Graduate {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Add a market to the launch's registry (registry authority).
+    RegisterMarket {
+        #[arg(long)]
+        mint: Pubkey,
+        /// Token account that receives tokens sold into the market.
+        #[arg(long)]
+        address: Pubkey,
+        #[arg(long, value_enum)]
+        kind: MarketKindArg,
+        #[arg(long)]
+        token_reserve: Option<Pubkey>,
+        #[arg(long)]
+        sol_reserve: Option<Pubkey>,
+    },
+    /// Show a launch.
+    ShowLaunch {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Show a buyer's record; defaults to the signer.
+    ShowBuyer {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        buyer: Option<Pubkey>,
+    },
+    /// Quote a curve buy (--buy-sol) or sell (--sell-amount).
+    Quote {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long, conflicts_with = "sell_amount", required_unless_present = "sell_amount")]
+        buy_sol: Option<f64>,
+        #[arg(long)]
+        sell_amount: Option<u64>,
+        /// Seller whose entry-price floor applies; defaults to the signer.
+        #[arg(long, requires = "sell_amount")]
+        seller: Option<Pubkey>,
+    },
+    /// Rewrite every BuyerRecord still storing whole-lamport entry prices,
+    /// and every MarketRegistry of the signer's launches still storing bare
+    /// market addresses.
+    MigrateRecords,
+    /// Release the curve inventory transfers carried out of the launch's
+    /// BuyerRecords, so its new holders can sell it back.
+    ReleaseInventory {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum MarketKindArg {
+    BondingCurveVault,
+    Cpmm,
+    Orderbook,
+}
@@ -0,0 +1,12 @@
+use anyhow::Result;
+use clap::Parser;
+use sames_cli::client::Client;
+use sames_cli::{commands, output, Cli};
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let client = Client::new(&cli.url, &cli.keypair, cli.dry_run)?;
+    let result = commands::run(&client, cli.command)?;
+    output::print(&result, cli.json);
+    Ok(())
+}
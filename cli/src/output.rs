@@ -0,0 +1,46 @@
+use serde_json::Value;
+
+/// Print a command result, either as JSON or as indented `key: value` lines.
+pub fn print(value: &Value, json: bool) {
+    if json {
+        println!("{}", serde_json::to_string_pretty(value).expect("JSON values serialize"));
+    } else {
+        print_human(value, 0);
+    }
+}
+
+fn print_human(value: &Value, indent: usize) {
+    let pad = "  ".repeat(indent);
+    match value {
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                match value {
+                    Value::Object(_) | Value::Array(_) => {
+                        println!("{pad}{key}:");
+                        print_human(value, indent + 1);
+                    }
+                    _ => println!("{pad}{key}: {}", scalar(value)),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                match item {
+                    Value::Object(_) | Value::Array(_) => {
+                        println!("{pad}-");
+                        print_human(item, indent + 1);
+                    }
+                    _ => println!("{pad}- {}", scalar(item)),
+                }
+            }
+        }
+        _ => println!("{pad}{}", scalar(value)),
+    }
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
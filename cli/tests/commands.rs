@@ -0,0 +1,187 @@
+//! The commands, parsed from command lines, run against the bank the program
+//! tests use: a presale, its allocation, curve trades and the records they
+//! leave.
+
+#[path = "../../programs/sames/tests/svm/mod.rs"]
+mod svm;
+
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::rc::Rc;
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use sames_cli::client::{Chain, Client};
+use sames_cli::{commands, Cli};
+use serde_json::{json, Value};
+use solana_client::rpc_config::RpcProgramAccountsConfig;
+use solana_client::rpc_response::RpcSimulateTransactionResult;
+use solana_sdk::account::{Account, AccountSharedData};
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+use svm::launch::*;
+
+/// The program tests' environment as the CLI's cluster.
+///
+/// A bank can't list accounts by owner, so program accounts are looked up
+/// among every address a transaction sent through it has touched.
+#[derive(Clone)]
+struct Bank(Rc<BankState>);
+
+struct BankState {
+    env: Env,
+    touched: RefCell<BTreeSet<Pubkey>>,
+}
+
+impl Bank {
+    fn new() -> Self {
+        Self(Rc::new(BankState { env: Env::new(), touched: RefCell::default() }))
+    }
+
+    fn env(&self) -> &Env {
+        &self.0.env
+    }
+
+    /// A funded wallet the CLI signs with.
+    fn wallet(&self) -> Keypair {
+        let wallet = Keypair::new();
+        self.env().svm.airdrop(&wallet.pubkey(), 100 * LAMPORTS_PER_SOL);
+        wallet
+    }
+
+    /// Parse `args` as typed after `sames-cli` and run them as `wallet`.
+    fn run(&self, wallet: &Keypair, args: &[&str]) -> Result<Value> {
+        let cli = Cli::try_parse_from(std::iter::once("sames-cli").chain(args.iter().copied()))?;
+        let client = Client::with_chain(Box::new(self.clone()), wallet.insecure_clone(), cli.dry_run);
+        commands::run(&client, cli.command)
+    }
+}
+
+impl Chain for Bank {
+    fn latest_blockhash(&self) -> Result<Hash> {
+        Ok(self.env().svm.latest_blockhash())
+    }
+
+    fn send_and_confirm(&self, tx: &Transaction) -> Result<Signature> {
+        self.0.touched.borrow_mut().extend(tx.message.account_keys.iter().copied());
+        self.env().svm.process_transaction(tx.clone()).map_err(|err| anyhow!("transaction failed: {err:?}"))?;
+        Ok(tx.signatures[0])
+    }
+
+    fn simulate(&self, tx: &Transaction) -> Result<RpcSimulateTransactionResult> {
+        let simulation = self.env().svm.simulate_transaction(tx.clone());
+        Ok(RpcSimulateTransactionResult {
+            err: simulation.result.err(),
+            logs: Some(simulation.logs),
+            accounts: None,
+            units_consumed: Some(simulation.units_consumed),
+            return_data: None,
+            inner_instructions: None,
+        })
+    }
+
+    fn account(&self, address: &Pubkey) -> Result<Option<Account>> {
+        Ok(self.env().svm.account(address))
+    }
+
+    fn minimum_balance_for_rent_exemption(&self, size: usize) -> Result<u64> {
+        Ok(Rent::default().minimum_balance(size))
+    }
+
+    fn program_accounts(&self, config: RpcProgramAccountsConfig) -> Result<Vec<(Pubkey, Account)>> {
+        let filters = config.filters.unwrap_or_default();
+        Ok(self
+            .0
+            .touched
+            .borrow()
+            .iter()
+            .filter_map(|address| Some((*address, self.env().svm.account(address)?)))
+            .filter(|(_, account)| account.owner == sames::ID)
+            .filter(|(_, account)| {
+                let shared = AccountSharedData::from(account.clone());
+                filters.iter().all(|filter| filter.allows(&shared))
+            })
+            .collect())
+    }
+}
+
+#[test]
+fn presale_to_curve_trades() {
+    let bank = Bank::new();
+    let (creator, alice, bob) = (bank.wallet(), bank.wallet(), bank.wallet());
+    let run = |wallet: &Keypair, args: &[&str]| bank.run(wallet, args).unwrap();
+
+    let (supply, price) = (SUPPLY.to_string(), PRICE.to_string());
+    let create = ["create", "--name", "Same", "--symbol", "SAME", "--supply", &supply, "--price", &price];
+    let created = run(&creator, &create);
+    let mint = created["mint"].as_str().unwrap().to_string();
+    let mint = mint.as_str();
+    let launch = run(&creator, &["show-launch", "--mint", mint]);
+    assert_eq!(launch["address"], created["launch_pool"]);
+    assert_eq!(launch["status"], "Presale");
+    assert_eq!(launch["creator"], json!(creator.pubkey().to_string()));
+
+    // A dry run simulates the deposit without making it.
+    let simulated = run(&alice, &["--dry-run", "buy-presale", "--mint", mint, "--sol", "1"]);
+    assert_eq!(simulated["simulated"], true);
+    assert_eq!(simulated["error"], Value::Null);
+    assert!(bank.run(&alice, &["show-buyer", "--mint", mint]).is_err());
+
+    run(&alice, &["buy-presale", "--mint", mint, "--sol", "1"]);
+    assert_eq!(run(&alice, &["show-buyer", "--mint", mint])["sol_deposited"], LAMPORTS_PER_SOL);
+
+    // Without --buyer, finalize finds every unallocated buyer itself.
+    bank.env().svm.warp(PRESALE_SECONDS);
+    let finalized = run(&creator, &["finalize", "--mint", mint]);
+    assert_eq!(finalized.as_array().unwrap().len(), 1);
+    assert_eq!(finalized[0]["buyer"], json!(alice.pubkey().to_string()));
+    let presale_tokens = run(&alice, &["show-buyer", "--mint", mint])["tokens_allocated"].as_u64().unwrap();
+    assert!(presale_tokens > 0);
+
+    run(&creator, &["start-curve", "--mint", mint]);
+    assert_eq!(run(&creator, &["show-launch", "--mint", mint])["status"], "BondingCurve");
+    run(&alice, &["buy", "--mint", mint, "--sol", "0.5"]);
+    let bought = run(&alice, &["show-buyer", "--mint", mint])["curve_tokens_bought"].as_u64().unwrap();
+    assert!(bought > 0);
+
+    // Sells are floor-checked on proceeds after the fee: Bob's buy lifts the
+    // price far enough for Alice to sell above her entry.
+    run(&bob, &["buy", "--mint", mint, "--sol", "1"]);
+    let amount = (bought / 2).to_string();
+    let quote = run(&alice, &["quote", "--mint", mint, "--sell-amount", &amount]);
+    assert_eq!(quote["blocked_by_floor"], false);
+    run(&alice, &["sell", "--mint", mint, "--amount", &amount]);
+
+    let record = run(&bob, &["show-buyer", "--mint", mint, "--buyer", &alice.pubkey().to_string()]);
+    assert_eq!(record["buyer"], json!(alice.pubkey().to_string()));
+    assert_eq!(record["sol_deposited"], LAMPORTS_PER_SOL);
+    assert_eq!(record["tokens_allocated"], presale_tokens);
+    assert_eq!(record["curve_tokens_bought"], bought);
+    assert_eq!(record["tokens_sold"], bought / 2);
+    assert_eq!(record["holdings"], presale_tokens + bought - bought / 2);
+    let token_account = sames_sdk::pda::token_account(&alice.pubkey(), &mint.parse().unwrap());
+    assert_eq!(bank.env().svm.token_balance(&token_account), presale_tokens + bought - bought / 2);
+}
+
+#[test]
+fn arguments_are_checked_before_anything_is_sent() {
+    let bank = Bank::new();
+    let wallet = bank.wallet();
+    let mint = Pubkey::new_unique().to_string();
+    let mint = mint.as_str();
+
+    // A quote takes exactly one side, and --seller only applies to sells.
+    assert!(bank.run(&wallet, &["quote", "--mint", mint]).is_err());
+    assert!(bank.run(&wallet, &["quote", "--mint", mint, "--buy-sol", "1", "--sell-amount", "1"]).is_err());
+    assert!(bank.run(&wallet, &["quote", "--mint", mint, "--buy-sol", "1", "--seller", mint]).is_err());
+    assert!(bank.run(&wallet, &["buy", "--mint", "not-a-key", "--sol", "1"]).is_err());
+
+    let error = bank.run(&wallet, &["buy-presale", "--mint", mint, "--sol", "0"]).unwrap_err();
+    assert_eq!(error.to_string(), "SOL amount must be positive");
+    let error = bank.run(&wallet, &["register-market", "--mint", mint, "--address", mint, "--kind", "cpmm"])
+        .unwrap_err();
+    assert!(error.to_string().starts_with("Cpmm markets need"));
+}
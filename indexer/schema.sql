@@ -0,0 +1,105 @@
+-- Tables written by sames-indexer. Everything here is derived from on-chain
+-- program events and token balances, so it can be dropped and rebuilt by
+-- replaying the program's transactions. u64 amounts are NUMERIC(20, 0).
+
+CREATE TABLE IF NOT EXISTS launches (
+    launch_pool    TEXT PRIMARY KEY,
+    mint           TEXT NOT NULL UNIQUE,
+    creator        TEXT NOT NULL,
+    total_supply   NUMERIC(20, 0) NOT NULL,
+    price_lamports NUMERIC(20, 0) NOT NULL,
+    created_slot   BIGINT NOT NULL,
+    created_at     TIMESTAMPTZ,
+    graduated_slot BIGINT,
+    cancelled_slot BIGINT,
+    refunds_until  TIMESTAMPTZ,                -- unclaimed refunds are swept after this
+    closed_slot    BIGINT,
+    paused         INTEGER NOT NULL DEFAULT 0  -- pause bits of this launch alone
+);
+
+CREATE TABLE IF NOT EXISTS chain_trades (
+    signature      TEXT NOT NULL,
+    event_index    INTEGER NOT NULL,
+    slot           BIGINT NOT NULL,
+    launch_pool    TEXT NOT NULL,
+    wallet         TEXT NOT NULL,
+    trade_type     TEXT NOT NULL,            -- presale | buy | sell
+    sol_amount     NUMERIC(20, 0) NOT NULL,
+    token_amount   NUMERIC(20, 0) NOT NULL,
+    fee            NUMERIC(20, 0) NOT NULL,
+    price_lamports NUMERIC(20, 0) NOT NULL,  -- spot price after the trade
+    tokens_sold    NUMERIC(20, 0),
+    sol_collected  NUMERIC(20, 0) NOT NULL,
+    created_at     TIMESTAMPTZ,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS chain_trades_by_launch ON chain_trades (launch_pool, slot);
+
+CREATE TABLE IF NOT EXISTS price_candles (
+    launch_pool     TEXT NOT NULL,
+    interval_secs   INTEGER NOT NULL,
+    bucket_start    TIMESTAMPTZ NOT NULL,
+    open            NUMERIC(20, 0) NOT NULL,
+    high            NUMERIC(20, 0) NOT NULL,
+    low             NUMERIC(20, 0) NOT NULL,
+    close           NUMERIC(20, 0) NOT NULL,
+    volume_lamports NUMERIC(30, 0) NOT NULL,
+    trades          INTEGER NOT NULL,
+    first_slot      BIGINT NOT NULL,
+    last_slot       BIGINT NOT NULL,
+    PRIMARY KEY (launch_pool, interval_secs, bucket_start)
+);
+
+CREATE TABLE IF NOT EXISTS holder_balances (
+    token_account TEXT PRIMARY KEY,
+    mint          TEXT NOT NULL,
+    owner         TEXT,
+    amount        NUMERIC(20, 0) NOT NULL,
+    slot          BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS holder_balances_by_mint ON holder_balances (mint, amount DESC);
+
+CREATE TABLE IF NOT EXISTS blocked_transfers (
+    signature      TEXT NOT NULL,
+    event_index    INTEGER NOT NULL,
+    slot           BIGINT NOT NULL,
+    launch_pool    TEXT NOT NULL,
+    owner          TEXT NOT NULL,
+    destination    TEXT NOT NULL,
+    reason         TEXT NOT NULL,            -- locked | paused | below_entry | unpriceable | no_receiver_record
+    amount         NUMERIC(20, 0) NOT NULL,
+    entry_price    NUMERIC(39, 0) NOT NULL,
+    realized_price NUMERIC(39, 0) NOT NULL,
+    created_at     TIMESTAMPTZ,
+    PRIMARY KEY (signature, event_index)
+);
+
+-- The protocol singleton, as its admin and guardian events left it.
+CREATE TABLE IF NOT EXISTS protocol_config (
+    id                           BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    admin                        TEXT NOT NULL,
+    pending_admin                TEXT,
+    guardian                     TEXT NOT NULL,
+    paused                       INTEGER NOT NULL DEFAULT 0,
+    platform_fee_bps             INTEGER NOT NULL,
+    presale_duration             BIGINT NOT NULL,
+    default_slope                NUMERIC(20, 0) NOT NULL,
+    default_graduation_threshold NUMERIC(20, 0) NOT NULL,
+    max_creator_allocation_bps   INTEGER NOT NULL,
+    updated_slot                 BIGINT NOT NULL
+);
+
+-- Pool authorities the transfer hook treats as markets for every launch.
+CREATE TABLE IF NOT EXISTS dex_programs (
+    program_id TEXT NOT NULL,
+    authority  TEXT NOT NULL,
+    added_slot BIGINT NOT NULL,
+    PRIMARY KEY (program_id, authority)
+);
+
+-- Newest signature processed by `sames-indexer follow`.
+CREATE TABLE IF NOT EXISTS indexer_cursor (
+    name      TEXT PRIMARY KEY,
+    signature TEXT NOT NULL,
+    slot      BIGINT NOT NULL
+);
@@ -0,0 +1,7 @@
+/// Candle widths maintained for every launch, in seconds.
+pub const INTERVALS: [i64; 4] = [60, 300, 3_600, 86_400];
+
+/// Start of the `interval`-wide bucket containing `timestamp`.
+pub fn bucket_start(timestamp: i64, interval: i64) -> i64 {
+    timestamp - timestamp.rem_euclid(interval)
+}
@@ -0,0 +1,118 @@
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use sames::events::*;
+
+use crate::tx::Transaction;
+
+/// Every event the `sames` program emits.
+pub enum SamesEvent {
+    LaunchCreated(LaunchCreated),
+    PresaleBuy(PresaleBuy),
+    AllocationClaimed(AllocationClaimed),
+    CurveTrade(CurveTrade),
+    Graduated(Graduated),
+    PriceUpdated(PriceUpdated),
+    MarketRegistered(MarketRegistered),
+    MarketRemoved(MarketRemoved),
+    LaunchCancelled(LaunchCancelled),
+    RefundClaimed(RefundClaimed),
+    HookBlocked(HookBlocked),
+    LaunchClosed(LaunchClosed),
+    BuyerRecordClosed(BuyerRecordClosed),
+    BuyerRecordCreated(BuyerRecordCreated),
+    BuyerRecordMigrated(BuyerRecordMigrated),
+    MarketRegistryMigrated(MarketRegistryMigrated),
+    CurveInventoryReleased(CurveInventoryReleased),
+    CostBasisCarried(CostBasisCarried),
+    ProtocolConfigInitialized(ProtocolConfigInitialized),
+    ProtocolParamsUpdated(ProtocolParamsUpdated),
+    ProtocolBoundsUpdated(ProtocolBoundsUpdated),
+    AdminProposed(AdminProposed),
+    AdminChanged(AdminChanged),
+    GuardianSet(GuardianSet),
+    DexProgramAdded(DexProgramAdded),
+    DexProgramRemoved(DexProgramRemoved),
+    PauseSet(PauseSet),
+}
+
+/// Decode `discriminator || borsh(event)`. Unknown discriminators and
+/// malformed bodies yield `None`.
+pub fn decode_event(data: &[u8]) -> Option<SamesEvent> {
+    if data.len() < 8 {
+        return None;
+    }
+    let (discriminator, mut body) = data.split_at(8);
+
+    macro_rules! try_decode {
+        ($($event:ident),*) => {
+            $(
+                if discriminator == $event::DISCRIMINATOR {
+                    return $event::deserialize(&mut body).ok().map(SamesEvent::$event);
+                }
+            )*
+        };
+    }
+    try_decode!(
+        LaunchCreated, PresaleBuy, AllocationClaimed, CurveTrade, Graduated, PriceUpdated,
+        MarketRegistered, MarketRemoved, LaunchCancelled, RefundClaimed, HookBlocked, LaunchClosed,
+        BuyerRecordClosed, BuyerRecordCreated, BuyerRecordMigrated, MarketRegistryMigrated,
+        CurveInventoryReleased, CostBasisCarried, ProtocolConfigInitialized, ProtocolParamsUpdated,
+        ProtocolBoundsUpdated, AdminProposed, AdminChanged, GuardianSet, DexProgramAdded, DexProgramRemoved,
+        PauseSet
+    );
+    None
+}
+
+/// Events of a successful transaction, from the `emit_cpi!` self-invocations.
+///
+/// Only the program can sign for its event authority, so a tagged inner
+/// instruction to the program in a transaction that succeeded is genuine.
+pub fn cpi_events(tx: &Transaction) -> Vec<SamesEvent> {
+    if tx.failed {
+        return Vec::new();
+    }
+    tx.inner_instructions
+        .iter()
+        .filter(|(program_id, _)| *program_id == sames::ID)
+        .filter_map(|(_, data)| data.strip_prefix(EVENT_IX_TAG_LE.as_slice()))
+        .filter_map(decode_event)
+        .collect()
+}
+
+/// Events logged with `emit!` — `HookBlocked`, which is written just before
+/// the transfer fails.
+///
+/// `Program data:` lines are only trusted while the program is the one
+/// executing, so other programs in the transaction can't forge them.
+pub fn log_events(tx: &Transaction) -> Vec<SamesEvent> {
+    let program_id = sames::ID.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in &tx.logs {
+        let Some(rest) = line.strip_prefix("Program ") else { continue };
+        if let Some(data) = rest.strip_prefix("data: ") {
+            if stack.last() != Some(&program_id.as_str()) {
+                continue;
+            }
+            let Ok(bytes) = BASE64.decode(data.trim()) else { continue };
+            if let Some(event @ SamesEvent::HookBlocked(_)) = decode_event(&bytes) {
+                events.push(event);
+            }
+            continue;
+        }
+
+        let mut words = rest.split(' ');
+        let (Some(id), Some(action)) = (words.next(), words.next()) else { continue };
+        match action {
+            "invoke" => stack.push(id),
+            "success" | "failed:" => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+    events
+}
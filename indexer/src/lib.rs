@@ -0,0 +1,8 @@
+//! Indexer for the SAMES program: decodes program events from transactions
+//! and writes trades, price candles and holder balances to Postgres.
+
+pub mod candles;
+pub mod decode;
+pub mod rpc;
+pub mod store;
+pub mod tx;
@@ -0,0 +1,99 @@
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Parser, Subcommand};
+use sames_indexer::rpc::RpcClient;
+use sames_indexer::store::Store;
+use sames_indexer::tx::{RpcBlock, Transaction};
+use serde_json::Value;
+
+const CURSOR: &str = "follow";
+
+#[derive(Parser)]
+#[command(about = "Index SAMES program events into Postgres")]
+struct Cli {
+    #[arg(long, env = "DATABASE_URL")]
+    database_url: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Poll a validator for finalized transactions that touch the program.
+    Follow {
+        #[arg(long, env = "RPC_URL", default_value = "http://127.0.0.1:8899")]
+        rpc: String,
+        #[arg(long, default_value_t = 2)]
+        poll_secs: u64,
+    },
+    /// Replay `getBlock` responses (JSON encoding) saved as `<slot>.json`.
+    Replay {
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut store = Store::connect(&cli.database_url)?;
+    store.migrate()?;
+
+    match cli.command {
+        Command::Follow { rpc, poll_secs } => follow(&mut store, &RpcClient::new(rpc), poll_secs),
+        Command::Replay { files } => replay(&mut store, files),
+    }
+}
+
+fn follow(store: &mut Store, rpc: &RpcClient, poll_secs: u64) -> Result<()> {
+    let program_id = sames::ID.to_string();
+    loop {
+        let cursor = store.cursor(CURSOR)?;
+        for info in rpc.signatures_since(&program_id, cursor.as_deref())? {
+            let tx = rpc.transaction(&info.signature)?
+                .ok_or_else(|| anyhow!("finalized transaction {} not found", info.signature))?;
+            store.apply(&Transaction::from_rpc(tx)?)?;
+            store.set_cursor(CURSOR, &info.signature, info.slot)?;
+        }
+        thread::sleep(Duration::from_secs(poll_secs));
+    }
+}
+
+fn replay(store: &mut Store, files: Vec<PathBuf>) -> Result<()> {
+    let mut blocks = files.into_iter()
+        .map(|path| Ok((block_slot(&path)?, path)))
+        .collect::<Result<Vec<_>>>()?;
+    blocks.sort();
+
+    for (slot, path) in blocks {
+        let mut json: Value = serde_json::from_slice(&std::fs::read(&path)?)
+            .with_context(|| format!("parsing {}", path.display()))?;
+        // Accept both the bare result and the full JSON-RPC response.
+        if let Some(result) = json.get_mut("result") {
+            json = result.take();
+        }
+        let block: RpcBlock = serde_json::from_value(json)
+            .with_context(|| format!("parsing {}", path.display()))?;
+
+        let mut indexed = 0;
+        for tx in block.transactions {
+            let tx = Transaction::from_block(slot, block.block_time, tx)?;
+            if !tx.account_keys.contains(&sames::ID) {
+                continue;
+            }
+            store.apply(&tx)?;
+            indexed += 1;
+        }
+        println!("slot {slot}: {indexed} transaction(s)");
+    }
+    Ok(())
+}
+
+fn block_slot(path: &Path) -> Result<u64> {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| stem.parse().ok())
+        .ok_or_else(|| anyhow!("{} isn't named <slot>.json", path.display()))
+}
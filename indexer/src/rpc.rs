@@ -0,0 +1,79 @@
+use anyhow::{anyhow, bail, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::tx::RpcTransaction;
+
+/// `getSignaturesForAddress` page size (the RPC maximum).
+const SIGNATURE_PAGE: usize = 1_000;
+
+/// Minimal blocking JSON-RPC client for the two calls the indexer needs.
+/// Everything is read at `finalized`, so indexed history is never rolled back.
+pub struct RpcClient {
+    url: String,
+    agent: ureq::Agent,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureInfo {
+    pub signature: String,
+    pub slot: u64,
+}
+
+impl RpcClient {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into(), agent: ureq::Agent::new() }
+    }
+
+    fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let mut response: Value = self.agent.post(&self.url)
+            .send_json(request)
+            .with_context(|| format!("{method} request failed"))?
+            .into_json()?;
+        if let Some(error) = response.get("error") {
+            bail!("{method} failed: {error}");
+        }
+        let result = response.get_mut("result").map(Value::take)
+            .ok_or_else(|| anyhow!("{method} returned neither result nor error"))?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// Signatures touching `address` newer than `until`, oldest first.
+    pub fn signatures_since(&self, address: &str, until: Option<&str>) -> Result<Vec<SignatureInfo>> {
+        let mut signatures = Vec::new();
+        let mut before: Option<String> = None;
+        loop {
+            let page: Vec<SignatureInfo> = self.call(
+                "getSignaturesForAddress",
+                json!([address, {
+                    "limit": SIGNATURE_PAGE,
+                    "before": before,
+                    "until": until,
+                    "commitment": "finalized",
+                }]),
+            )?;
+            let full = page.len() == SIGNATURE_PAGE;
+            before = page.last().map(|info| info.signature.clone());
+            signatures.extend(page);
+            if !full {
+                break;
+            }
+        }
+        signatures.reverse();
+        Ok(signatures)
+    }
+
+    pub fn transaction(&self, signature: &str) -> Result<Option<RpcTransaction>> {
+        self.call(
+            "getTransaction",
+            json!([signature, {
+                "encoding": "json",
+                "commitment": "finalized",
+                "maxSupportedTransactionVersion": 0,
+            }]),
+        )
+    }
+}
@@ -0,0 +1,375 @@
+use anyhow::{Context, Result};
+use postgres::{Client, NoTls, Transaction as DbTransaction};
+use anchor_lang::prelude::Pubkey;
+use postgres::types::ToSql;
+use sames::events::{BlockReason, CurveTrade, HookBlocked, LaunchCreated, PresaleBuy, ProtocolConfigInitialized};
+
+use crate::candles::{bucket_start, INTERVALS};
+use crate::decode::{cpi_events, log_events, SamesEvent};
+use crate::tx::Transaction;
+
+const SCHEMA: &str = include_str!("../schema.sql");
+
+/// Postgres sink for decoded transactions.
+///
+/// Applying a transaction is idempotent: trades and blocked transfers are
+/// keyed by (signature, event index), candles only move when a trade row is
+/// new, and balances only move forward in slot order.
+pub struct Store {
+    client: Client,
+}
+
+impl Store {
+    pub fn connect(database_url: &str) -> Result<Self> {
+        let client = Client::connect(database_url, NoTls)
+            .context("connecting to Postgres")?;
+        Ok(Self { client })
+    }
+
+    pub fn migrate(&mut self) -> Result<()> {
+        self.client.batch_execute(SCHEMA).context("applying schema")
+    }
+
+    pub fn cursor(&mut self, name: &str) -> Result<Option<String>> {
+        let row = self.client.query_opt("SELECT signature FROM indexer_cursor WHERE name = $1", &[&name])?;
+        Ok(row.map(|row| row.get(0)))
+    }
+
+    pub fn set_cursor(&mut self, name: &str, signature: &str, slot: u64) -> Result<()> {
+        self.client.execute(
+            "INSERT INTO indexer_cursor (name, signature, slot) VALUES ($1, $2, $3)
+             ON CONFLICT (name) DO UPDATE SET signature = EXCLUDED.signature, slot = EXCLUDED.slot",
+            &[&name, &signature, &(slot as i64)],
+        )?;
+        Ok(())
+    }
+
+    /// Record the events and launch-token balances of `tx`.
+    pub fn apply(&mut self, tx: &Transaction) -> Result<()> {
+        let mut events = cpi_events(tx);
+        events.extend(log_events(tx));
+
+        let mut db = self.client.transaction()?;
+        for (index, event) in events.iter().enumerate() {
+            apply_event(&mut db, tx, index as i32, event)
+                .with_context(|| format!("event {index} of {}", tx.signature))?;
+        }
+        if !tx.failed {
+            record_balances(&mut db, tx)?;
+        }
+        db.commit()?;
+        Ok(())
+    }
+}
+
+fn apply_event(db: &mut DbTransaction, tx: &Transaction, index: i32, event: &SamesEvent) -> Result<()> {
+    match event {
+        SamesEvent::LaunchCreated(event) => record_launch(db, tx, event),
+        SamesEvent::PresaleBuy(event) => record_presale_buy(db, tx, index, event),
+        SamesEvent::CurveTrade(event) => record_curve_trade(db, tx, index, event),
+        SamesEvent::PriceUpdated(event) => {
+            db.execute(
+                "UPDATE launches SET price_lamports = $2::TEXT::NUMERIC WHERE launch_pool = $1",
+                &[&event.launch_pool.to_string(), &event.new_price.to_string()],
+            )?;
+            Ok(())
+        }
+        SamesEvent::Graduated(event) => {
+            db.execute(
+                "UPDATE launches SET graduated_slot = $2 WHERE launch_pool = $1",
+                &[&event.launch_pool.to_string(), &(tx.slot as i64)],
+            )?;
+            Ok(())
+        }
+        SamesEvent::LaunchCancelled(event) => {
+            db.execute(
+                "UPDATE launches SET cancelled_slot = $2, refunds_until = to_timestamp($3::BIGINT)
+                 WHERE launch_pool = $1",
+                &[&event.launch_pool.to_string(), &(tx.slot as i64), &event.refund_deadline],
+            )?;
+            Ok(())
+        }
+        SamesEvent::LaunchClosed(event) => {
+            db.execute(
+                "UPDATE launches SET closed_slot = $2 WHERE launch_pool = $1",
+                &[&event.launch_pool.to_string(), &(tx.slot as i64)],
+            )?;
+            Ok(())
+        }
+        SamesEvent::HookBlocked(event) => record_blocked(db, tx, index, event),
+        SamesEvent::ProtocolConfigInitialized(event) => record_config(db, tx, event),
+        SamesEvent::ProtocolParamsUpdated(event) => {
+            let params = &event.params;
+            db.execute(
+                "UPDATE protocol_config SET platform_fee_bps = $2, presale_duration = $3,
+                        default_slope = $4::TEXT::NUMERIC, default_graduation_threshold = $5::TEXT::NUMERIC,
+                        max_creator_allocation_bps = $6, updated_slot = $1",
+                &[
+                    &(tx.slot as i64),
+                    &(params.platform_fee_bps as i32),
+                    &params.presale_duration,
+                    &params.default_slope.to_string(),
+                    &params.default_graduation_threshold.to_string(),
+                    &(params.max_creator_allocation_bps as i32),
+                ],
+            )?;
+            Ok(())
+        }
+        SamesEvent::AdminProposed(event) => {
+            let pending = (event.pending_admin != Pubkey::default()).then(|| event.pending_admin.to_string());
+            update_config(db, tx, "pending_admin = $2", &pending)
+        }
+        SamesEvent::AdminChanged(event) => {
+            update_config(db, tx, "admin = $2, pending_admin = NULL", &event.admin.to_string())
+        }
+        SamesEvent::GuardianSet(event) => update_config(db, tx, "guardian = $2", &event.guardian.to_string()),
+        SamesEvent::PauseSet(event) => match event.launch_pool {
+            Some(launch_pool) => {
+                db.execute(
+                    "UPDATE launches SET paused = $2 WHERE launch_pool = $1",
+                    &[&launch_pool.to_string(), &(event.flags as i32)],
+                )?;
+                Ok(())
+            }
+            None => update_config(db, tx, "paused = $2", &(event.flags as i32)),
+        },
+        SamesEvent::DexProgramAdded(event) => {
+            db.execute(
+                "INSERT INTO dex_programs (program_id, authority, added_slot) VALUES ($1, $2, $3)
+                 ON CONFLICT (program_id, authority) DO NOTHING",
+                &[&event.program_id.to_string(), &event.authority.to_string(), &(tx.slot as i64)],
+            )?;
+            Ok(())
+        }
+        SamesEvent::DexProgramRemoved(event) => {
+            db.execute("DELETE FROM dex_programs WHERE program_id = $1", &[&event.program_id.to_string()])?;
+            Ok(())
+        }
+        // Nothing in the history tables depends on these yet.
+        SamesEvent::AllocationClaimed(_)
+        | SamesEvent::MarketRegistered(_)
+        | SamesEvent::MarketRemoved(_)
+        | SamesEvent::RefundClaimed(_)
+        | SamesEvent::BuyerRecordClosed(_)
+        | SamesEvent::BuyerRecordCreated(_)
+        | SamesEvent::BuyerRecordMigrated(_)
+        | SamesEvent::MarketRegistryMigrated(_)
+        | SamesEvent::CurveInventoryReleased(_)
+        | SamesEvent::CostBasisCarried(_)
+        | SamesEvent::ProtocolBoundsUpdated(_) => Ok(()),
+    }
+}
+
+/// Set one column group of the protocol_config row; `$2` is `value`.
+fn update_config(
+    db: &mut DbTransaction,
+    tx: &Transaction,
+    set: &str,
+    value: &(dyn ToSql + Sync),
+) -> Result<()> {
+    db.execute(
+        &format!("UPDATE protocol_config SET {set}, updated_slot = $1"),
+        &[&(tx.slot as i64), value],
+    )?;
+    Ok(())
+}
+
+/// The admin starts out as guardian too.
+fn record_config(db: &mut DbTransaction, tx: &Transaction, event: &ProtocolConfigInitialized) -> Result<()> {
+    let params = &event.params;
+    db.execute(
+        "INSERT INTO protocol_config (admin, guardian, platform_fee_bps, presale_duration, default_slope,
+                                      default_graduation_threshold, max_creator_allocation_bps, updated_slot)
+         VALUES ($1, $1, $2, $3, $4::TEXT::NUMERIC, $5::TEXT::NUMERIC, $6, $7)
+         ON CONFLICT (id) DO NOTHING",
+        &[
+            &event.admin.to_string(),
+            &(params.platform_fee_bps as i32),
+            &params.presale_duration,
+            &params.default_slope.to_string(),
+            &params.default_graduation_threshold.to_string(),
+            &(params.max_creator_allocation_bps as i32),
+            &(tx.slot as i64),
+        ],
+    )?;
+    Ok(())
+}
+
+fn record_launch(db: &mut DbTransaction, tx: &Transaction, event: &LaunchCreated) -> Result<()> {
+    db.execute(
+        "INSERT INTO launches (launch_pool, mint, creator, total_supply, price_lamports, created_slot, created_at)
+         VALUES ($1, $2, $3, $4::TEXT::NUMERIC, $5::TEXT::NUMERIC, $6, to_timestamp($7::BIGINT))
+         ON CONFLICT (launch_pool) DO NOTHING",
+        &[
+            &event.launch_pool.to_string(),
+            &event.mint.to_string(),
+            &event.creator.to_string(),
+            &event.total_supply.to_string(),
+            &event.price_lamports.to_string(),
+            &(tx.slot as i64),
+            &tx.block_time,
+        ],
+    )?;
+    Ok(())
+}
+
+fn record_presale_buy(db: &mut DbTransaction, tx: &Transaction, index: i32, event: &PresaleBuy) -> Result<()> {
+    // Presale buys fill at the launch price; tokens are allocated at finalize.
+    let price = db
+        .query_opt(
+            "SELECT price_lamports::TEXT FROM launches WHERE launch_pool = $1",
+            &[&event.launch_pool.to_string()],
+        )?
+        .map(|row| row.get::<_, String>(0).parse::<u64>())
+        .transpose()?
+        .unwrap_or(0);
+    let trade = Trade {
+        launch_pool: event.launch_pool.to_string(),
+        wallet: event.buyer.to_string(),
+        trade_type: "presale",
+        sol_amount: event.sol_amount,
+        token_amount: 0,
+        fee: 0,
+        price,
+        tokens_sold: None,
+        sol_collected: event.total_sol_collected,
+    };
+    record_trade(db, tx, index, &trade)
+}
+
+fn record_curve_trade(db: &mut DbTransaction, tx: &Transaction, index: i32, event: &CurveTrade) -> Result<()> {
+    let trade = Trade {
+        launch_pool: event.launch_pool.to_string(),
+        wallet: event.trader.to_string(),
+        trade_type: if event.is_buy { "buy" } else { "sell" },
+        sol_amount: event.sol_amount,
+        token_amount: event.token_amount,
+        fee: event.fee,
+        price: event.price_after,
+        tokens_sold: Some(event.tokens_sold_curve),
+        sol_collected: event.curve_sol_collected,
+    };
+    record_trade(db, tx, index, &trade)
+}
+
+struct Trade {
+    launch_pool: String,
+    wallet: String,
+    trade_type: &'static str,
+    sol_amount: u64,
+    token_amount: u64,
+    fee: u64,
+    price: u64,
+    tokens_sold: Option<u64>,
+    sol_collected: u64,
+}
+
+fn record_trade(db: &mut DbTransaction, tx: &Transaction, index: i32, trade: &Trade) -> Result<()> {
+    let inserted = db.execute(
+        "INSERT INTO chain_trades (signature, event_index, slot, launch_pool, wallet, trade_type,
+                                   sol_amount, token_amount, fee, price_lamports, tokens_sold, sol_collected, created_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7::TEXT::NUMERIC, $8::TEXT::NUMERIC, $9::TEXT::NUMERIC,
+                 $10::TEXT::NUMERIC, $11::TEXT::NUMERIC, $12::TEXT::NUMERIC, to_timestamp($13::BIGINT))
+         ON CONFLICT (signature, event_index) DO NOTHING",
+        &[
+            &tx.signature,
+            &index,
+            &(tx.slot as i64),
+            &trade.launch_pool,
+            &trade.wallet,
+            &trade.trade_type,
+            &trade.sol_amount.to_string(),
+            &trade.token_amount.to_string(),
+            &trade.fee.to_string(),
+            &trade.price.to_string(),
+            &trade.tokens_sold.map(|sold| sold.to_string()),
+            &trade.sol_collected.to_string(),
+            &tx.block_time,
+        ],
+    )?;
+
+    // Replayed trades must not count twice towards volume.
+    let Some(block_time) = tx.block_time.filter(|_| inserted == 1) else {
+        return Ok(());
+    };
+    let volume = trade.sol_amount as u128 + trade.fee as u128;
+    for interval in INTERVALS {
+        db.execute(
+            "INSERT INTO price_candles AS c (launch_pool, interval_secs, bucket_start, open, high, low, close,
+                                             volume_lamports, trades, first_slot, last_slot)
+             VALUES ($1, $2, to_timestamp($3::BIGINT), $4::TEXT::NUMERIC, $4::TEXT::NUMERIC, $4::TEXT::NUMERIC,
+                     $4::TEXT::NUMERIC, $5::TEXT::NUMERIC, 1, $6, $6)
+             ON CONFLICT (launch_pool, interval_secs, bucket_start) DO UPDATE SET
+                 open = CASE WHEN EXCLUDED.first_slot < c.first_slot THEN EXCLUDED.open ELSE c.open END,
+                 high = GREATEST(c.high, EXCLUDED.high),
+                 low = LEAST(c.low, EXCLUDED.low),
+                 close = CASE WHEN EXCLUDED.last_slot >= c.last_slot THEN EXCLUDED.close ELSE c.close END,
+                 volume_lamports = c.volume_lamports + EXCLUDED.volume_lamports,
+                 trades = c.trades + 1,
+                 first_slot = LEAST(c.first_slot, EXCLUDED.first_slot),
+                 last_slot = GREATEST(c.last_slot, EXCLUDED.last_slot)",
+            &[
+                &trade.launch_pool,
+                &(interval as i32),
+                &bucket_start(block_time, interval),
+                &trade.price.to_string(),
+                &volume.to_string(),
+                &(tx.slot as i64),
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+fn record_blocked(db: &mut DbTransaction, tx: &Transaction, index: i32, event: &HookBlocked) -> Result<()> {
+    let reason = match event.reason {
+        BlockReason::Locked => "locked",
+        BlockReason::Paused => "paused",
+        BlockReason::BelowEntry => "below_entry",
+        BlockReason::Unpriceable => "unpriceable",
+        BlockReason::NoReceiverRecord => "no_receiver_record",
+    };
+    db.execute(
+        "INSERT INTO blocked_transfers (signature, event_index, slot, launch_pool, owner, destination, reason,
+                                        amount, entry_price, realized_price, created_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8::TEXT::NUMERIC, $9::TEXT::NUMERIC, $10::TEXT::NUMERIC,
+                 to_timestamp($11::BIGINT))
+         ON CONFLICT (signature, event_index) DO NOTHING",
+        &[
+            &tx.signature,
+            &index,
+            &(tx.slot as i64),
+            &event.launch_pool.to_string(),
+            &event.owner.to_string(),
+            &event.destination.to_string(),
+            &reason,
+            &event.amount.to_string(),
+            &event.entry_price.to_string(),
+            &event.realized_price.to_string(),
+            &tx.block_time,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Upsert the post-transaction balance of every account holding a launch token.
+fn record_balances(db: &mut DbTransaction, tx: &Transaction) -> Result<()> {
+    for balance in &tx.token_balances {
+        db.execute(
+            "INSERT INTO holder_balances (token_account, mint, owner, amount, slot)
+             SELECT $1, $2, $3, $4::TEXT::NUMERIC, $5
+             WHERE EXISTS (SELECT 1 FROM launches WHERE mint = $2)
+             ON CONFLICT (token_account) DO UPDATE SET
+                 owner = EXCLUDED.owner, amount = EXCLUDED.amount, slot = EXCLUDED.slot
+             WHERE holder_balances.slot <= EXCLUDED.slot",
+            &[
+                &balance.account.to_string(),
+                &balance.mint.to_string(),
+                &balance.owner.map(|owner| owner.to_string()),
+                &balance.amount.to_string(),
+                &(tx.slot as i64),
+            ],
+        )?;
+    }
+    Ok(())
+}
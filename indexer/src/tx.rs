@@ -0,0 +1,192 @@
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Wire format — the `"encoding": "json"` shape returned by `getTransaction`
+// and `getBlock`. Only the fields the indexer reads are declared.
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransaction {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    #[serde(flatten)]
+    pub inner: RpcBlockTransaction,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBlock {
+    pub block_time: Option<i64>,
+    #[serde(default)]
+    pub transactions: Vec<RpcBlockTransaction>,
+}
+
+#[derive(Deserialize)]
+pub struct RpcBlockTransaction {
+    pub transaction: UiTransaction,
+    pub meta: Option<UiMeta>,
+}
+
+#[derive(Deserialize)]
+pub struct UiTransaction {
+    pub signatures: Vec<String>,
+    pub message: UiMessage,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiMessage {
+    pub account_keys: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiInstruction {
+    pub program_id_index: u8,
+    pub data: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiMeta {
+    pub err: Option<Value>,
+    #[serde(default)]
+    pub inner_instructions: Option<Vec<UiInnerInstructions>>,
+    #[serde(default)]
+    pub log_messages: Option<Vec<String>>,
+    #[serde(default)]
+    pub pre_token_balances: Option<Vec<UiTokenBalance>>,
+    #[serde(default)]
+    pub post_token_balances: Option<Vec<UiTokenBalance>>,
+    #[serde(default)]
+    pub loaded_addresses: Option<UiLoadedAddresses>,
+}
+
+#[derive(Deserialize)]
+pub struct UiInnerInstructions {
+    pub index: u8,
+    pub instructions: Vec<UiInstruction>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiTokenBalance {
+    pub account_index: u8,
+    pub mint: String,
+    pub owner: Option<String>,
+    pub ui_token_amount: UiTokenAmount,
+}
+
+#[derive(Deserialize)]
+pub struct UiTokenAmount {
+    pub amount: String,
+}
+
+#[derive(Deserialize, Default)]
+pub struct UiLoadedAddresses {
+    #[serde(default)]
+    pub writable: Vec<String>,
+    #[serde(default)]
+    pub readonly: Vec<String>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Decoded transaction — keys resolved, instruction data un-base58'd.
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub struct Transaction {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub failed: bool,
+    /// Static keys followed by lookup-table keys.
+    pub account_keys: Vec<Pubkey>,
+    /// Inner instructions in execution order, as (program id, data).
+    pub inner_instructions: Vec<(Pubkey, Vec<u8>)>,
+    pub logs: Vec<String>,
+    pub token_balances: Vec<TokenBalance>,
+}
+
+/// A token account's balance after the transaction (0 if it was closed).
+pub struct TokenBalance {
+    pub account: Pubkey,
+    pub mint: Pubkey,
+    pub owner: Option<Pubkey>,
+    pub amount: u64,
+}
+
+impl Transaction {
+    pub fn from_rpc(tx: RpcTransaction) -> Result<Self> {
+        Self::from_block(tx.slot, tx.block_time, tx.inner)
+    }
+
+    pub fn from_block(slot: u64, block_time: Option<i64>, tx: RpcBlockTransaction) -> Result<Self> {
+        let signature = tx.transaction.signatures.first()
+            .cloned()
+            .ok_or_else(|| anyhow!("transaction without a signature in slot {slot}"))?;
+        let meta = tx.meta.ok_or_else(|| anyhow!("transaction {signature} has no meta"))?;
+
+        // v0 messages append lookup-table addresses after the static keys.
+        let loaded = meta.loaded_addresses.unwrap_or_default();
+        let account_keys = tx.transaction.message.account_keys.iter()
+            .chain(&loaded.writable)
+            .chain(&loaded.readonly)
+            .map(|key| parse_pubkey(key))
+            .collect::<Result<Vec<_>>>()?;
+        let key_at = |index: u8| {
+            account_keys.get(index as usize).copied()
+                .ok_or_else(|| anyhow!("account index {index} out of range in {signature}"))
+        };
+
+        let mut inner_instructions = Vec::new();
+        for group in meta.inner_instructions.unwrap_or_default() {
+            for ix in group.instructions {
+                let data = bs58::decode(&ix.data).into_vec()
+                    .with_context(|| format!("bad instruction data in {signature}"))?;
+                inner_instructions.push((key_at(ix.program_id_index)?, data));
+            }
+        }
+
+        let decode_balance = |balance: UiTokenBalance| -> Result<TokenBalance> {
+            Ok(TokenBalance {
+                account: key_at(balance.account_index)?,
+                mint: parse_pubkey(&balance.mint)?,
+                owner: balance.owner.as_deref().map(parse_pubkey).transpose()?,
+                amount: balance.ui_token_amount.amount.parse()
+                    .with_context(|| format!("bad token amount in {signature}"))?,
+            })
+        };
+        let mut token_balances = meta.post_token_balances.unwrap_or_default().into_iter()
+            .map(decode_balance)
+            .collect::<Result<Vec<_>>>()?;
+        // Accounts closed by the transaction only show up in the pre-balances.
+        for balance in meta.pre_token_balances.unwrap_or_default() {
+            let mut balance = decode_balance(balance)?;
+            if token_balances.iter().all(|post| post.account != balance.account) {
+                balance.amount = 0;
+                token_balances.push(balance);
+            }
+        }
+
+        Ok(Self {
+            signature,
+            slot,
+            block_time,
+            failed: meta.err.is_some(),
+            account_keys,
+            inner_instructions,
+            logs: meta.log_messages.unwrap_or_default(),
+            token_balances,
+        })
+    }
+}
+
+fn parse_pubkey(key: &str) -> Result<Pubkey> {
+    Pubkey::from_str(key).map_err(|_| anyhow!("invalid pubkey {key}"))
+}
@@ -0,0 +1,182 @@
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Event;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use sames::events::{
+    BlockReason, BuyerRecordCreated, CostBasisCarried, CurveInventoryReleased, CurveTrade, HookBlocked,
+    MarketRegistryMigrated, PauseSet, ProtocolBoundsUpdated, ProtocolParamsUpdated,
+};
+use sames_indexer::candles::bucket_start;
+use sames::state::{ProtocolBounds, ProtocolParams};
+use sames_indexer::decode::{cpi_events, decode_event, log_events, SamesEvent};
+use sames_indexer::tx::{RpcTransaction, Transaction};
+use serde_json::json;
+
+fn curve_trade() -> CurveTrade {
+    CurveTrade {
+        launch_pool: Pubkey::new_unique(),
+        trader: Pubkey::new_unique(),
+        is_buy: false,
+        token_amount: 1_000,
+        sol_amount: 50_000,
+        fee: 500,
+        price_after: 49,
+        tokens_sold_curve: 9_000,
+        curve_sol_collected: 450_000,
+        entry_price: 40,
+    }
+}
+
+fn hook_blocked() -> HookBlocked {
+    HookBlocked {
+        launch_pool: Pubkey::new_unique(),
+        owner: Pubkey::new_unique(),
+        destination: Pubkey::new_unique(),
+        reason: BlockReason::BelowEntry,
+        amount: 10,
+        entry_price: 100,
+        realized_price: 90,
+    }
+}
+
+/// A `getTransaction` response with one inner instruction to `program`.
+fn transaction(program: Pubkey, inner_data: &[u8], err: bool, logs: Vec<String>) -> Transaction {
+    let payer = Pubkey::new_unique();
+    let response = json!({
+        "slot": 42,
+        "blockTime": 1_700_000_000,
+        "transaction": {
+            "signatures": ["sig"],
+            "message": { "accountKeys": [payer.to_string(), program.to_string()] },
+        },
+        "meta": {
+            "err": if err { json!({ "InstructionError": [0, { "Custom": 6000 }] }) } else { json!(null) },
+            "innerInstructions": [{
+                "index": 0,
+                "instructions": [{
+                    "programIdIndex": 1,
+                    "accounts": [],
+                    "data": bs58::encode(inner_data).into_string(),
+                }],
+            }],
+            "logMessages": logs,
+        },
+    });
+    let tx: RpcTransaction = serde_json::from_value(response).unwrap();
+    Transaction::from_rpc(tx).unwrap()
+}
+
+fn event_cpi_data(event: &impl Event) -> Vec<u8> {
+    [EVENT_IX_TAG_LE.as_slice(), &event.data()].concat()
+}
+
+#[test]
+fn decodes_cpi_event() {
+    let trade = curve_trade();
+    let tx = transaction(sames::ID, &event_cpi_data(&trade), false, vec![]);
+
+    let events = cpi_events(&tx);
+    assert_eq!(events.len(), 1);
+    let SamesEvent::CurveTrade(decoded) = &events[0] else { panic!("expected CurveTrade") };
+    assert_eq!(decoded.launch_pool, trade.launch_pool);
+    assert_eq!(decoded.trader, trade.trader);
+    assert!(!decoded.is_buy);
+    assert_eq!(decoded.sol_amount, 50_000);
+    assert_eq!(decoded.price_after, 49);
+}
+
+#[test]
+fn decodes_admin_events() {
+    let launch_pool = Pubkey::new_unique();
+    let paused = PauseSet { launch_pool: Some(launch_pool), flags: 0b11 };
+    let tx = transaction(sames::ID, &event_cpi_data(&paused), false, vec![]);
+    let [SamesEvent::PauseSet(decoded)] = &cpi_events(&tx)[..] else { panic!("expected PauseSet") };
+    assert_eq!((decoded.launch_pool, decoded.flags), (Some(launch_pool), 0b11));
+
+    let params = ProtocolParams::default();
+    let Some(SamesEvent::ProtocolParamsUpdated(decoded)) = decode_event(&ProtocolParamsUpdated { params }.data())
+    else {
+        panic!("expected ProtocolParamsUpdated")
+    };
+    assert_eq!(decoded.params, params);
+
+    let bounds = ProtocolBounds::LIMITS;
+    let Some(SamesEvent::ProtocolBoundsUpdated(decoded)) = decode_event(&ProtocolBoundsUpdated { bounds }.data())
+    else {
+        panic!("expected ProtocolBoundsUpdated")
+    };
+    assert_eq!(decoded.bounds, bounds);
+}
+
+#[test]
+fn decodes_record_and_migration_events() {
+    let (launch_pool, buyer, receiver) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let created = BuyerRecordCreated { launch_pool, buyer, rent_payer: receiver };
+    let released = CurveInventoryReleased { launch_pool, buyer, released: 700, curve_tokens_unassigned: 900 };
+    let carried = CostBasisCarried {
+        launch_pool,
+        sender: buyer,
+        receiver,
+        amount: 700,
+        sender_entry_price: 40,
+        receiver_entry_price: 25,
+    };
+    let migrated = MarketRegistryMigrated { launch_pool, markets: vec![Pubkey::new_unique()] };
+
+    let Some(SamesEvent::BuyerRecordCreated(decoded)) = decode_event(&created.data()) else {
+        panic!("expected BuyerRecordCreated")
+    };
+    assert_eq!((decoded.buyer, decoded.rent_payer), (buyer, receiver));
+    let Some(SamesEvent::CurveInventoryReleased(decoded)) = decode_event(&released.data()) else {
+        panic!("expected CurveInventoryReleased")
+    };
+    assert_eq!((decoded.released, decoded.curve_tokens_unassigned), (700, 900));
+    let Some(SamesEvent::CostBasisCarried(decoded)) = decode_event(&carried.data()) else {
+        panic!("expected CostBasisCarried")
+    };
+    assert_eq!((decoded.receiver, decoded.receiver_entry_price), (receiver, 25));
+    let Some(SamesEvent::MarketRegistryMigrated(decoded)) = decode_event(&migrated.data()) else {
+        panic!("expected MarketRegistryMigrated")
+    };
+    assert_eq!(decoded.markets, migrated.markets);
+}
+
+#[test]
+fn ignores_events_from_failed_or_foreign_instructions() {
+    let data = event_cpi_data(&curve_trade());
+    assert!(cpi_events(&transaction(sames::ID, &data, true, vec![])).is_empty());
+    assert!(cpi_events(&transaction(Pubkey::new_unique(), &data, false, vec![])).is_empty());
+    // Untagged instruction data is a regular instruction, not an event.
+    assert!(cpi_events(&transaction(sames::ID, &curve_trade().data(), false, vec![])).is_empty());
+}
+
+#[test]
+fn decodes_hook_blocked_from_program_logs_only() {
+    let blocked = hook_blocked();
+    let data = format!("Program data: {}", BASE64.encode(blocked.data()));
+    let other = Pubkey::new_unique();
+    let logs = vec![
+        format!("Program {other} invoke [1]"),
+        data.clone(),
+        format!("Program {} invoke [2]", sames::ID),
+        data,
+        format!("Program {} failed: custom program error: 0x1772", sames::ID),
+        format!("Program {other} failed: custom program error: 0x1772"),
+    ];
+    let tx = transaction(sames::ID, &[], true, logs);
+
+    let events = log_events(&tx);
+    assert_eq!(events.len(), 1, "only the line logged by the program counts");
+    let SamesEvent::HookBlocked(decoded) = &events[0] else { panic!("expected HookBlocked") };
+    assert_eq!(decoded.owner, blocked.owner);
+    assert_eq!(decoded.reason, BlockReason::BelowEntry);
+    assert_eq!(decoded.realized_price, 90);
+}
+
+#[test]
+fn buckets_align_to_interval() {
+    assert_eq!(bucket_start(1_700_000_123, 60), 1_700_000_100);
+    assert_eq!(bucket_start(1_700_000_100, 60), 1_700_000_100);
+    assert_eq!(bucket_start(-1, 60), -60);
+}
@@ -0,0 +1,15 @@
+//! Random sequences of buys, sells, claims, transfers and graduations
+//! across several wallets, checking the launch's accounting after every
+//! step. See `tests/svm/sequence.rs` for the actions and invariants.
+//!
+//!     cargo fuzz run sequences
+
+#![no_main]
+
+#[path = "../../tests/svm/mod.rs"]
+mod svm;
+
+use libfuzzer_sys::fuzz_target;
+use svm::sequence::{run, Sequence};
+
+fuzz_target!(|sequence: Sequence| run(&sequence));
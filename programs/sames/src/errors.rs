@@ -10,8 +10,6 @@ pub enum SamesError {
     PresaleStillActive,
     #[msg("Launch has already been finalized")]
     AlreadyFinalized,
-    #[msg("Launch has not been finalized yet")]
-    NotFinalized,
     #[msg("Deposit amount must be greater than zero")]
     ZeroDeposit,
     #[msg("Arithmetic overflow")]
@@ -22,8 +20,6 @@ pub enum SamesError {
     ZeroSellAmount,
     #[msg("Sell price is below entry price — blocked by price floor")]
     SellBelowEntry,
-    #[msg("No buyer record found")]
-    NoBuyerRecord,
     #[msg("Transfer hook: sell price below entry")]
     HookSellBelowEntry,
     #[msg("Transfer hook: price derivation failed")]
@@ -46,4 +42,64 @@ pub enum SamesError {
     NotBondingCurve,
     #[msg("Graduation threshold not reached yet")]
     NotReadyToGraduate,
+    #[msg("Transfer hook invoked outside of a token transfer")]
+    NotTransferring,
+    #[msg("BuyerRecord is malformed or does not belong to this launch and wallet")]
+    InvalidBuyerRecord,
+    #[msg("Tokens are not transferable during the presale")]
+    TransfersLocked,
+    #[msg("No vested tokens are waiting to be claimed")]
+    TokensStillVesting,
+    #[msg("Invalid vesting schedule: cliff must not exceed duration")]
+    InvalidVestingSchedule,
+    #[msg("Creator allocation exceeds the limit or has no lock-up")]
+    InvalidCreatorAllocation,
+    #[msg("Market is already registered")]
+    MarketAlreadyRegistered,
+    #[msg("Market is not registered")]
+    MarketNotFound,
+    #[msg("Market registry is full")]
+    MarketRegistryFull,
+    #[msg("Unauthorized: not the protocol admin")]
+    UnauthorizedAdmin,
+    #[msg("DEX program can't be allowlisted or the authority isn't one of its PDAs")]
+    InvalidDexProgram,
+    #[msg("DEX allowlist is full")]
+    DexAllowlistFull,
+    #[msg("Protocol parameter outside its allowed range")]
+    InvalidProtocolParams,
+    #[msg("Unauthorized: not the guardian")]
+    UnauthorizedGuardian,
+    #[msg("This action is paused")]
+    Paused,
+    #[msg("Unknown pause flags")]
+    InvalidPauseFlags,
+    #[msg("Launch is closed")]
+    LaunchClosed,
+    #[msg("Launch can only be cancelled during the presale or before any curve trade")]
+    NotCancellable,
+    #[msg("Launch is not closed")]
+    NotClosed,
+    #[msg("Nothing to refund")]
+    NothingToRefund,
+    #[msg("Presale deposits are still waiting to be refunded")]
+    RefundsOutstanding,
+    #[msg("A required token account was not provided")]
+    MissingTokenAccount,
+    #[msg("BuyerRecord is still needed and can't be closed")]
+    BuyerRecordInUse,
+    #[msg("Only tokens bought on the curve can be sold back to it")]
+    ExceedsCurveInventory,
+    #[msg("BuyerRecord is already in the current layout")]
+    BuyerRecordMigrated,
+    #[msg("Receiver needs a BuyerRecord before tokens with a price floor can be sent to it")]
+    ReceiverRecordMissing,
+    #[msg("MarketRegistry is malformed or does not belong to its launch")]
+    InvalidMarketRegistry,
+    #[msg("MarketRegistry is already in the current layout")]
+    MarketRegistryMigrated,
+    #[msg("Escrow still holds tokens")]
+    EscrowNotEmpty,
+    #[msg("Protocol bounds exceed the hard limits or exclude the current parameters")]
+    InvalidProtocolBounds,
 }
@@ -0,0 +1,270 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{MarketKind, ProtocolBounds, ProtocolParams, VestingSchedule};
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Events — emitted through `emit_cpi!` so indexers can read them from the
+// instruction data of the transaction rather than from truncatable logs.
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[event]
+pub struct LaunchCreated {
+    pub launch_pool: Pubkey,
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub total_supply: u64,
+    pub price_lamports: u64,
+    pub slope_scaled: u64,
+    pub graduation_threshold: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub floor_after_graduation: bool,
+    pub presale_vesting: VestingSchedule,
+    pub creator_allocation: u64,
+    pub creator_vesting: VestingSchedule,
+}
+
+#[event]
+pub struct PresaleBuy {
+    pub launch_pool: Pubkey,
+    pub buyer: Pubkey,
+    pub sol_amount: u64,
+    /// Buyer's deposits so far, this one included.
+    pub sol_deposited: u64,
+    pub total_sol_collected: u64,
+}
+
+/// Tokens leaving the protocol for a wallet or the vesting escrow.
+#[event]
+pub struct AllocationClaimed {
+    pub launch_pool: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    /// Minted into the vesting escrow rather than to the recipient.
+    pub to_escrow: bool,
+    /// The creator allocation rather than a presale allocation.
+    pub creator: bool,
+    /// Still locked in escrow for this recipient after this claim.
+    pub still_vesting: u64,
+}
+
+#[event]
+pub struct CurveTrade {
+    pub launch_pool: Pubkey,
+    pub trader: Pubkey,
+    pub is_buy: bool,
+    pub token_amount: u64,
+    /// Lamports paid by the buyer, or received by the seller after fees.
+    pub sol_amount: u64,
+    pub fee: u64,
+    /// Curve spot price after the trade.
+    pub price_after: u64,
+    pub tokens_sold_curve: u64,
+    pub curve_sol_collected: u64,
+    /// Trader's entry price after the trade (fixed point, see `ENTRY_PRICE_SCALE`).
+    pub entry_price: u128,
+}
+
+/// Curve inventory that transfers carried out of `buyer`'s wallet, handed
+/// to the launch's unassigned inventory.
+#[event]
+pub struct CurveInventoryReleased {
+    pub launch_pool: Pubkey,
+    pub buyer: Pubkey,
+    pub released: u64,
+    pub curve_tokens_unassigned: u64,
+}
+
+#[event]
+pub struct Graduated {
+    pub launch_pool: Pubkey,
+    pub curve_sol_collected: u64,
+    pub tokens_sold_curve: u64,
+}
+
+#[event]
+pub struct PriceUpdated {
+    pub launch_pool: Pubkey,
+    pub old_price: u64,
+    pub new_price: u64,
+}
+
+/// A market added to a launch's registry, or an entry replaced in place.
+#[event]
+pub struct MarketRegistered {
+    pub launch_pool: Pubkey,
+    pub address: Pubkey,
+    pub kind: MarketKind,
+    pub token_reserve: Pubkey,
+    pub sol_reserve: Pubkey,
+    /// The entry this one replaced, if any.
+    pub replaced: Option<Pubkey>,
+}
+
+#[event]
+pub struct MarketRemoved {
+    pub launch_pool: Pubkey,
+    pub address: Pubkey,
+}
+
+#[event]
+pub struct LaunchCancelled {
+    pub launch_pool: Pubkey,
+    pub refundable_lamports: u64,
+    /// Refunds not claimed by then can be swept by `close_launch`.
+    pub refund_deadline: i64,
+}
+
+#[event]
+pub struct RefundClaimed {
+    pub launch_pool: Pubkey,
+    pub buyer: Pubkey,
+    pub sol_amount: u64,
+    pub tokens_burned: u64,
+}
+
+/// Once a Closed launch's accounts are all closed, or a Graduated launch
+/// has shed what the hook doesn't need.
+#[event]
+pub struct LaunchClosed {
+    pub launch_pool: Pubkey,
+    pub graduated: bool,
+    /// Left in the vault and swept to the creator (always 0 once graduated).
+    pub swept_lamports: u64,
+}
+
+#[event]
+pub struct BuyerRecordClosed {
+    pub launch_pool: Pubkey,
+    pub buyer: Pubkey,
+    /// Gets `rent_refunded` back; any rent beyond that goes to the buyer.
+    pub rent_payer: Pubkey,
+    pub rent_refunded: u64,
+}
+
+/// A record opened ahead of a transfer by `create_buyer_record`.
+#[event]
+pub struct BuyerRecordCreated {
+    pub launch_pool: Pubkey,
+    pub buyer: Pubkey,
+    pub rent_payer: Pubkey,
+}
+
+/// A legacy record rewritten in the current layout.
+#[event]
+pub struct BuyerRecordMigrated {
+    pub launch_pool: Pubkey,
+    pub buyer: Pubkey,
+    /// Fixed point (see `ENTRY_PRICE_SCALE`).
+    pub entry_price: u128,
+}
+
+/// A legacy registry rewritten in the current layout; its markets carry
+/// over as orderbooks.
+#[event]
+pub struct MarketRegistryMigrated {
+    pub launch_pool: Pubkey,
+    pub markets: Vec<Pubkey>,
+}
+
+/// The protocol singleton was created; `admin` is also its guardian.
+#[event]
+pub struct ProtocolConfigInitialized {
+    pub admin: Pubkey,
+    pub params: ProtocolParams,
+}
+
+#[event]
+pub struct ProtocolParamsUpdated {
+    pub params: ProtocolParams,
+}
+
+#[event]
+pub struct ProtocolBoundsUpdated {
+    pub bounds: ProtocolBounds,
+}
+
+/// A handover to `pending_admin` was proposed, or cancelled if it's
+/// `Pubkey::default()`.
+#[event]
+pub struct AdminProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminChanged {
+    pub previous: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct GuardianSet {
+    pub previous: Pubkey,
+    pub guardian: Pubkey,
+}
+
+#[event]
+pub struct DexProgramAdded {
+    pub program_id: Pubkey,
+    pub authority: Pubkey,
+}
+
+/// Every allowlist entry of `program_id` was dropped.
+#[event]
+pub struct DexProgramRemoved {
+    pub program_id: Pubkey,
+}
+
+/// A pause mask was replaced: the protocol-wide one, or that of
+/// `launch_pool`.
+#[event]
+pub struct PauseSet {
+    pub launch_pool: Option<Pubkey>,
+    pub flags: u16,
+}
+
+/// A wallet-to-wallet transfer carried the sender's entry price over to
+/// the receiver's record.
+#[event]
+pub struct CostBasisCarried {
+    pub launch_pool: Pubkey,
+    pub sender: Pubkey,
+    pub receiver: Pubkey,
+    pub amount: u64,
+    /// Fixed point (see `ENTRY_PRICE_SCALE`); 0 if the sender has no floor.
+    pub sender_entry_price: u128,
+    /// Receiver's entry price after the merge.
+    pub receiver_entry_price: u128,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlockReason {
+    /// Presale tokens can't move yet.
+    Locked,
+    /// Transfers are paused by the guardian.
+    Paused,
+    /// The sell's realized price is below the sender's entry price.
+    BelowEntry,
+    /// The destination market couldn't be priced.
+    Unpriceable,
+    /// The receiving wallet has no BuyerRecord to carry the entry price.
+    NoReceiverRecord,
+}
+
+/// A transfer rejected by the hook. The transaction fails, which discards
+/// CPI events, so this one is logged with `emit!`; logs of failed
+/// transactions are kept.
+#[event]
+pub struct HookBlocked {
+    pub launch_pool: Pubkey,
+    pub owner: Pubkey,
+    pub destination: Pubkey,
+    pub reason: BlockReason,
+    pub amount: u64,
+    /// Sender's entry price, fixed point (see `ENTRY_PRICE_SCALE`).
+    pub entry_price: u128,
+    /// Realized price of the attempted sell in the same fixed point (0 if
+    /// it couldn't be priced).
+    pub realized_price: u128,
+}
@@ -1,7 +1,17 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList};
+use spl_token_2022::extension::transfer_hook::TransferHookAccount;
+use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use spl_token_2022::state::Account as SplTokenAccount;
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
 
 use crate::errors::SamesError;
-use crate::state::{BuyerRecord, LaunchPool, LaunchStatus, MarketRegistry};
+use crate::events::{BlockReason, CostBasisCarried, HookBlocked};
+use crate::state::{
+    bonding_curve_sell_proceeds, cpmm_sell_proceeds, sale_below_entry, BuyerRecord, LaunchPool,
+    LaunchStatus, pause, MarketEntry, MarketKind, MarketRegistry, ProtocolConfig, ENTRY_PRICE_SCALE,
+};
 
 // ─────────────────────────────────────────────────────────────────────────────
 // Transfer Hook — enforces "no sell below entry price"
@@ -10,22 +20,49 @@ use crate::state::{BuyerRecord, LaunchPool, LaunchStatus, MarketRegistry};
 // How it works:
 // 1. Token-2022 calls our program on every transfer of SAMES tokens.
 // 2. We look up the sender's BuyerRecord to get their entry_price.
-// 3. We check if the destination is a known DEX/market account.
-// 4. If it IS a market account, we derive the implied sell price and compare.
-// 5. If sell price < entry price → REJECT the transfer.
-// 6. If destination is NOT a market (wallet-to-wallet), we allow it.
+// 3. We check if the destination is a market: registered in the launch's
+//    MarketRegistry, or a pool of a DEX on the ProtocolConfig allowlist
+//    (its token account owner is that DEX's pool-vault authority PDA).
+// 4. If it IS a market account, we price this particular sell of `amount`.
+// 5. If realized price < entry price → REJECT the transfer.
+// 6. If destination is NOT a market (wallet-to-wallet), we allow it, and
+//    fold the sender's entry price into the receiver's BuyerRecord
+//    (weighted average). Floored tokens only go to wallets that already have
+//    a record (`create_buyer_record`, paid by the receiver or the sender), so
+//    sending tokens to a fresh wallet doesn't shed the floor.
+//
+// Lock-up policy by LaunchStatus:
+// - Presale: every transfer is rejected.
+// - BondingCurve: price floor. Presale allocations under a vesting schedule
+//   never reach the wallet before they vest — they sit in the launch's
+//   escrow until `claim_vested` — so the hook has nothing to lock there.
+// - Graduated / Closed: unrestricted (the floor stays only if the launch
+//   opted into `floor_after_graduation`).
+// - Any phase: the guardian can halt transfers with `pause::TRANSFER`,
+//   protocol-wide or for this launch.
 //
 // Price derivation:
-// The implied sell price is passed via the extra_account_metas mechanism.
-// In practice, a cranker or the DEX frontend sets the current market price
-// in a PriceOracle PDA that we read during the hook.
+// The realized price is the SOL the destination market would pay for
+// `amount`, divided by `amount` — i.e. the average execution price of this
+// swap, not a pool-wide spot price. Large sells move the price against the
+// seller and can be blocked even when the spot price is above entry.
+// How it's derived depends on the destination's MarketKind:
 //
-// For V1 we use a simpler model: the LaunchPool tracks a `price_lamports`
-// that represents the current market price (updated by an oracle/cranker).
-// Transfers to market accounts are blocked if pool price < sender entry price.
+// - BondingCurveVault: priced with `bonding_curve_sell_proceeds`, the same
+//   math `sell_curve` uses, from the LaunchPool's curve position. Only while
+//   the curve is live.
+// - Cpmm: the pool's two reserve token accounts are appended to every
+//   transfer (see `extra_account_metas`) and priced with
+//   `cpmm_sell_proceeds`. Token-2022 invokes the hook after moving the
+//   tokens, so a transfer into the pool's token reserve is already included
+//   in its balance and is subtracted back out.
+// - Orderbook: no on-chain price to derive, so the sell is rejected.
+// - Allowlisted DEX pool the launch never registered: nothing to price it
+//   with either, so the sell is rejected until it is registered.
 
 /// Accounts required by the transfer hook.
 /// These are resolved via the extra-account-metas pattern.
+#[event_cpi]
 #[derive(Accounts)]
 pub struct TransferHook<'info> {
     /// The source token account (sender).
@@ -52,101 +89,367 @@ pub struct TransferHook<'info> {
     )]
     pub extra_account_meta_list: UncheckedAccount<'info>,
 
-    /// The LaunchPool for this token.
+    /// The LaunchPool for this token. `Account` checks owner and discriminator;
+    /// the seeds pin it to this mint. Read-only, so transfers of the token
+    /// don't all lock the same account; lists written while it was writable
+    /// still pass it that way.
     #[account(
         seeds = [b"launch_pool", mint.key().as_ref()],
         bump = launch_pool.bump,
+        constraint = launch_pool.mint == mint.key() @ SamesError::InvalidMint,
     )]
     pub launch_pool: Account<'info, LaunchPool>,
 
-    /// The sender's BuyerRecord (may not exist if they bought on market).
-    /// If it doesn't exist, we allow the transfer (they have no price floor).
-    /// CHECK: We try to deserialize; if it fails, transfer is allowed.
-    #[account(
-        seeds = [b"buyer_record", launch_pool.key().as_ref(), owner.key().as_ref()],
-        bump,
-    )]
+    /// BuyerRecord of the source account's owner (may not exist if they
+    /// bought on market — then they have no price floor).
+    /// CHECK: validated by `resolve_buyer_record` in the handler.
+    #[account(mut)]
     pub buyer_record: UncheckedAccount<'info>,
 
     /// MarketRegistry — list of known DEX accounts.
     #[account(
         seeds = [b"market_registry", launch_pool.key().as_ref()],
         bump = market_registry.bump,
+        has_one = launch_pool @ SamesError::InvalidMarket,
     )]
     pub market_registry: Account<'info, MarketRegistry>,
+
+    /// BuyerRecord of the destination account's owner. Must exist before
+    /// floored tokens can arrive (see `create_buyer_record`).
+    /// CHECK: validated by `resolve_buyer_record` in the handler.
+    #[account(mut)]
+    pub destination_record: UncheckedAccount<'info>,
+
+    /// Protocol-wide settings, holding the DEX allowlist. Required: without
+    /// it no transfer goes through, but `create_launch` needs it too, so it
+    /// exists before any launch's token does.
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    // Remaining accounts: reserve token accounts of every Cpmm market,
+    // matched by key against the registry.
 }
 
 /// Execute the transfer hook logic.
 ///
 /// Called by Token-2022 on every transfer. We enforce price floor only
 /// when the destination is a known market account AND the sender has a
-/// BuyerRecord (original presale participant).
+/// BuyerRecord (original presale participant). Wallet-to-wallet transfers
+/// carry the sender's entry price over to the receiver's BuyerRecord, so the
+/// floor follows the tokens rather than the wallet.
 pub fn handler(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
     let launch_pool = &ctx.accounts.launch_pool;
     let market_registry = &ctx.accounts.market_registry;
     let destination = ctx.accounts.destination_account.key();
 
-    // ── 1. Only enforce on live launches ────────────────────────────────
-    if launch_pool.status != LaunchStatus::BondingCurve {
-        // Presale tokens shouldn't be transferable anyway; Closed = no restrictions
-        return Ok(());
-    }
+    // ── 0. Only run from inside a Token-2022 transfer ───────────────────
+    // The hook mutates BuyerRecords, so it must not be callable directly.
+    let source_owner = assert_is_transferring(&ctx.accounts.source_account)?;
 
-    // ── 2. Check if destination is a known market/DEX account ───────────
-    let is_market_transfer = market_registry
-        .market_accounts
-        .iter()
-        .any(|m| *m == destination);
+    if ctx.accounts.protocol_config.is_paused(launch_pool, pause::TRANSFER) {
+        msg!("SAMES: Transfer BLOCKED. Transfers are paused");
+        emit_blocked(&ctx, &source_owner, BlockReason::Paused, amount, 0, 0);
+        return Err(SamesError::Paused.into());
+    }
 
-    if !is_market_transfer {
-        // Wallet-to-wallet transfer — allowed without price check.
-        // This means users can send tokens to friends freely.
+    // ── 1. Lock-up policy per launch phase ──────────────────────────────
+    // Presale: nothing moves. BondingCurve: price floor.
+    // Graduated / Closed: unrestricted, unless the launch opted into
+    // keeping the floor after graduation.
+    if launch_pool.are_transfers_locked() {
+        msg!("SAMES: Transfer BLOCKED. Presale tokens are locked until the curve goes live");
+        emit_blocked(&ctx, &source_owner, BlockReason::Locked, amount, 0, 0);
+        return Err(SamesError::TransfersLocked.into());
+    }
+    if !launch_pool.is_floor_active() {
         return Ok(());
     }
 
-    // ── 3. Try to load sender's BuyerRecord ─────────────────────────────
-    let buyer_record_info = &ctx.accounts.buyer_record;
+    // ── 2. Load the sender's BuyerRecord ────────────────────────────────
+    // If the account was never created, this person bought on the open
+    // market (not in presale) — no price floor applies to them. Anything
+    // else that isn't a genuine record for this owner rejects the transfer.
+    let launch_pool_key = launch_pool.key();
+    let mut sender_record =
+        resolve_buyer_record(&ctx.accounts.buyer_record, &launch_pool_key, &source_owner)?;
 
-    // If the account doesn't exist or has no data, this person bought on the
-    // open market (not in presale) — no price floor applies to them.
-    if buyer_record_info.data_is_empty() {
+    // ── 3. Check if destination is a known market/DEX account ───────────
+    let destination_owner = token_account_owner(&ctx.accounts.destination_account)?;
+    let market = market_registry.find_market(&destination);
+    let is_dex_pool = ctx.accounts.protocol_config.is_dex_account(&destination_owner);
+    if market.is_some() || is_dex_pool {
+        if let Some(record) = sender_record.as_mut() {
+            enforce_price_floor(&ctx, &source_owner, market, record.entry_price, amount)?;
+            record.send(amount).ok_or(SamesError::MathOverflow)?;
+            store_buyer_record(&ctx.accounts.buyer_record, record)?;
+        }
         return Ok(());
     }
 
-    // Deserialize the BuyerRecord.
-    let buyer_data = buyer_record_info.try_borrow_data()?;
-    // Skip 8-byte Anchor discriminator
-    if buyer_data.len() < 8 {
-        return Ok(()); // Malformed — allow transfer (fail open for non-presale users)
+    // ── 4. Wallet-to-wallet transfer — carry the cost basis ─────────────
+    // Users can still send tokens to friends freely, but the friend inherits
+    // the sender's entry price for what they received.
+    if destination_owner == source_owner {
+        return Ok(());
     }
 
-    let buyer_record = BuyerRecord::try_deserialize(&mut &buyer_data[..])
-        .map_err(|_| SamesError::NoBuyerRecord)?;
+    // Curve-issued tokens among them may be sold back against unassigned
+    // inventory. A wallet without a record could sell any of its tokens
+    // that way itself, so they all count.
+    let (sender_entry_price, curve_tokens) = match sender_record.as_mut() {
+        Some(record) => {
+            let curve_tokens = record.send(amount).ok_or(SamesError::MathOverflow)?;
+            store_buyer_record(&ctx.accounts.buyer_record, record)?;
+            (record.entry_price, curve_tokens)
+        }
+        None => (0, amount),
+    };
 
-    // ── 4. Price floor enforcement ──────────────────────────────────────
-    // The current "market price" is stored in LaunchPool.price_lamports.
-    // In production, this would be fed by an oracle or TWAP.
-    // For V1, the creator/cranker updates it.
-    let current_price = launch_pool.price_lamports;
-    let entry_price = buyer_record.entry_price;
+    let mut receiver_record = match resolve_buyer_record(
+        &ctx.accounts.destination_record,
+        &launch_pool_key,
+        &destination_owner,
+    )? {
+        Some(record) => record,
+        // Tokens without a floor arriving at a wallet without a record: nothing to track.
+        None if sender_entry_price == 0 => return Ok(()),
+        None => {
+            msg!(
+                "SAMES: Transfer BLOCKED. {} has no BuyerRecord to carry the entry price",
+                destination_owner
+            );
+            emit_blocked(&ctx, &source_owner, BlockReason::NoReceiverRecord, amount, sender_entry_price, 0);
+            return Err(SamesError::ReceiverRecordMissing.into());
+        }
+    };
+    receiver_record
+        .merge_entry_price(amount, sender_entry_price)
+        .ok_or(SamesError::MathOverflow)?;
+    receiver_record.tokens_received = receiver_record.tokens_received
+        .checked_add(amount).ok_or(SamesError::MathOverflow)?;
+    receiver_record.curve_tokens_received = receiver_record.curve_tokens_received
+        .checked_add(curve_tokens).ok_or(SamesError::MathOverflow)?;
+    store_buyer_record(&ctx.accounts.destination_record, &receiver_record)?;
 
-    if current_price < entry_price {
+    msg!(
+        "SAMES: Cost basis carried. amount={}, sender_entry={}, receiver_entry={}",
+        amount,
+        sender_entry_price,
+        receiver_record.entry_price
+    );
+    let event = CostBasisCarried {
+        launch_pool: launch_pool_key,
+        sender: source_owner,
+        receiver: destination_owner,
+        amount,
+        sender_entry_price,
+        receiver_entry_price: receiver_record.entry_price,
+    };
+    emit_cpi!(event);
+
+    Ok(())
+}
+
+/// Reject a market-bound transfer whose realized price is below `entry_price`.
+fn enforce_price_floor(
+    ctx: &Context<TransferHook>,
+    owner: &Pubkey,
+    market: Option<&MarketEntry>,
+    entry_price: u128,
+    amount: u64,
+) -> Result<()> {
+    let sol_out = match sell_proceeds(ctx, market, amount) {
+        Ok(sol_out) => sol_out,
+        Err(err) => {
+            emit_blocked(ctx, owner, BlockReason::Unpriceable, amount, entry_price, 0);
+            return Err(err);
+        }
+    };
+    // Only reported; the check itself doesn't divide.
+    let realized_price = (sol_out as u128 * ENTRY_PRICE_SCALE).checked_div(amount as u128).unwrap_or(0);
+
+    if sale_below_entry(sol_out, amount, entry_price) {
         msg!(
-            "SAMES: Transfer BLOCKED. Market price {} < entry price {}",
-            current_price,
-            entry_price
+            "SAMES: Transfer BLOCKED. Realized price {} < entry price {} (amount={}, proceeds={})",
+            realized_price,
+            entry_price,
+            amount,
+            sol_out
         );
+        emit_blocked(ctx, owner, BlockReason::BelowEntry, amount, entry_price, realized_price);
         return Err(SamesError::HookSellBelowEntry.into());
     }
 
-    // ── 5. Passed all checks — transfer allowed ────────────────────────
     msg!(
-        "SAMES: Transfer OK. amount={}, market_price={}, entry_price={}",
+        "SAMES: Transfer OK. amount={}, realized_price={}, entry_price={}",
         amount,
-        current_price,
+        realized_price,
         entry_price
     );
+    Ok(())
+}
+
+/// Lamports a sale of `amount` into `market` would realize.
+fn sell_proceeds(ctx: &Context<TransferHook>, market: Option<&MarketEntry>, amount: u64) -> Result<u64> {
+    let launch_pool = &ctx.accounts.launch_pool;
+    let Some(market) = market else {
+        msg!(
+            "SAMES: Transfer BLOCKED. DEX pool {} isn't registered for this launch, so it can't be priced",
+            ctx.accounts.destination_account.key()
+        );
+        return Err(SamesError::HookPriceDerivationFailed.into());
+    };
+
+    match market.kind {
+        MarketKind::BondingCurveVault => {
+            require!(
+                launch_pool.status == LaunchStatus::BondingCurve,
+                SamesError::HookPriceDerivationFailed
+            );
+            bonding_curve_sell_proceeds(
+                launch_pool.price_lamports,
+                launch_pool.slope_scaled,
+                launch_pool.tokens_sold_curve,
+                amount,
+            )
+        }
+        MarketKind::Cpmm => {
+            let reserves = ctx.remaining_accounts;
+            let sol_reserve = reserve_token_account(reserve_account(reserves, &market.sol_reserve)?)
+                .ok_or(SamesError::HookPriceDerivationFailed)?;
+            let token_reserve = reserve_token_account(reserve_account(reserves, &market.token_reserve)?)
+                .ok_or(SamesError::HookPriceDerivationFailed)?;
+            // Registered before reserves were checked, or not a pool of this token.
+            require!(
+                MarketEntry::reserves_match(&launch_pool.mint, &token_reserve.mint, &sol_reserve.mint),
+                SamesError::HookPriceDerivationFailed
+            );
+            let sol_reserve = sol_reserve.amount;
+            let mut token_reserve = token_reserve.amount;
+            if market.address == market.token_reserve {
+                token_reserve = token_reserve
+                    .checked_sub(amount)
+                    .ok_or(SamesError::HookPriceDerivationFailed)?;
+            }
+            cpmm_sell_proceeds(token_reserve, sol_reserve, amount)
+        }
+        MarketKind::Orderbook => {
+            msg!("SAMES: Transfer BLOCKED. Orderbook market {} can't be priced on-chain", market.address);
+            return Err(SamesError::HookPriceDerivationFailed.into());
+        }
+    }
+    .ok_or_else(|| SamesError::HookPriceDerivationFailed.into())
+}
+
+/// Log a rejected transfer. The transaction is about to fail, which drops
+/// CPI events, so this goes through `emit!`.
+fn emit_blocked(
+    ctx: &Context<TransferHook>,
+    owner: &Pubkey,
+    reason: BlockReason,
+    amount: u64,
+    entry_price: u128,
+    realized_price: u128,
+) {
+    emit!(HookBlocked {
+        launch_pool: ctx.accounts.launch_pool.key(),
+        owner: *owner,
+        destination: ctx.accounts.destination_account.key(),
+        reason,
+        amount,
+        entry_price,
+        realized_price,
+    });
+}
+
+/// Load the BuyerRecord of `owner` for `launch_pool`, failing closed.
+///
+/// Returns `None` only for the genuine, never-created PDA: system-owned and
+/// without data. A wrong address, a foreign owner, a bad discriminator or a
+/// record whose fields point at another launch or wallet are all rejected.
+pub fn resolve_buyer_record(
+    info: &AccountInfo,
+    launch_pool: &Pubkey,
+    owner: &Pubkey,
+) -> Result<Option<BuyerRecord>> {
+    let (expected, _) = buyer_record_address(launch_pool, owner);
+    require_keys_eq!(info.key(), expected, SamesError::InvalidBuyerRecord);
+
+    if *info.owner == system_program::ID && info.data_is_empty() {
+        return Ok(None);
+    }
+    require_keys_eq!(*info.owner, crate::ID, SamesError::InvalidBuyerRecord);
+
+    let data = info.try_borrow_data()?;
+    let record = BuyerRecord::try_deserialize(&mut &data[..])
+        .map_err(|_| SamesError::InvalidBuyerRecord)?;
+    require_keys_eq!(record.launch_pool, *launch_pool, SamesError::InvalidBuyerRecord);
+    require_keys_eq!(record.buyer, *owner, SamesError::InvalidBuyerRecord);
+    Ok(Some(record))
+}
+
+pub fn store_buyer_record(info: &AccountInfo, record: &BuyerRecord) -> Result<()> {
+    let mut data = info.try_borrow_mut_data()?;
+    record.try_serialize(&mut &mut data[..])
+}
+
+pub fn buyer_record_address(launch_pool: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"buyer_record", launch_pool.as_ref(), owner.as_ref()], &crate::ID)
+}
+
+/// Check the source account is mid-transfer and return its owner.
+fn assert_is_transferring(source: &AccountInfo) -> Result<Pubkey> {
+    require_keys_eq!(*source.owner, spl_token_2022::ID, SamesError::NotTransferring);
+    let data = source.try_borrow_data()?;
+    let account = StateWithExtensions::<SplTokenAccount>::unpack(&data)
+        .map_err(|_| SamesError::NotTransferring)?;
+    let extension = account
+        .get_extension::<TransferHookAccount>()
+        .map_err(|_| SamesError::NotTransferring)?;
+    require!(bool::from(extension.transferring), SamesError::NotTransferring);
+    Ok(account.base.owner)
+}
+
+/// Read the `owner` of a Token-2022 token account.
+fn token_account_owner(info: &AccountInfo) -> Result<Pubkey> {
+    let data = info.try_borrow_data()?;
+    let account = StateWithExtensions::<SplTokenAccount>::unpack(&data)
+        .map_err(|_| SamesError::InvalidMint)?;
+    Ok(account.base.owner)
+}
+
+/// Find a market's reserve account among the hook's remaining accounts.
+fn reserve_account<'a, 'info>(accounts: &'a [AccountInfo<'info>], key: &Pubkey) -> Result<&'a AccountInfo<'info>> {
+    accounts
+        .iter()
+        .find(|info| info.key == key)
+        .ok_or_else(|| SamesError::HookPriceDerivationFailed.into())
+}
+
+/// Load an SPL Token or Token-2022 token account; `None` if it isn't one.
+fn reserve_token_account(info: &AccountInfo) -> Option<SplTokenAccount> {
+    if *info.owner != anchor_spl::token::ID && *info.owner != spl_token_2022::ID {
+        return None;
+    }
+    let data = info.try_borrow_data().ok()?;
+    let account = StateWithExtensions::<SplTokenAccount>::unpack(&data).ok()?;
+    Some(account.base)
+}
 
+/// Check a `Cpmm` market's reserves, found among `accounts`, pair `mint`
+/// with wrapped SOL. Other kinds have no reserves.
+pub fn check_market_reserves(market: &MarketEntry, accounts: &[AccountInfo], mint: &Pubkey) -> Result<()> {
+    if market.kind != MarketKind::Cpmm {
+        return Ok(());
+    }
+    let mint_of = |key: &Pubkey| {
+        let info = accounts.iter().find(|info| info.key == key)?;
+        reserve_token_account(info).map(|account| account.mint)
+    };
+    let (token_mint, sol_mint) = mint_of(&market.token_reserve)
+        .zip(mint_of(&market.sol_reserve))
+        .ok_or(SamesError::InvalidMarket)?;
+    require!(MarketEntry::reserves_match(mint, &token_mint, &sol_mint), SamesError::InvalidMarket);
     Ok(())
 }
 
@@ -154,6 +457,139 @@ pub fn handler(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
 // Initialize extra-account-metas (called once at launch creation)
 // ─────────────────────────────────────────────────────────────────────────────
 // This sets up the additional accounts that Token-2022 will pass to our hook.
+//
+// Account indices seen by the hook:
+//   0 source, 1 mint, 2 destination, 3 owner, 4 extra_account_meta_list,
+//   5 launch_pool, 6 buyer_record, 7 market_registry,
+//   8 destination_record, 9 protocol_config,
+//   10 event_authority, 11 program (for `CostBasisCarried`),
+//   12.. token and SOL reserve of each Cpmm market, in registry order
+//
+// The reserves are written in as fixed pubkeys, so the list is rewritten
+// (`sync_extra_account_meta_list`) whenever the registry changes.
+//
+// BuyerRecords are keyed by the token accounts' owners (read from bytes
+// 32..64 of the token account), not by the transfer authority, so delegated
+// transfers still hit the holder's record.
+
+/// The extra accounts Token-2022 resolves and appends on every transfer.
+/// Order must match the `TransferHook` accounts struct.
+pub fn extra_account_metas(registry: &MarketRegistry) -> Result<Vec<ExtraAccountMeta>> {
+    let mut metas = vec![
+        // 5: launch_pool = PDA["launch_pool", mint]
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"launch_pool".to_vec() },
+                Seed::AccountKey { index: 1 },
+            ],
+            false,
+            false,
+        )?,
+        // 6: buyer_record = PDA["buyer_record", launch_pool, source.owner]
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"buyer_record".to_vec() },
+                Seed::AccountKey { index: 5 },
+                Seed::AccountData { account_index: 0, data_index: 32, length: 32 },
+            ],
+            false,
+            true,
+        )?,
+        // 7: market_registry = PDA["market_registry", launch_pool]
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"market_registry".to_vec() },
+                Seed::AccountKey { index: 5 },
+            ],
+            false,
+            false,
+        )?,
+        // 8: destination_record = PDA["buyer_record", launch_pool, destination.owner]
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"buyer_record".to_vec() },
+                Seed::AccountKey { index: 5 },
+                Seed::AccountData { account_index: 2, data_index: 32, length: 32 },
+            ],
+            false,
+            true,
+        )?,
+        // 9: protocol_config = PDA["protocol_config"]
+        ExtraAccountMeta::new_with_seeds(
+            &[Seed::Literal { bytes: b"protocol_config".to_vec() }],
+            false,
+            false,
+        )?,
+        // 10: event_authority = PDA["__event_authority"]
+        ExtraAccountMeta::new_with_seeds(
+            &[Seed::Literal { bytes: b"__event_authority".to_vec() }],
+            false,
+            false,
+        )?,
+        // 11: this program, for the event CPI
+        ExtraAccountMeta::new_with_pubkey(&crate::ID, false, false)?,
+    ];
+    // 12..: Cpmm reserves
+    for market in registry.markets.iter().filter(|m| m.kind == MarketKind::Cpmm) {
+        metas.push(ExtraAccountMeta::new_with_pubkey(&market.token_reserve, false, false)?);
+        metas.push(ExtraAccountMeta::new_with_pubkey(&market.sol_reserve, false, false)?);
+    }
+    Ok(metas)
+}
+
+/// Rewrite the extra-account-metas PDA after `registry` changed, resizing
+/// it and settling the rent difference with `payer`. A list that hasn't
+/// been initialized yet is left alone; it picks up the registry when it is.
+pub fn sync_extra_account_meta_list<'info>(
+    meta_list: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    registry: &MarketRegistry,
+) -> Result<()> {
+    if meta_list.data_is_empty() {
+        return Ok(());
+    }
+    require_keys_eq!(*meta_list.owner, crate::ID, SamesError::InvalidMarket);
+
+    let metas = extra_account_metas(registry)?;
+    let size = ExtraAccountMetaList::size_of(metas.len())?;
+    let rent = Rent::get()?.minimum_balance(size);
+    let current_size = meta_list.data_len();
+
+    if size > current_size {
+        let top_up = rent.saturating_sub(meta_list.lamports());
+        if top_up > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    system_program::Transfer { from: payer.clone(), to: meta_list.clone() },
+                ),
+                top_up,
+            )?;
+        }
+        meta_list.realloc(size, false)?;
+    }
+
+    ExtraAccountMetaList::update::<ExecuteInstruction>(&mut meta_list.try_borrow_mut_data()?, &metas)?;
+
+    if size < current_size {
+        meta_list.realloc(size, false)?;
+        let refund = meta_list.lamports().saturating_sub(rent);
+        **meta_list.try_borrow_mut_lamports()? -= refund;
+        **payer.try_borrow_mut_lamports()? += refund;
+    }
+    Ok(())
+}
+
+/// Close an account of this program, moving all its lamports to `destination`.
+pub fn close_program_account<'info>(info: &AccountInfo<'info>, destination: &AccountInfo<'info>) -> Result<()> {
+    let lamports = info.lamports();
+    **info.try_borrow_mut_lamports()? = 0;
+    **destination.try_borrow_mut_lamports()? += lamports;
+    info.assign(&system_program::ID);
+    info.realloc(0, false)?;
+    Ok(())
+}
 
 #[derive(Accounts)]
 pub struct InitializeExtraAccountMetaList<'info> {
@@ -188,3 +624,43 @@ pub struct InitializeExtraAccountMetaList<'info> {
 
     pub system_program: Program<'info, System>,
 }
+
+/// Create the extra-account-metas PDA and write the hook's account list into it.
+pub fn initialize_extra_account_meta_list(ctx: Context<InitializeExtraAccountMetaList>) -> Result<()> {
+    require!(
+        ctx.accounts.launch_pool.creator == ctx.accounts.payer.key(),
+        SamesError::UnauthorizedCreator
+    );
+
+    let metas = extra_account_metas(&ctx.accounts.market_registry)?;
+    let size = ExtraAccountMetaList::size_of(metas.len())?;
+    let lamports = Rent::get()?.minimum_balance(size);
+
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[u8]] = &[
+        b"extra-account-metas",
+        mint_key.as_ref(),
+        &[ctx.bumps.extra_account_meta_list],
+    ];
+    system_program::create_account(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::CreateAccount {
+                from: ctx.accounts.payer.to_account_info(),
+                to: ctx.accounts.extra_account_meta_list.to_account_info(),
+            },
+            &[signer_seeds],
+        ),
+        lamports,
+        size as u64,
+        ctx.program_id,
+    )?;
+
+    ExtraAccountMetaList::init::<ExecuteInstruction>(
+        &mut ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?,
+        &metas,
+    )?;
+
+    msg!("SAMES: Transfer hook extra account metas initialized ({} accounts)", metas.len());
+    Ok(())
+}
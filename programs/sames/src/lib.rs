@@ -1,29 +1,24 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
+use anchor_lang::Discriminator;
 use anchor_spl::token_2022::{self, Token2022};
 use anchor_spl::token_interface::{Mint as MintAccount, TokenAccount};
 
 pub mod errors;
+pub mod events;
 pub mod state;
 pub mod hook;
 
 use errors::SamesError;
+use events::*;
+use hook::*;
 use state::*;
 
 declare_id!("H91AKWdUASAKjpGwq4NXzp8kyddLbZMj9N1rP8HFjCmw");
 
-/// Presale window duration in seconds.
-const PRESALE_DURATION: i64 = 30;
-
-/// Default graduation threshold: 69 SOL.
-const DEFAULT_GRADUATION_THRESHOLD: u64 = 69_000_000_000;
-
-/// Default bonding curve slope (scaled by 1e9).
-/// With base_price=1000 lamports and slope=100, price doubles after 10M tokens sold.
-const DEFAULT_SLOPE: u64 = 100;
-
-/// Platform fee: 1% (in basis points = 100).
-const PLATFORM_FEE_BPS: u64 = 100;
+// Fees, durations and launch defaults live in the ProtocolConfig account
+// (see `ProtocolParams`), along with the ranges they may take
+// (`ProtocolBounds`), which have fixed outer limits.
 
 #[program]
 pub mod sames {
@@ -32,17 +27,30 @@ pub mod sames {
     // ═════════════════════════════════════════════════════════════════════
     // 1. CREATE LAUNCH
     // ═════════════════════════════════════════════════════════════════════
+    #[allow(clippy::too_many_arguments)]
     pub fn create_launch(
         ctx: Context<CreateLaunch>,
         token_name: String,
         token_symbol: String,
         total_supply: u64,
         price_lamports: u64,
+        floor_after_graduation: bool,
+        presale_vesting: VestingSchedule,
+        creator_allocation_bps: u16,
+        creator_vesting: VestingSchedule,
     ) -> Result<()> {
         require!(token_name.len() <= 32, SamesError::NameTooLong);
         require!(token_symbol.len() <= 10, SamesError::SymbolTooLong);
         require!(total_supply > 0, SamesError::ZeroSupply);
         require!(price_lamports > 0, SamesError::ZeroPrice);
+        require!(presale_vesting.is_valid(), SamesError::InvalidVestingSchedule);
+        require!(creator_vesting.is_valid(), SamesError::InvalidVestingSchedule);
+        let params = ctx.accounts.protocol_config.params;
+        require!(
+            creator_allocation_bps <= params.max_creator_allocation_bps
+                && (creator_allocation_bps == 0 || creator_vesting.is_enabled()),
+            SamesError::InvalidCreatorAllocation
+        );
 
         let clock = Clock::get()?;
         let now = clock.unix_timestamp;
@@ -54,28 +62,64 @@ pub mod sames {
         pool.token_symbol = token_symbol;
         pool.total_supply = total_supply;
         pool.price_lamports = price_lamports;
-        pool.slope_scaled = DEFAULT_SLOPE;
+        pool.slope_scaled = params.default_slope;
         pool.tokens_sold_curve = 0;
         pool.curve_sol_collected = 0;
         pool.start_time = now;
         pool.end_time = now
-            .checked_add(PRESALE_DURATION)
+            .checked_add(params.presale_duration)
             .ok_or(SamesError::MathOverflow)?;
         pool.total_sol_collected = 0;
         pool.buyer_count = 0;
-        pool.graduation_threshold = DEFAULT_GRADUATION_THRESHOLD;
+        pool.graduation_threshold = params.default_graduation_threshold;
         pool.status = LaunchStatus::Presale;
         pool.bump = ctx.bumps.launch_pool;
         pool.vault_bump = ctx.bumps.vault;
-        pool._reserved = [0u8; 64];
+        pool.floor_after_graduation = floor_after_graduation;
+        pool.presale_vesting = presale_vesting;
+        pool.creator_allocation_bps = creator_allocation_bps;
+        pool.creator_vesting = creator_vesting;
+        pool.creator_tokens_claimed = 0;
+        pool.paused = 0;
+        pool.curve_tokens_unassigned = 0;
+        pool.refund_deadline = 0;
+        pool._reserved = [0u8; 19];
 
         let registry = &mut ctx.accounts.market_registry;
         registry.launch_pool = pool.key();
         registry.authority = ctx.accounts.creator.key();
-        registry.market_accounts = Vec::new();
+        registry.markets = Vec::new();
         registry.bump = ctx.bumps.market_registry;
 
-        msg!("SAMES: Launch created. Presale {} to {}", pool.start_time, pool.end_time);
+        msg!(
+            "SAMES: Launch created. Presale {} to {}. Floor after graduation: {}. Vesting: cliff {}s, duration {}s",
+            pool.start_time, pool.end_time, floor_after_graduation,
+            presale_vesting.cliff_seconds, presale_vesting.duration_seconds
+        );
+        if creator_allocation_bps > 0 {
+            msg!(
+                "SAMES: Creator allocation {} tokens ({} bps), locked: cliff {}s, duration {}s",
+                pool.creator_allocation(), creator_allocation_bps,
+                creator_vesting.cliff_seconds, creator_vesting.duration_seconds
+            );
+        }
+
+        let event = LaunchCreated {
+            launch_pool: pool.key(),
+            mint: pool.mint,
+            creator: pool.creator,
+            total_supply,
+            price_lamports,
+            slope_scaled: pool.slope_scaled,
+            graduation_threshold: pool.graduation_threshold,
+            start_time: pool.start_time,
+            end_time: pool.end_time,
+            floor_after_graduation,
+            presale_vesting,
+            creator_allocation: pool.creator_allocation(),
+            creator_vesting,
+        };
+        emit_cpi!(event);
         Ok(())
     }
 
@@ -84,6 +128,10 @@ pub mod sames {
     // ═════════════════════════════════════════════════════════════════════
     pub fn buy_presale(ctx: Context<BuyPresale>, sol_amount: u64) -> Result<()> {
         require!(sol_amount > 0, SamesError::ZeroDeposit);
+        require!(
+            !ctx.accounts.protocol_config.is_paused(&ctx.accounts.launch_pool, pause::PRESALE_BUY),
+            SamesError::Paused
+        );
 
         let clock = Clock::get()?;
         let now = clock.unix_timestamp;
@@ -108,17 +156,23 @@ pub mod sames {
         pool.total_sol_collected = pool.total_sol_collected
             .checked_add(sol_amount).ok_or(SamesError::MathOverflow)?;
 
+        let rent = ctx.accounts.buyer_record.to_account_info().lamports();
         let record = &mut ctx.accounts.buyer_record;
-        if record.sol_deposited == 0 && record.curve_sol_spent == 0 {
+        if record.launch_pool == Pubkey::default() {
             record.launch_pool = pool.key();
             record.buyer = ctx.accounts.buyer.key();
-            record.entry_price = pool.price_lamports;
+            record.entry_price = pool.price_lamports as u128 * ENTRY_PRICE_SCALE;
             record.tokens_allocated = 0;
             record.tokens_sold = 0;
             record.curve_sol_spent = 0;
             record.curve_tokens_bought = 0;
             record.bump = ctx.bumps.buyer_record;
-            record._reserved = [0u8; 32];
+            record.tokens_received = 0;
+            record.tokens_sent = 0;
+            record.tokens_vesting = 0;
+            record.rent_payer = ctx.accounts.buyer.key();
+            record.rent_paid = rent;
+            record.curve_tokens_received = 0;
             pool.buyer_count = pool.buyer_count.checked_add(1).ok_or(SamesError::MathOverflow)?;
         }
 
@@ -126,6 +180,14 @@ pub mod sames {
             .checked_add(sol_amount).ok_or(SamesError::MathOverflow)?;
 
         msg!("SAMES: Presale buy {} lamports by {}", sol_amount, ctx.accounts.buyer.key());
+        let event = PresaleBuy {
+            launch_pool: pool.key(),
+            buyer: record.buyer,
+            sol_amount,
+            sol_deposited: record.sol_deposited,
+            total_sol_collected: pool.total_sol_collected,
+        };
+        emit_cpi!(event);
         Ok(())
     }
 
@@ -144,20 +206,93 @@ pub mod sames {
         // Calculate this buyer's token allocation
         let record = &mut ctx.accounts.buyer_record;
         require!(record.sol_deposited > 0, SamesError::ZeroDeposit);
+        require!(record.tokens_allocated == 0, SamesError::AlreadyFinalized);
 
         let tokens = (record.sol_deposited as u128)
-            .checked_mul(pool.total_supply as u128)
+            .checked_mul(pool.presale_supply() as u128)
             .ok_or(SamesError::MathOverflow)?
             .checked_div(pool.total_sol_collected as u128)
             .ok_or(SamesError::MathOverflow)? as u64;
 
         record.tokens_allocated = tokens;
-        record.entry_price = pool.price_lamports;
+        record.entry_price = pool.price_lamports as u128 * ENTRY_PRICE_SCALE;
+
+        // Vesting launches hold the allocation in escrow until claim_vested
+        let vesting = pool.presale_vesting.is_enabled();
+        record.tokens_vesting = if vesting { tokens } else { 0 };
+        let to = if vesting {
+            ctx.accounts.vesting_escrow.to_account_info()
+        } else {
+            ctx.accounts.buyer_token_account.to_account_info()
+        };
+
+        // Mint tokens to buyer (or escrow)
+        let mint_key = pool.mint;
+        let pool_seeds: &[&[u8]] = &[b"launch_pool", mint_key.as_ref(), &[pool.bump]];
+
+        token_2022::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_2022::MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to,
+                    authority: ctx.accounts.launch_pool.to_account_info(),
+                },
+                &[pool_seeds],
+            ),
+            tokens,
+        )?;
+
+        if vesting {
+            msg!("SAMES: Allocated {} tokens to {} (vesting in escrow)", tokens, record.buyer);
+        } else {
+            msg!("SAMES: Allocated {} tokens to {}", tokens, record.buyer);
+        }
+        let event = AllocationClaimed {
+            launch_pool: record.launch_pool,
+            recipient: record.buyer,
+            amount: tokens,
+            to_escrow: vesting,
+            creator: false,
+            still_vesting: record.tokens_vesting,
+        };
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    // ═════════════════════════════════════════════════════════════════════
+    // 3a. CLAIM VESTED PRESALE TOKENS
+    // ═════════════════════════════════════════════════════════════════════
+    /// Release whatever part of the buyer's escrowed allocation has vested.
+    /// Escrowed tokens are burned and re-minted to the buyer rather than
+    /// transferred: a Token-2022 transfer would invoke this program's own
+    /// transfer hook, and Solana rejects that kind of reentrancy.
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let pool = &ctx.accounts.launch_pool;
+        require!(pool.status != LaunchStatus::Presale, SamesError::TransfersLocked);
+        require!(pool.status != LaunchStatus::Closed, SamesError::LaunchClosed);
+
+        let record = &ctx.accounts.buyer_record;
+        let locked = pool.locked_presale_tokens(record.tokens_allocated, now);
+        let claimable = record.tokens_vesting.saturating_sub(locked);
+        require!(claimable > 0, SamesError::TokensStillVesting);
 
-        // Mint tokens to buyer
         let mint_key = pool.mint;
         let pool_seeds: &[&[u8]] = &[b"launch_pool", mint_key.as_ref(), &[pool.bump]];
 
+        token_2022::burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_2022::Burn {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    from: ctx.accounts.vesting_escrow.to_account_info(),
+                    authority: ctx.accounts.launch_pool.to_account_info(),
+                },
+                &[pool_seeds],
+            ),
+            claimable,
+        )?;
         token_2022::mint_to(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
@@ -168,10 +303,22 @@ pub mod sames {
                 },
                 &[pool_seeds],
             ),
-            tokens,
+            claimable,
         )?;
 
-        msg!("SAMES: Allocated {} tokens to {}", tokens, record.buyer);
+        let record = &mut ctx.accounts.buyer_record;
+        record.tokens_vesting -= claimable;
+
+        msg!("SAMES: Claimed {} vested tokens for {} ({} still vesting)", claimable, record.buyer, record.tokens_vesting);
+        let event = AllocationClaimed {
+            launch_pool: record.launch_pool,
+            recipient: record.buyer,
+            amount: claimable,
+            to_escrow: false,
+            creator: false,
+            still_vesting: record.tokens_vesting,
+        };
+        emit_cpi!(event);
         Ok(())
     }
 
@@ -191,6 +338,105 @@ pub mod sames {
         // Set the base price for the curve based on presale price
         // The curve starts where the presale ended
         msg!("SAMES: Bonding curve LIVE. Base price: {} lamports", pool.price_lamports);
+
+        // Lock the creator allocation in its own escrow, apart from presale
+        // vesting, so neither claim path can pay out the other's tokens
+        let allocation = pool.creator_allocation();
+        if allocation > 0 {
+            let mint_key = pool.mint;
+            let pool_seeds: &[&[u8]] = &[b"launch_pool", mint_key.as_ref(), &[pool.bump]];
+            token_2022::mint_to(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_2022::MintTo {
+                        mint: ctx.accounts.mint.to_account_info(),
+                        to: ctx.accounts.creator_escrow.to_account_info(),
+                        authority: ctx.accounts.launch_pool.to_account_info(),
+                    },
+                    &[pool_seeds],
+                ),
+                allocation,
+            )?;
+
+            let pool = &ctx.accounts.launch_pool;
+            msg!(
+                "SAMES: Creator allocation of {} tokens locked in escrow. Cliff {}s, duration {}s from {}",
+                allocation, pool.creator_vesting.cliff_seconds,
+                pool.creator_vesting.duration_seconds, pool.end_time
+            );
+            let event = AllocationClaimed {
+                launch_pool: pool.key(),
+                recipient: pool.creator,
+                amount: allocation,
+                to_escrow: true,
+                creator: true,
+                still_vesting: allocation,
+            };
+            emit_cpi!(event);
+        }
+        Ok(())
+    }
+
+    // ═════════════════════════════════════════════════════════════════════
+    // 4b. CLAIM CREATOR ALLOCATION
+    // ═════════════════════════════════════════════════════════════════════
+    /// Release the vested part of the creator allocation from escrow, by
+    /// burn and re-mint for the same reason as `claim_vested`.
+    pub fn claim_creator_allocation(ctx: Context<ClaimCreatorAllocation>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let pool = &ctx.accounts.launch_pool;
+        require!(pool.creator == ctx.accounts.creator.key(), SamesError::UnauthorizedCreator);
+        require!(pool.status != LaunchStatus::Presale, SamesError::TransfersLocked);
+        require!(pool.status != LaunchStatus::Closed, SamesError::LaunchClosed);
+
+        let claimable = pool.claimable_creator_tokens(now);
+        require!(claimable > 0, SamesError::TokensStillVesting);
+
+        let mint_key = pool.mint;
+        let pool_seeds: &[&[u8]] = &[b"launch_pool", mint_key.as_ref(), &[pool.bump]];
+
+        token_2022::burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_2022::Burn {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    from: ctx.accounts.creator_escrow.to_account_info(),
+                    authority: ctx.accounts.launch_pool.to_account_info(),
+                },
+                &[pool_seeds],
+            ),
+            claimable,
+        )?;
+        token_2022::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_2022::MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.creator_token_account.to_account_info(),
+                    authority: ctx.accounts.launch_pool.to_account_info(),
+                },
+                &[pool_seeds],
+            ),
+            claimable,
+        )?;
+
+        let pool = &mut ctx.accounts.launch_pool;
+        pool.creator_tokens_claimed = pool.creator_tokens_claimed
+            .checked_add(claimable).ok_or(SamesError::MathOverflow)?;
+
+        msg!(
+            "SAMES: Creator claimed {} tokens ({} of {} released)",
+            claimable, pool.creator_tokens_claimed, pool.creator_allocation()
+        );
+        let event = AllocationClaimed {
+            launch_pool: pool.key(),
+            recipient: pool.creator,
+            amount: claimable,
+            to_escrow: false,
+            creator: true,
+            still_vesting: pool.creator_allocation() - pool.creator_tokens_claimed,
+        };
+        emit_cpi!(event);
         Ok(())
     }
 
@@ -199,6 +445,10 @@ pub mod sames {
     // ═════════════════════════════════════════════════════════════════════
     pub fn buy_curve(ctx: Context<BuyCurve>, sol_amount: u64) -> Result<()> {
         require!(sol_amount > 0, SamesError::ZeroDeposit);
+        require!(
+            !ctx.accounts.protocol_config.is_paused(&ctx.accounts.launch_pool, pause::CURVE_BUY),
+            SamesError::Paused
+        );
 
         // Read values first to avoid borrow conflicts with CPI
         let pool_status = ctx.accounts.launch_pool.status;
@@ -254,30 +504,33 @@ pub mod sames {
         pool.curve_sol_collected = pool.curve_sol_collected
             .checked_add(cost).ok_or(SamesError::MathOverflow)?;
 
+        let rent = ctx.accounts.buyer_record.to_account_info().lamports();
         let record = &mut ctx.accounts.buyer_record;
-        if record.sol_deposited == 0 && record.curve_sol_spent == 0 {
+        if record.launch_pool == Pubkey::default() {
             record.launch_pool = pool.key();
             record.buyer = ctx.accounts.buyer.key();
             record.tokens_allocated = 0;
             record.tokens_sold = 0;
             record.bump = ctx.bumps.buyer_record;
-            record._reserved = [0u8; 32];
+            record.tokens_received = 0;
+            record.tokens_sent = 0;
+            record.tokens_vesting = 0;
+            record.rent_payer = ctx.accounts.buyer.key();
+            record.rent_paid = rent;
+            record.curve_tokens_received = 0;
             pool.buyer_count = pool.buyer_count.checked_add(1).ok_or(SamesError::MathOverflow)?;
         }
 
+        // Update entry price to the weighted average of what the buyer already
+        // holds (presale, curve or received by transfer) and this purchase.
+        let price = entry_price(cost, tokens).ok_or(SamesError::MathOverflow)?;
+        record.merge_entry_price(tokens, price).ok_or(SamesError::MathOverflow)?;
+
         record.curve_sol_spent = record.curve_sol_spent
             .checked_add(cost).ok_or(SamesError::MathOverflow)?;
         record.curve_tokens_bought = record.curve_tokens_bought
             .checked_add(tokens).ok_or(SamesError::MathOverflow)?;
 
-        // Update entry price to weighted average
-        let total_sol = record.sol_deposited.saturating_add(record.curve_sol_spent);
-        let total_tkns = record.tokens_allocated.saturating_add(record.curve_tokens_bought);
-        if total_tkns > 0 {
-            record.entry_price = ((total_sol as u128)
-                .checked_div(total_tkns as u128).unwrap_or(0)) as u64;
-        }
-
         // Check graduation
         if pool.curve_sol_collected >= graduation_threshold {
             msg!("SAMES: Graduation threshold reached! {} lamports", pool.curve_sol_collected);
@@ -285,6 +538,19 @@ pub mod sames {
 
         let new_price = bonding_curve_price(base_price, slope, pool.tokens_sold_curve);
         msg!("SAMES: Curve buy {} tokens for {} lamports. Price: {}", tokens, cost, new_price);
+        let event = CurveTrade {
+            launch_pool: pool.key(),
+            trader: record.buyer,
+            is_buy: true,
+            token_amount: tokens,
+            sol_amount: cost,
+            fee: 0,
+            price_after: new_price,
+            tokens_sold_curve: pool.tokens_sold_curve,
+            curve_sol_collected: pool.curve_sol_collected,
+            entry_price: record.entry_price,
+        };
+        emit_cpi!(event);
         Ok(())
     }
 
@@ -293,52 +559,89 @@ pub mod sames {
     // ═════════════════════════════════════════════════════════════════════
     pub fn sell_curve(ctx: Context<SellCurve>, token_amount: u64) -> Result<()> {
         require!(token_amount > 0, SamesError::ZeroSellAmount);
+        require!(
+            !ctx.accounts.protocol_config.is_paused(&ctx.accounts.launch_pool, pause::CURVE_SELL),
+            SamesError::Paused
+        );
 
         // Read values first to avoid borrow issues
         let pool_status = ctx.accounts.launch_pool.status;
         let base_price = ctx.accounts.launch_pool.price_lamports;
         let slope = ctx.accounts.launch_pool.slope_scaled;
         let tokens_sold = ctx.accounts.launch_pool.tokens_sold_curve;
-        let _vault_bump = ctx.accounts.launch_pool.vault_bump;
-        let entry_price = ctx.accounts.buyer_record.entry_price;
+        let vault_bump = ctx.accounts.launch_pool.vault_bump;
+        let pool_key = ctx.accounts.launch_pool.key();
+        let seller = ctx.accounts.seller.key();
 
         require!(pool_status == LaunchStatus::BondingCurve, SamesError::NotBondingCurve);
 
-        // Check balance
-        let total_tokens = ctx.accounts.buyer_record.tokens_allocated
-            .saturating_add(ctx.accounts.buyer_record.curve_tokens_bought);
-        let available = total_tokens.saturating_sub(ctx.accounts.buyer_record.tokens_sold);
+        // Holders without a record got their tokens on a market: as in the
+        // transfer hook, no price floor applies to them.
+        let mut record = resolve_buyer_record(&ctx.accounts.buyer_record, &pool_key, &seller)?;
+        let entry_price = record.as_ref().map_or(0, |record| record.entry_price);
+
+        // Check balance. The wallet's real balance, not the record's
+        // counters: it reflects transfers in and out, and escrowed tokens
+        // aren't in it.
+        let available = ctx.accounts.seller_token_account.amount;
         require!(token_amount <= available, SamesError::InsufficientBalance);
 
-        // PRICE FLOOR CHECK
-        let current_price = bonding_curve_price(base_price, slope, tokens_sold);
-        require!(current_price >= entry_price, SamesError::SellBelowEntry);
+        // The curve only buys back what it issued: presale allocations would
+        // be priced from a curve position that doesn't exist, paid out of
+        // curve buyers' SOL. The seller's own inventory goes first, then
+        // inventory carried off by transfers, which no record holds. A
+        // record only draws on that for curve tokens it received.
+        let from_inventory = record.as_ref().map_or(0, |record| record.curve_inventory()).min(token_amount);
+        let from_unassigned = token_amount - from_inventory;
+        if let Some(record) = record.as_ref() {
+            require!(from_unassigned <= record.unassigned_allowance(), SamesError::ExceedsCurveInventory);
+        }
+        let unassigned_after = ctx.accounts.launch_pool.curve_tokens_unassigned
+            .checked_sub(from_unassigned).ok_or(SamesError::ExceedsCurveInventory)?;
+        let tokens_sold_after = tokens_sold
+            .checked_sub(token_amount).ok_or(SamesError::ExceedsCurveInventory)?;
 
         // Calculate SOL to return
-        let sol_return_raw = bonding_curve_cost(
-            base_price, slope,
-            tokens_sold.saturating_sub(token_amount),
-            token_amount,
-        ).ok_or(SamesError::MathOverflow)?;
+        let sol_return_raw = bonding_curve_sell_proceeds(base_price, slope, tokens_sold, token_amount)
+            .ok_or(SamesError::MathOverflow)?;
 
-        // Apply 1% fee
-        let fee = sol_return_raw.checked_mul(PLATFORM_FEE_BPS)
-            .ok_or(SamesError::MathOverflow)?
-            .checked_div(10_000)
+        // Apply the platform fee
+        let fee = platform_fee(sol_return_raw, ctx.accounts.protocol_config.params.platform_fee_bps)
             .ok_or(SamesError::MathOverflow)?;
-        let sol_return = sol_return_raw.saturating_sub(fee);
+        let sol_return = sol_return_raw.checked_sub(fee).ok_or(SamesError::MathOverflow)?;
+
+        // PRICE FLOOR CHECK: what the seller actually gets, not the spot
+        // price before the sale.
+        require!(!sale_below_entry(sol_return, token_amount, entry_price), SamesError::SellBelowEntry);
+        let curve_sol_after = ctx.accounts.launch_pool.curve_sol_collected
+            .checked_sub(sol_return_raw).ok_or(SamesError::MathOverflow)?;
 
-        // Transfer SOL from vault to seller
-        **ctx.accounts.vault.to_account_info().try_borrow_mut_lamports()? -= sol_return;
-        **ctx.accounts.seller.to_account_info().try_borrow_mut_lamports()? += sol_return;
+        // Transfer SOL from vault to seller. The vault is system-owned, so
+        // only the system program can debit it.
+        let vault_seeds: &[&[u8]] = &[b"vault", pool_key.as_ref(), &[vault_bump]];
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.seller.to_account_info(),
+                },
+                &[vault_seeds],
+            ),
+            sol_return,
+        )?;
 
         // Update state
         let pool = &mut ctx.accounts.launch_pool;
-        pool.tokens_sold_curve = pool.tokens_sold_curve.saturating_sub(token_amount);
-        pool.curve_sol_collected = pool.curve_sol_collected.saturating_sub(sol_return_raw);
-        let record = &mut ctx.accounts.buyer_record;
-        record.tokens_sold = record.tokens_sold
-            .checked_add(token_amount).ok_or(SamesError::MathOverflow)?;
+        pool.tokens_sold_curve = tokens_sold_after;
+        pool.curve_sol_collected = curve_sol_after;
+        pool.curve_tokens_unassigned = unassigned_after;
+        if let Some(record) = record.as_mut() {
+            record.debit(token_amount, from_inventory).ok_or(SamesError::MathOverflow)?;
+            record.curve_tokens_received = record.curve_tokens_received
+                .checked_sub(from_unassigned).ok_or(SamesError::MathOverflow)?;
+            store_buyer_record(&ctx.accounts.buyer_record, record)?;
+        }
 
         // Burn tokens from seller
         token_2022::burn(
@@ -354,6 +657,44 @@ pub mod sames {
         )?;
 
         msg!("SAMES: Curve sell {} tokens for {} lamports (fee: {})", token_amount, sol_return, fee);
+        let pool = &ctx.accounts.launch_pool;
+        let event = CurveTrade {
+            launch_pool: pool.key(),
+            trader: seller,
+            is_buy: false,
+            token_amount,
+            sol_amount: sol_return,
+            fee,
+            price_after: bonding_curve_price(base_price, slope, pool.tokens_sold_curve),
+            tokens_sold_curve: pool.tokens_sold_curve,
+            curve_sol_collected: pool.curve_sol_collected,
+            entry_price,
+        };
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    /// Hand the curve inventory transfers carried out of `buyer_record`'s
+    /// wallet to the launch's unassigned inventory, where whoever holds
+    /// those tokens now can sell them back. The transfer hook can't, as it
+    /// doesn't write the LaunchPool. Anyone can release any record.
+    pub fn release_curve_inventory(ctx: Context<ReleaseCurveInventory>) -> Result<()> {
+        let record = &mut ctx.accounts.buyer_record;
+        let released = record.unreleased_inventory();
+        record.release_inventory(released).ok_or(SamesError::MathOverflow)?;
+
+        let pool = &mut ctx.accounts.launch_pool;
+        pool.curve_tokens_unassigned = pool.curve_tokens_unassigned
+            .checked_add(released).ok_or(SamesError::MathOverflow)?;
+
+        msg!("SAMES: Released {} tokens of curve inventory from {}", released, record.buyer);
+        let event = CurveInventoryReleased {
+            launch_pool: pool.key(),
+            buyer: record.buyer,
+            released,
+            curve_tokens_unassigned: pool.curve_tokens_unassigned,
+        };
+        emit_cpi!(event);
         Ok(())
     }
 
@@ -364,6 +705,10 @@ pub mod sames {
     /// In production, this would create a Raydium LP and migrate liquidity.
     /// For now, it just flips the status.
     pub fn graduate(ctx: Context<Graduate>) -> Result<()> {
+        require!(
+            !ctx.accounts.protocol_config.is_paused(&ctx.accounts.launch_pool, pause::GRADUATE),
+            SamesError::Paused
+        );
         let pool = &mut ctx.accounts.launch_pool;
         require!(pool.status == LaunchStatus::BondingCurve, SamesError::NotBondingCurve);
         require!(pool.curve_sol_collected >= pool.graduation_threshold, SamesError::NotReadyToGraduate);
@@ -377,6 +722,12 @@ pub mod sames {
         // 4. Remaining vault SOL to creator as profit
 
         msg!("SAMES: 🎓 GRADUATED! Token is now on Raydium. Price floor removed.");
+        let event = Graduated {
+            launch_pool: pool.key(),
+            curve_sol_collected: pool.curve_sol_collected,
+            tokens_sold_curve: pool.tokens_sold_curve,
+        };
+        emit_cpi!(event);
         Ok(())
     }
 
@@ -387,20 +738,687 @@ pub mod sames {
         let pool = &mut ctx.accounts.launch_pool;
         require!(pool.creator == ctx.accounts.authority.key(), SamesError::UnauthorizedCreator);
         require!(new_price > 0, SamesError::ZeroPrice);
+        let old_price = pool.price_lamports;
         pool.price_lamports = new_price;
         msg!("SAMES: Price updated to {}", new_price);
+        let event = PriceUpdated { launch_pool: pool.key(), old_price, new_price };
+        emit_cpi!(event);
         Ok(())
     }
 
     // ═════════════════════════════════════════════════════════════════════
-    // 8. REGISTER MARKET ACCOUNT
+    // 8. MARKET REGISTRY
     // ═════════════════════════════════════════════════════════════════════
-    pub fn register_market(ctx: Context<RegisterMarket>, market_account: Pubkey) -> Result<()> {
+    // Every change also rewrites the hook's extra-account-metas, which carry
+    // the reserve accounts of Cpmm markets. Adding or swapping in a Cpmm
+    // market takes its two reserves as remaining accounts, to check their
+    // mints.
+    pub fn add_market(ctx: Context<AddMarket>, market: MarketEntry) -> Result<()> {
         let registry = &mut ctx.accounts.market_registry;
         require!(registry.authority == ctx.accounts.authority.key(), SamesError::UnauthorizedCreator);
-        require!(registry.market_accounts.len() < MarketRegistry::MAX_MARKETS, SamesError::InvalidMarket);
-        registry.market_accounts.push(market_account);
-        msg!("SAMES: Registered market {}", market_account);
+        require!(market.is_valid(), SamesError::InvalidMarket);
+        hook::check_market_reserves(&market, ctx.remaining_accounts, &ctx.accounts.launch_pool.mint)?;
+        require!(registry.find_market(&market.address).is_none(), SamesError::MarketAlreadyRegistered);
+        require!(registry.markets.len() < MarketRegistry::MAX_MARKETS, SamesError::MarketRegistryFull);
+        if market.kind == MarketKind::Cpmm {
+            require!(registry.cpmm_count() < MarketRegistry::MAX_CPMM_MARKETS, SamesError::MarketRegistryFull);
+        }
+        registry.markets.push(market);
+
+        hook::sync_extra_account_meta_list(
+            &ctx.accounts.extra_account_meta_list.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.market_registry,
+        )?;
+        msg!("SAMES: Registered {:?} market {}", market.kind, market.address);
+        let event = MarketRegistered {
+            launch_pool: ctx.accounts.launch_pool.key(),
+            address: market.address,
+            kind: market.kind,
+            token_reserve: market.token_reserve,
+            sol_reserve: market.sol_reserve,
+            replaced: None,
+        };
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    pub fn remove_market(ctx: Context<RemoveMarket>, address: Pubkey) -> Result<()> {
+        let registry = &mut ctx.accounts.market_registry;
+        require!(registry.authority == ctx.accounts.authority.key(), SamesError::UnauthorizedCreator);
+        let index = registry.markets.iter().position(|m| m.address == address)
+            .ok_or(SamesError::MarketNotFound)?;
+        registry.markets.remove(index);
+
+        hook::sync_extra_account_meta_list(
+            &ctx.accounts.extra_account_meta_list.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.market_registry,
+        )?;
+        msg!("SAMES: Removed market {}", address);
+        let event = MarketRemoved { launch_pool: ctx.accounts.launch_pool.key(), address };
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    /// Swap the entry at `address` for `market`, e.g. when a pool migrates
+    /// or its reserve accounts change.
+    pub fn replace_market(ctx: Context<ReplaceMarket>, address: Pubkey, market: MarketEntry) -> Result<()> {
+        let registry = &mut ctx.accounts.market_registry;
+        require!(registry.authority == ctx.accounts.authority.key(), SamesError::UnauthorizedCreator);
+        require!(market.is_valid(), SamesError::InvalidMarket);
+        hook::check_market_reserves(&market, ctx.remaining_accounts, &ctx.accounts.launch_pool.mint)?;
+        let index = registry.markets.iter().position(|m| m.address == address)
+            .ok_or(SamesError::MarketNotFound)?;
+        if market.address != address {
+            require!(registry.find_market(&market.address).is_none(), SamesError::MarketAlreadyRegistered);
+        }
+        let old = registry.markets[index];
+        if market.kind == MarketKind::Cpmm && old.kind != MarketKind::Cpmm {
+            require!(registry.cpmm_count() < MarketRegistry::MAX_CPMM_MARKETS, SamesError::MarketRegistryFull);
+        }
+        registry.markets[index] = market;
+
+        hook::sync_extra_account_meta_list(
+            &ctx.accounts.extra_account_meta_list.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.market_registry,
+        )?;
+        msg!("SAMES: Replaced market {} with {:?} market {}", address, market.kind, market.address);
+        let event = MarketRegistered {
+            launch_pool: ctx.accounts.launch_pool.key(),
+            address: market.address,
+            kind: market.kind,
+            token_reserve: market.token_reserve,
+            sol_reserve: market.sol_reserve,
+            replaced: Some(address),
+        };
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    // ═════════════════════════════════════════════════════════════════════
+    // 9. TRANSFER HOOK
+    // ═════════════════════════════════════════════════════════════════════
+    pub fn initialize_extra_account_meta_list(ctx: Context<InitializeExtraAccountMetaList>) -> Result<()> {
+        hook::initialize_extra_account_meta_list(ctx)
+    }
+
+    /// Invoked by Token-2022 on every transfer of a SAMES mint.
+    #[interface(spl_transfer_hook_interface::execute)]
+    pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+        hook::handler(ctx, amount)
+    }
+
+    /// Open an empty BuyerRecord for `owner`, with rent from `payer`. The
+    /// hook has no fee payer, so a wallet must have a record before tokens
+    /// carrying a price floor can be sent to it. The rent goes back to
+    /// `payer` when the record is closed.
+    pub fn create_buyer_record(ctx: Context<CreateBuyerRecord>) -> Result<()> {
+        let rent = ctx.accounts.buyer_record.to_account_info().lamports();
+        let record = &mut ctx.accounts.buyer_record;
+        record.launch_pool = ctx.accounts.launch_pool.key();
+        record.buyer = ctx.accounts.owner.key();
+        record.sol_deposited = 0;
+        record.entry_price = 0;
+        record.tokens_allocated = 0;
+        record.tokens_sold = 0;
+        record.curve_sol_spent = 0;
+        record.curve_tokens_bought = 0;
+        record.bump = ctx.bumps.buyer_record;
+        record.tokens_received = 0;
+        record.tokens_sent = 0;
+        record.tokens_vesting = 0;
+        record.rent_payer = ctx.accounts.payer.key();
+        record.rent_paid = rent;
+        record.curve_tokens_received = 0;
+
+        msg!("SAMES: Opened BuyerRecord of {}", record.buyer);
+        let event = BuyerRecordCreated {
+            launch_pool: record.launch_pool,
+            buyer: record.buyer,
+            rent_payer: record.rent_payer,
+        };
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    // ═════════════════════════════════════════════════════════════════════
+    // 10. PROTOCOL CONFIG
+    // ═════════════════════════════════════════════════════════════════════
+    /// One-time setup of the protocol singleton; the program's upgrade
+    /// authority becomes its admin.
+    pub fn initialize_protocol_config(
+        ctx: Context<InitializeProtocolConfig>,
+        params: ProtocolParams,
+    ) -> Result<()> {
+        require!(ProtocolBounds::LIMITS.contains(&params), SamesError::InvalidProtocolParams);
+        let config = &mut ctx.accounts.protocol_config;
+        config.admin = ctx.accounts.admin.key();
+        config.pending_admin = Pubkey::default();
+        config.bump = ctx.bumps.protocol_config;
+        config.params = params;
+        config.bounds = ProtocolBounds::LIMITS;
+        config.guardian = ctx.accounts.admin.key();
+        config.paused = 0;
+        config.dex_programs = Vec::new();
+        msg!("SAMES: Protocol config initialized. Admin {}, {:?}", config.admin, params);
+        let event = ProtocolConfigInitialized { admin: config.admin, params };
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    /// Replace the protocol parameters. Launches already created keep the
+    /// slope, threshold and presale window they were created with.
+    pub fn update_protocol_config(ctx: Context<UpdateProtocolConfig>, params: ProtocolParams) -> Result<()> {
+        let config = &mut ctx.accounts.protocol_config;
+        require!(config.bounds.contains(&params), SamesError::InvalidProtocolParams);
+        config.params = params;
+        msg!("SAMES: Protocol params updated: {:?}", params);
+        let event = ProtocolParamsUpdated { params };
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    /// Replace the ranges `update_protocol_config` accepts. They must stay
+    /// inside `ProtocolBounds::LIMITS` and hold the current parameters.
+    pub fn update_protocol_bounds(ctx: Context<UpdateProtocolConfig>, bounds: ProtocolBounds) -> Result<()> {
+        let config = &mut ctx.accounts.protocol_config;
+        require!(bounds.is_valid() && bounds.contains(&config.params), SamesError::InvalidProtocolBounds);
+        config.bounds = bounds;
+        msg!("SAMES: Protocol bounds updated: {:?}", bounds);
+        let event = ProtocolBoundsUpdated { bounds };
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    /// First step of an admin handover: nominate `new_admin`. Nominating
+    /// `Pubkey::default()` cancels a pending handover.
+    pub fn propose_admin(ctx: Context<UpdateProtocolConfig>, new_admin: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.protocol_config;
+        config.pending_admin = new_admin;
+        msg!("SAMES: Admin handover proposed to {}", new_admin);
+        let event = AdminProposed { admin: config.admin, pending_admin: new_admin };
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    /// Second step: the nominee accepts and becomes admin.
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.protocol_config;
+        require!(config.pending_admin != Pubkey::default(), SamesError::UnauthorizedAdmin);
+        require_keys_eq!(config.pending_admin, ctx.accounts.new_admin.key(), SamesError::UnauthorizedAdmin);
+        let previous = config.admin;
+        config.admin = config.pending_admin;
+        config.pending_admin = Pubkey::default();
+        msg!("SAMES: Admin changed from {} to {}", previous, config.admin);
+        let event = AdminChanged { previous, admin: config.admin };
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    /// Allowlist a DEX: token accounts owned by the PDA that
+    /// `authority_seeds` (bump included) derive from `program_id` are
+    /// markets. A DEX with several pool authorities gets an entry for each.
+    pub fn add_dex_program(
+        ctx: Context<UpdateProtocolConfig>,
+        program_id: Pubkey,
+        authority_seeds: Vec<Vec<u8>>,
+    ) -> Result<()> {
+        require!(
+            program_id != system_program::ID
+                && program_id != token_2022::ID
+                && program_id != anchor_spl::token::ID
+                && program_id != crate::ID,
+            SamesError::InvalidDexProgram
+        );
+        require!(!authority_seeds.is_empty(), SamesError::InvalidDexProgram);
+        let seeds: Vec<&[u8]> = authority_seeds.iter().map(Vec::as_slice).collect();
+        let authority = Pubkey::create_program_address(&seeds, &program_id)
+            .map_err(|_| SamesError::InvalidDexProgram)?;
+
+        let config = &mut ctx.accounts.protocol_config;
+        let dex = DexProgram { program_id, authority };
+        require!(!config.dex_programs.contains(&dex), SamesError::InvalidDexProgram);
+        require!(config.dex_programs.len() < ProtocolConfig::MAX_DEX_PROGRAMS, SamesError::DexAllowlistFull);
+        config.dex_programs.push(dex);
+
+        msg!("SAMES: Allowlisted DEX {} (authority {})", program_id, authority);
+        let event = DexProgramAdded { program_id, authority };
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    /// Appoint the key that can pause and unpause.
+    pub fn set_guardian(ctx: Context<UpdateProtocolConfig>, guardian: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.protocol_config;
+        let previous = config.guardian;
+        config.guardian = guardian;
+        msg!("SAMES: Guardian set to {}", guardian);
+        let event = GuardianSet { previous, guardian };
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    /// Replace the protocol-wide pause mask (`pause` bits); 0 resumes everything.
+    pub fn set_protocol_pause(ctx: Context<SetProtocolPause>, flags: u16) -> Result<()> {
+        require!(flags & !pause::ALL == 0, SamesError::InvalidPauseFlags);
+        ctx.accounts.protocol_config.paused = flags;
+        msg!("SAMES: Protocol pause flags set to {:#06b}", flags);
+        let event = PauseSet { launch_pool: None, flags };
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    /// Replace one launch's pause mask (`pause` bits); 0 resumes everything.
+    pub fn set_launch_pause(ctx: Context<SetLaunchPause>, flags: u16) -> Result<()> {
+        require!(flags & !pause::ALL == 0, SamesError::InvalidPauseFlags);
+        let pool = &mut ctx.accounts.launch_pool;
+        pool.paused = flags;
+        msg!("SAMES: Launch {} pause flags set to {:#06b}", pool.mint, flags);
+        let event = PauseSet { launch_pool: Some(pool.key()), flags };
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    /// Drop every allowlist entry of `program_id`.
+    pub fn remove_dex_program(ctx: Context<UpdateProtocolConfig>, program_id: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.protocol_config;
+        let before = config.dex_programs.len();
+        config.dex_programs.retain(|dex| dex.program_id != program_id);
+        require!(config.dex_programs.len() < before, SamesError::InvalidDexProgram);
+        msg!("SAMES: Removed DEX {} from the allowlist", program_id);
+        let event = DexProgramRemoved { program_id };
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    // ═════════════════════════════════════════════════════════════════════
+    // 11. CANCEL, REFUND & CLOSE
+    // ═════════════════════════════════════════════════════════════════════
+    /// Creator cancels the launch: during the presale, or after it as long
+    /// as nobody has traded on the curve. Deposits become refundable
+    /// through `claim_refund`, and the unclaimed creator allocation is burned.
+    pub fn cancel_launch(ctx: Context<CancelLaunch>) -> Result<()> {
+        let pool = &ctx.accounts.launch_pool;
+        require!(pool.creator == ctx.accounts.creator.key(), SamesError::UnauthorizedCreator);
+        require!(pool.is_cancellable(), SamesError::NotCancellable);
+
+        // The creator allocation is only minted once the curve starts
+        let unclaimed = pool.creator_allocation().saturating_sub(pool.creator_tokens_claimed);
+        if pool.status == LaunchStatus::BondingCurve && unclaimed > 0 {
+            let escrow = ctx.accounts.creator_escrow.as_ref().ok_or(SamesError::MissingTokenAccount)?;
+            let mint_key = pool.mint;
+            let pool_seeds: &[&[u8]] = &[b"launch_pool", mint_key.as_ref(), &[pool.bump]];
+            token_2022::burn(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_2022::Burn {
+                        mint: ctx.accounts.mint.to_account_info(),
+                        from: escrow.to_account_info(),
+                        authority: ctx.accounts.launch_pool.to_account_info(),
+                    },
+                    &[pool_seeds],
+                ),
+                unclaimed,
+            )?;
+        }
+
+        let pool = &mut ctx.accounts.launch_pool;
+        pool.status = LaunchStatus::Closed;
+        pool.refund_deadline = Clock::get()?.unix_timestamp
+            .checked_add(LaunchPool::REFUND_WINDOW).ok_or(SamesError::MathOverflow)?;
+        msg!(
+            "SAMES: Launch cancelled. {} lamports refundable to {} buyers until {}",
+            pool.total_sol_collected, pool.buyer_count, pool.refund_deadline
+        );
+        let event = LaunchCancelled {
+            launch_pool: pool.key(),
+            refundable_lamports: pool.total_sol_collected,
+            refund_deadline: pool.refund_deadline,
+        };
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    /// Refund a buyer's presale deposit from the vault after cancellation.
+    /// A finalized allocation is burned first: the part still vesting from
+    /// escrow, the part in the buyer's wallet from `buyer_token_account`, as
+    /// much of it as is still there. The refund is the burned share of the
+    /// allocation; the rest stays claimable once the tokens come back.
+    pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
+        let pool = &ctx.accounts.launch_pool;
+        require!(pool.status == LaunchStatus::Closed, SamesError::NotClosed);
+        require!(
+            !ctx.accounts.protocol_config.is_paused(pool, pause::REFUND),
+            SamesError::Paused
+        );
+
+        let record = &ctx.accounts.buyer_record;
+        let in_wallet = match record.tokens_allocated - record.tokens_vesting {
+            0 => 0,
+            allocated => {
+                let from = ctx.accounts.buyer_token_account.as_ref().ok_or(SamesError::MissingTokenAccount)?;
+                allocated.min(from.amount)
+            }
+        };
+        let tokens_burned = in_wallet + record.tokens_vesting;
+        let refund = if record.tokens_allocated == 0 {
+            record.sol_deposited
+        } else {
+            (record.sol_deposited as u128 * tokens_burned as u128 / record.tokens_allocated as u128) as u64
+        };
+        require!(refund > 0, SamesError::NothingToRefund);
+
+        let mint_key = pool.mint;
+        let pool_seeds: &[&[u8]] = &[b"launch_pool", mint_key.as_ref(), &[pool.bump]];
+        if in_wallet > 0 {
+            let from = ctx.accounts.buyer_token_account.as_ref().ok_or(SamesError::MissingTokenAccount)?;
+            token_2022::burn(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_2022::Burn {
+                        mint: ctx.accounts.mint.to_account_info(),
+                        from: from.to_account_info(),
+                        authority: ctx.accounts.buyer.to_account_info(),
+                    },
+                ),
+                in_wallet,
+            )?;
+        }
+        if record.tokens_vesting > 0 {
+            let escrow = ctx.accounts.vesting_escrow.as_ref().ok_or(SamesError::MissingTokenAccount)?;
+            token_2022::burn(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_2022::Burn {
+                        mint: ctx.accounts.mint.to_account_info(),
+                        from: escrow.to_account_info(),
+                        authority: ctx.accounts.launch_pool.to_account_info(),
+                    },
+                    &[pool_seeds],
+                ),
+                record.tokens_vesting,
+            )?;
+        }
+
+        let pool_key = pool.key();
+        let vault_seeds: &[&[u8]] = &[b"vault", pool_key.as_ref(), &[pool.vault_bump]];
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.buyer.to_account_info(),
+                },
+                &[vault_seeds],
+            ),
+            refund,
+        )?;
+
+        let record = &mut ctx.accounts.buyer_record;
+        record.sol_deposited -= refund;
+        record.tokens_allocated -= tokens_burned;
+        record.tokens_vesting = 0;
+        let pool = &mut ctx.accounts.launch_pool;
+        pool.total_sol_collected = pool.total_sol_collected
+            .checked_sub(refund).ok_or(SamesError::MathOverflow)?;
+
+        msg!("SAMES: Refunded {} lamports to {}", refund, ctx.accounts.buyer.key());
+        let event = RefundClaimed {
+            launch_pool: pool.key(),
+            buyer: ctx.accounts.buyer.key(),
+            sol_amount: refund,
+            tokens_burned,
+        };
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    /// Return the rent of a finished launch's accounts to the creator, who
+    /// paid for all of them. BuyerRecords are closed separately by their
+    /// owners with `close_buyer_record`.
+    ///
+    /// Closed: once every deposit is refunded, or `refund_deadline` has
+    /// passed, sweep what's left in the vault and close every launch
+    /// account. Allocations of buyers who never claimed their refund are
+    /// burned from the vesting escrow; their deposits go to the creator.
+    ///
+    /// Graduated: the mint keeps calling the hook, which needs the
+    /// LaunchPool, registry and meta list on every transfer, so those stay.
+    /// The escrows are closed once they hold no tokens, and unless the
+    /// floor outlives graduation the registered markets are dropped, which
+    /// shrinks the registry and the meta list. The vault keeps the curve's SOL.
+    pub fn close_launch(ctx: Context<CloseLaunch>) -> Result<()> {
+        let pool = &ctx.accounts.launch_pool;
+        require!(pool.creator == ctx.accounts.creator.key(), SamesError::UnauthorizedCreator);
+        let graduated = match pool.status {
+            LaunchStatus::Closed => {
+                require!(pool.are_refunds_settled(Clock::get()?.unix_timestamp), SamesError::RefundsOutstanding);
+                false
+            }
+            LaunchStatus::Graduated => true,
+            LaunchStatus::Presale | LaunchStatus::BondingCurve => return err!(SamesError::NotClosed),
+        };
+        let creator = ctx.accounts.creator.to_account_info();
+
+        let escrows = [ctx.accounts.vesting_escrow.as_ref(), ctx.accounts.creator_escrow.as_ref()];
+        for escrow in escrows.into_iter().flatten() {
+            require!(!graduated || escrow.amount == 0, SamesError::EscrowNotEmpty);
+            let mint_key = pool.mint;
+            let pool_seeds: &[&[u8]] = &[b"launch_pool", mint_key.as_ref(), &[pool.bump]];
+            if escrow.amount > 0 {
+                token_2022::burn(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        token_2022::Burn {
+                            mint: ctx.accounts.mint.to_account_info(),
+                            from: escrow.to_account_info(),
+                            authority: ctx.accounts.launch_pool.to_account_info(),
+                        },
+                        &[pool_seeds],
+                    ),
+                    escrow.amount,
+                )?;
+            }
+            token_2022::close_account(CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_2022::CloseAccount {
+                    account: escrow.to_account_info(),
+                    destination: creator.clone(),
+                    authority: ctx.accounts.launch_pool.to_account_info(),
+                },
+                &[pool_seeds],
+            ))?;
+        }
+
+        if graduated {
+            if !pool.is_floor_active() && !ctx.accounts.market_registry.markets.is_empty() {
+                let registry = &mut ctx.accounts.market_registry;
+                registry.markets.clear();
+                let info = registry.to_account_info();
+                info.realloc(MarketRegistry::space(0), false)?;
+                let refund = info.lamports().saturating_sub(Rent::get()?.minimum_balance(info.data_len()));
+                **info.try_borrow_mut_lamports()? -= refund;
+                **creator.try_borrow_mut_lamports()? += refund;
+                hook::sync_extra_account_meta_list(
+                    &ctx.accounts.extra_account_meta_list.to_account_info(),
+                    &creator,
+                    &ctx.accounts.system_program.to_account_info(),
+                    &ctx.accounts.market_registry,
+                )?;
+            }
+            msg!("SAMES: Graduated launch cleaned up");
+            let event = LaunchClosed { launch_pool: pool.key(), graduated, swept_lamports: 0 };
+            emit_cpi!(event);
+            return Ok(());
+        }
+
+        let pool_key = pool.key();
+        let vault_seeds: &[&[u8]] = &[b"vault", pool_key.as_ref(), &[pool.vault_bump]];
+        let leftover = ctx.accounts.vault.lamports();
+        if leftover > 0 {
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.vault.to_account_info(),
+                        to: creator.clone(),
+                    },
+                    &[vault_seeds],
+                ),
+                leftover,
+            )?;
+        }
+
+        // The hook's account list, if it was ever created
+        let meta_list = ctx.accounts.extra_account_meta_list.to_account_info();
+        if *meta_list.owner == crate::ID {
+            close_program_account(&meta_list, &creator)?;
+        }
+        close_program_account(&ctx.accounts.market_registry.to_account_info(), &creator)?;
+        close_program_account(&ctx.accounts.launch_pool.to_account_info(), &creator)?;
+
+        msg!("SAMES: Launch closed. {} lamports swept from the vault", leftover);
+        let event = LaunchClosed { launch_pool: pool_key, graduated, swept_lamports: leftover };
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    /// Close a BuyerRecord the program no longer needs (see
+    /// `BuyerRecord::is_closable`). The rent goes back to whoever paid it:
+    /// `rent_paid` to the record's `rent_payer`, the rest to the buyer. Once
+    /// the launch itself is closed every record can go.
+    pub fn close_buyer_record(ctx: Context<CloseBuyerRecord>) -> Result<()> {
+        let record = &ctx.accounts.buyer_record;
+        let pool_info = ctx.accounts.launch_pool.to_account_info();
+        let launch_live = *pool_info.owner == crate::ID && !pool_info.data_is_empty();
+        if launch_live {
+            let pool = LaunchPool::try_deserialize(&mut &pool_info.try_borrow_data()?[..])?;
+            require!(record.is_closable(&pool), SamesError::BuyerRecordInUse);
+        }
+
+        let info = record.to_account_info();
+        let rent_refunded = record.rent_paid.min(info.lamports());
+        **info.try_borrow_mut_lamports()? -= rent_refunded;
+        **ctx.accounts.rent_payer.try_borrow_mut_lamports()? += rent_refunded;
+        msg!(
+            "SAMES: Closed BuyerRecord of {}, {} lamports of rent to {}",
+            record.buyer, rent_refunded, record.rent_payer
+        );
+        let event = BuyerRecordClosed {
+            launch_pool: record.launch_pool,
+            buyer: record.buyer,
+            rent_payer: record.rent_payer,
+            rent_refunded,
+        };
+        emit_cpi!(event);
+        ctx.accounts.buyer_record.close(ctx.accounts.buyer.to_account_info())
+    }
+
+    // ═════════════════════════════════════════════════════════════════════
+    // 12. MIGRATIONS
+    // ═════════════════════════════════════════════════════════════════════
+
+    /// Rewrite a BuyerRecord stored with whole-lamport entry prices (see
+    /// `LegacyBuyerRecord`) in the current, fixed-point layout. Until then
+    /// the record can't be loaded, so its holder can neither trade on the
+    /// curve nor transfer. Anyone can migrate any record; the payer covers
+    /// the rent of the extra space.
+    pub fn migrate_buyer_record(ctx: Context<MigrateBuyerRecord>) -> Result<()> {
+        let info = ctx.accounts.buyer_record.to_account_info();
+        let legacy = {
+            let data = info.try_borrow_data()?;
+            require!(data.len() != BuyerRecord::MAX_SIZE, SamesError::BuyerRecordMigrated);
+            require!(data.len() == LegacyBuyerRecord::SIZE, SamesError::InvalidBuyerRecord);
+            require!(data[..8] == BuyerRecord::DISCRIMINATOR, SamesError::InvalidBuyerRecord);
+            LegacyBuyerRecord::deserialize(&mut &data[8..]).map_err(|_| SamesError::InvalidBuyerRecord)?
+        };
+        let (expected, _) = buyer_record_address(&legacy.launch_pool, &legacy.buyer);
+        require_keys_eq!(info.key(), expected, SamesError::InvalidBuyerRecord);
+
+        let rent = Rent::get()?.minimum_balance(BuyerRecord::MAX_SIZE);
+        let top_up = rent.saturating_sub(info.lamports());
+        if top_up > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: info.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        info.realloc(BuyerRecord::MAX_SIZE, false)?;
+
+        let mut record = legacy.migrate();
+        if top_up > 0 {
+            record.rent_payer = ctx.accounts.payer.key();
+            record.rent_paid = top_up;
+        }
+        store_buyer_record(&info, &record)?;
+        msg!("SAMES: Migrated BuyerRecord of {}, entry price {}", record.buyer, record.entry_price);
+        let event = BuyerRecordMigrated {
+            launch_pool: record.launch_pool,
+            buyer: record.buyer,
+            entry_price: record.entry_price,
+        };
+        emit_cpi!(event);
+        Ok(())
+    }
+
+    /// Rewrite a MarketRegistry stored as a list of addresses (see
+    /// `LegacyMarketRegistry`) in the current layout. Until then the
+    /// registry can't be loaded, so no transfer of the launch's token goes
+    /// through. Anyone can migrate any registry, but if it grows, the extra
+    /// rent must come from its authority, the creator, who gets all of the
+    /// registry's rent back from `close_launch`.
+    pub fn migrate_market_registry(ctx: Context<MigrateMarketRegistry>) -> Result<()> {
+        let info = ctx.accounts.market_registry.to_account_info();
+        let legacy = {
+            let data = info.try_borrow_data()?;
+            require!(data.get(..8) == Some(&MarketRegistry::DISCRIMINATOR[..]), SamesError::InvalidMarketRegistry);
+            require!(data.len() == LegacyMarketRegistry::SIZE, SamesError::MarketRegistryMigrated);
+            LegacyMarketRegistry::deserialize(&mut &data[8..]).map_err(|_| SamesError::InvalidMarketRegistry)?
+        };
+        let expected = Pubkey::create_program_address(
+            &[b"market_registry", legacy.launch_pool.as_ref(), &[legacy.bump]],
+            &crate::ID,
+        )
+        .map_err(|_| SamesError::InvalidMarketRegistry)?;
+        require_keys_eq!(info.key(), expected, SamesError::InvalidMarketRegistry);
+
+        let registry = legacy.migrate();
+        let space = MarketRegistry::space(registry.markets.len());
+        let rent = Rent::get()?.minimum_balance(space);
+        let top_up = rent.saturating_sub(info.lamports());
+        if top_up > 0 {
+            require_keys_eq!(ctx.accounts.payer.key(), registry.authority, SamesError::UnauthorizedCreator);
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: info.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        info.realloc(space, false)?;
+
+        registry.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        msg!("SAMES: Migrated MarketRegistry of {}, {} markets", registry.launch_pool, registry.markets.len());
+        let event = MarketRegistryMigrated {
+            launch_pool: registry.launch_pool,
+            markets: registry.markets.iter().map(|market| market.address).collect(),
+        };
+        emit_cpi!(event);
         Ok(())
     }
 }
@@ -409,8 +1427,13 @@ pub mod sames {
 // ACCOUNT CONTEXTS
 // ═════════════════════════════════════════════════════════════════════════════
 
+#[event_cpi]
 #[derive(Accounts)]
-#[instruction(token_name: String, token_symbol: String, total_supply: u64, price_lamports: u64)]
+#[instruction(
+    token_name: String, token_symbol: String, total_supply: u64, price_lamports: u64,
+    floor_after_graduation: bool, presale_vesting: VestingSchedule,
+    creator_allocation_bps: u16, creator_vesting: VestingSchedule,
+)]
 pub struct CreateLaunch<'info> {
     #[account(mut)]
     pub creator: Signer<'info>,
@@ -425,13 +1448,16 @@ pub struct CreateLaunch<'info> {
     #[account(mut, seeds = [b"vault", launch_pool.key().as_ref()], bump)]
     pub vault: SystemAccount<'info>,
     #[account(
-        init, payer = creator, space = MarketRegistry::MAX_SIZE,
+        init, payer = creator, space = MarketRegistry::space(0),
         seeds = [b"market_registry", launch_pool.key().as_ref()], bump,
     )]
     pub market_registry: Account<'info, MarketRegistry>,
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(sol_amount: u64)]
 pub struct BuyPresale<'info> {
@@ -448,8 +1474,11 @@ pub struct BuyPresale<'info> {
     )]
     pub buyer_record: Account<'info, BuyerRecord>,
     pub system_program: Program<'info, System>,
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct FinalizeLaunch<'info> {
     #[account(mut)]
@@ -463,18 +1492,85 @@ pub struct FinalizeLaunch<'info> {
         bump = buyer_record.bump,
     )]
     pub buyer_record: Account<'info, BuyerRecord>,
-    #[account(mut)]
+    #[account(
+        mut,
+        associated_token::mint = mint, associated_token::authority = buyer_record.buyer,
+        associated_token::token_program = token_program,
+    )]
+    pub buyer_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed, payer = creator,
+        seeds = [b"vesting_escrow", launch_pool.key().as_ref()], bump,
+        token::mint = mint, token::authority = launch_pool, token::token_program = token_program,
+    )]
+    pub vesting_escrow: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    pub buyer: Signer<'info>,
+    #[account(seeds = [b"launch_pool", launch_pool.mint.as_ref()], bump = launch_pool.bump)]
+    pub launch_pool: Account<'info, LaunchPool>,
+    #[account(mut, constraint = mint.key() == launch_pool.mint @ SamesError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, MintAccount>,
+    #[account(
+        mut, seeds = [b"buyer_record", launch_pool.key().as_ref(), buyer.key().as_ref()],
+        bump = buyer_record.bump,
+        has_one = launch_pool, has_one = buyer,
+    )]
+    pub buyer_record: Account<'info, BuyerRecord>,
+    #[account(
+        mut, seeds = [b"vesting_escrow", launch_pool.key().as_ref()], bump,
+        token::mint = mint, token::authority = launch_pool,
+    )]
+    pub vesting_escrow: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint, token::authority = buyer)]
     pub buyer_token_account: InterfaceAccount<'info, TokenAccount>,
     pub token_program: Program<'info, Token2022>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct StartBondingCurve<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(mut, seeds = [b"launch_pool", launch_pool.mint.as_ref()], bump = launch_pool.bump)]
+    pub launch_pool: Account<'info, LaunchPool>,
+    #[account(mut, constraint = mint.key() == launch_pool.mint @ SamesError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, MintAccount>,
+    /// Holds the creator allocation while it vests.
+    #[account(
+        init_if_needed, payer = creator,
+        seeds = [b"creator_escrow", launch_pool.key().as_ref()], bump,
+        token::mint = mint, token::authority = launch_pool, token::token_program = token_program,
+    )]
+    pub creator_escrow: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimCreatorAllocation<'info> {
     pub creator: Signer<'info>,
     #[account(mut, seeds = [b"launch_pool", launch_pool.mint.as_ref()], bump = launch_pool.bump)]
     pub launch_pool: Account<'info, LaunchPool>,
+    #[account(mut, constraint = mint.key() == launch_pool.mint @ SamesError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, MintAccount>,
+    #[account(
+        mut, seeds = [b"creator_escrow", launch_pool.key().as_ref()], bump,
+        token::mint = mint, token::authority = launch_pool,
+    )]
+    pub creator_escrow: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint, token::authority = creator)]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(sol_amount: u64)]
 pub struct BuyCurve<'info> {
@@ -492,12 +1588,19 @@ pub struct BuyCurve<'info> {
         seeds = [b"buyer_record", launch_pool.key().as_ref(), buyer.key().as_ref()], bump,
     )]
     pub buyer_record: Account<'info, BuyerRecord>,
-    #[account(mut)]
+    #[account(
+        mut,
+        associated_token::mint = mint, associated_token::authority = buyer,
+        associated_token::token_program = token_program,
+    )]
     pub buyer_token_account: InterfaceAccount<'info, TokenAccount>,
     pub token_program: Program<'info, Token2022>,
     pub system_program: Program<'info, System>,
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(token_amount: u64)]
 pub struct SellCurve<'info> {
@@ -510,18 +1613,36 @@ pub struct SellCurve<'info> {
     /// CHECK: SOL vault PDA.
     #[account(mut, seeds = [b"vault", launch_pool.key().as_ref()], bump = launch_pool.vault_bump)]
     pub vault: SystemAccount<'info>,
+    /// BuyerRecord of the seller, if any: holders who bought on a market
+    /// don't have one.
+    /// CHECK: validated by `resolve_buyer_record` in the handler.
+    #[account(mut)]
+    pub buyer_record: UncheckedAccount<'info>,
     #[account(
         mut,
-        seeds = [b"buyer_record", launch_pool.key().as_ref(), seller.key().as_ref()],
-        bump = buyer_record.bump,
-        constraint = buyer_record.buyer == seller.key() @ SamesError::NoBuyerRecord,
+        associated_token::mint = mint, associated_token::authority = seller,
+        associated_token::token_program = token_program,
     )]
-    pub buyer_record: Account<'info, BuyerRecord>,
-    #[account(mut)]
     pub seller_token_account: InterfaceAccount<'info, TokenAccount>,
     pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ReleaseCurveInventory<'info> {
+    #[account(mut, address = buyer_record.launch_pool)]
+    pub launch_pool: Account<'info, LaunchPool>,
+    #[account(
+        mut, seeds = [b"buyer_record", launch_pool.key().as_ref(), buyer_record.buyer.as_ref()],
+        bump = buyer_record.bump,
+    )]
+    pub buyer_record: Account<'info, BuyerRecord>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Graduate<'info> {
     #[account(mut)]
@@ -531,8 +1652,11 @@ pub struct Graduate<'info> {
     /// CHECK: SOL vault PDA.
     #[account(mut, seeds = [b"vault", launch_pool.key().as_ref()], bump = launch_pool.vault_bump)]
     pub vault: SystemAccount<'info>,
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct UpdatePrice<'info> {
     pub authority: Signer<'info>,
@@ -540,9 +1664,261 @@ pub struct UpdatePrice<'info> {
     pub launch_pool: Account<'info, LaunchPool>,
 }
 
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AddMarket<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"launch_pool", launch_pool.mint.as_ref()], bump = launch_pool.bump)]
+    pub launch_pool: Account<'info, LaunchPool>,
+    #[account(
+        mut, seeds = [b"market_registry", launch_pool.key().as_ref()], bump = market_registry.bump,
+        has_one = launch_pool,
+        realloc = MarketRegistry::space(market_registry.markets.len() + 1),
+        realloc::payer = authority, realloc::zero = false,
+    )]
+    pub market_registry: Account<'info, MarketRegistry>,
+    /// CHECK: the hook's extra-account-metas PDA, rewritten by the handler.
+    #[account(mut, seeds = [b"extra-account-metas", launch_pool.mint.as_ref()], bump)]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RemoveMarket<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"launch_pool", launch_pool.mint.as_ref()], bump = launch_pool.bump)]
+    pub launch_pool: Account<'info, LaunchPool>,
+    #[account(
+        mut, seeds = [b"market_registry", launch_pool.key().as_ref()], bump = market_registry.bump,
+        has_one = launch_pool,
+        realloc = MarketRegistry::space(market_registry.markets.len().saturating_sub(1)),
+        realloc::payer = authority, realloc::zero = false,
+    )]
+    pub market_registry: Account<'info, MarketRegistry>,
+    /// CHECK: the hook's extra-account-metas PDA, rewritten by the handler.
+    #[account(mut, seeds = [b"extra-account-metas", launch_pool.mint.as_ref()], bump)]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
 #[derive(Accounts)]
-pub struct RegisterMarket<'info> {
+pub struct ReplaceMarket<'info> {
+    #[account(mut)]
     pub authority: Signer<'info>,
-    #[account(mut, seeds = [b"market_registry", market_registry.launch_pool.as_ref()], bump = market_registry.bump)]
+    #[account(seeds = [b"launch_pool", launch_pool.mint.as_ref()], bump = launch_pool.bump)]
+    pub launch_pool: Account<'info, LaunchPool>,
+    #[account(
+        mut, seeds = [b"market_registry", launch_pool.key().as_ref()], bump = market_registry.bump,
+        has_one = launch_pool,
+    )]
+    pub market_registry: Account<'info, MarketRegistry>,
+    /// CHECK: the hook's extra-account-metas PDA, rewritten by the handler.
+    #[account(mut, seeds = [b"extra-account-metas", launch_pool.mint.as_ref()], bump)]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeProtocolConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        init, payer = admin, space = ProtocolConfig::MAX_SIZE,
+        seeds = [b"protocol_config"], bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(
+        seeds = [crate::ID.as_ref()], bump, seeds::program = anchor_lang::solana_program::bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ SamesError::UnauthorizedAdmin,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateProtocolConfig<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut, seeds = [b"protocol_config"], bump = protocol_config.bump,
+        has_one = admin @ SamesError::UnauthorizedAdmin,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub new_admin: Signer<'info>,
+    #[account(mut, seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetProtocolPause<'info> {
+    pub guardian: Signer<'info>,
+    #[account(
+        mut, seeds = [b"protocol_config"], bump = protocol_config.bump,
+        has_one = guardian @ SamesError::UnauthorizedGuardian,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetLaunchPause<'info> {
+    pub guardian: Signer<'info>,
+    #[account(
+        seeds = [b"protocol_config"], bump = protocol_config.bump,
+        has_one = guardian @ SamesError::UnauthorizedGuardian,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    #[account(mut, seeds = [b"launch_pool", launch_pool.mint.as_ref()], bump = launch_pool.bump)]
+    pub launch_pool: Account<'info, LaunchPool>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelLaunch<'info> {
+    pub creator: Signer<'info>,
+    #[account(mut, seeds = [b"launch_pool", launch_pool.mint.as_ref()], bump = launch_pool.bump)]
+    pub launch_pool: Account<'info, LaunchPool>,
+    #[account(mut, constraint = mint.key() == launch_pool.mint @ SamesError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, MintAccount>,
+    /// Only needed once the creator allocation has been escrowed.
+    #[account(mut, seeds = [b"creator_escrow", launch_pool.key().as_ref()], bump)]
+    pub creator_escrow: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimRefund<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    #[account(mut, seeds = [b"launch_pool", launch_pool.mint.as_ref()], bump = launch_pool.bump)]
+    pub launch_pool: Account<'info, LaunchPool>,
+    #[account(mut, constraint = mint.key() == launch_pool.mint @ SamesError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, MintAccount>,
+    /// CHECK: SOL vault PDA.
+    #[account(mut, seeds = [b"vault", launch_pool.key().as_ref()], bump = launch_pool.vault_bump)]
+    pub vault: SystemAccount<'info>,
+    #[account(
+        mut, seeds = [b"buyer_record", launch_pool.key().as_ref(), buyer.key().as_ref()],
+        bump = buyer_record.bump,
+        has_one = launch_pool, has_one = buyer,
+    )]
+    pub buyer_record: Account<'info, BuyerRecord>,
+    /// Needed if the allocation was finalized and has vested.
+    #[account(mut, token::mint = mint, token::authority = buyer)]
+    pub buyer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Needed if part of the allocation is still vesting.
+    #[account(mut, seeds = [b"vesting_escrow", launch_pool.key().as_ref()], bump)]
+    pub vesting_escrow: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(seeds = [b"protocol_config"], bump = protocol_config.bump)]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseLaunch<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    /// Closed by the handler, as it stays for graduated launches.
+    #[account(mut, seeds = [b"launch_pool", launch_pool.mint.as_ref()], bump = launch_pool.bump)]
+    pub launch_pool: Account<'info, LaunchPool>,
+    #[account(mut, constraint = mint.key() == launch_pool.mint @ SamesError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, MintAccount>,
+    /// CHECK: SOL vault PDA.
+    #[account(mut, seeds = [b"vault", launch_pool.key().as_ref()], bump = launch_pool.vault_bump)]
+    pub vault: SystemAccount<'info>,
+    /// Closed or emptied by the handler.
+    #[account(
+        mut, seeds = [b"market_registry", launch_pool.key().as_ref()], bump = market_registry.bump,
+        has_one = launch_pool,
+    )]
     pub market_registry: Account<'info, MarketRegistry>,
+    /// CHECK: the hook's extra-account-metas PDA; closed or resized if it exists.
+    #[account(mut, seeds = [b"extra-account-metas", launch_pool.mint.as_ref()], bump)]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+    /// Pass the escrows that were created, so their rent is returned too.
+    /// A graduated launch's must be empty; a cancelled launch's leftovers
+    /// are burned.
+    #[account(mut, seeds = [b"vesting_escrow", launch_pool.key().as_ref()], bump)]
+    pub vesting_escrow: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, seeds = [b"creator_escrow", launch_pool.key().as_ref()], bump)]
+    pub creator_escrow: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateBuyerRecord<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: any wallet; only its key seeds the record.
+    pub owner: UncheckedAccount<'info>,
+    pub launch_pool: Account<'info, LaunchPool>,
+    #[account(
+        init,
+        payer = payer,
+        space = BuyerRecord::MAX_SIZE,
+        seeds = [b"buyer_record", launch_pool.key().as_ref(), owner.key().as_ref()],
+        bump,
+    )]
+    pub buyer_record: Account<'info, BuyerRecord>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseBuyerRecord<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    /// CHECK: the record's launch; may already be closed, read in the handler.
+    #[account(address = buyer_record.launch_pool)]
+    pub launch_pool: UncheckedAccount<'info>,
+    #[account(
+        mut, seeds = [b"buyer_record", launch_pool.key().as_ref(), buyer.key().as_ref()],
+        bump = buyer_record.bump,
+        has_one = buyer,
+    )]
+    pub buyer_record: Account<'info, BuyerRecord>,
+    /// CHECK: receives the rent it paid; the buyer itself unless someone
+    /// else opened or migrated the record.
+    #[account(mut, address = buyer_record.rent_payer)]
+    pub rent_payer: UncheckedAccount<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateBuyerRecord<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: a BuyerRecord in the legacy layout, which `Account` can't
+    /// load; discriminator, size and address are checked in the handler.
+    #[account(mut, owner = crate::ID)]
+    pub buyer_record: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateMarketRegistry<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: a MarketRegistry in the legacy layout, which `Account` can't
+    /// load; discriminator, size and address are checked in the handler.
+    #[account(mut, owner = crate::ID)]
+    pub market_registry: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
 }
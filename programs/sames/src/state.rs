@@ -17,111 +17,55 @@ pub enum LaunchStatus {
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
-// Bonding curve math
+// Vesting schedule — cliff + linear release
 // ─────────────────────────────────────────────────────────────────────────────
-// We use a linear bonding curve for simplicity:
-//   price = base_price + slope * tokens_sold
-//
-// For a buy of `amount` tokens starting at `tokens_sold`:
-//   cost = integral from tokens_sold to tokens_sold + amount of (base + slope * x) dx
-//        = base * amount + slope * (amount * tokens_sold + amount^2 / 2)
-//
-// This gives increasing price as more tokens are bought.
-
-/// Calculate the cost in lamports to buy `amount` tokens on the bonding curve.
-/// Uses integer math with scaling to avoid overflow.
-/// base_price and slope are in lamports (slope is lamports per token, scaled by 1e9).
-pub fn bonding_curve_cost(
-    base_price: u64,
-    slope_scaled: u64,  // slope * 1e9 (to handle fractional slopes)
-    tokens_sold: u64,
-    amount: u64,
-) -> Option<u64> {
-    // cost = base_price * amount + slope_scaled * amount * (2*tokens_sold + amount) / (2 * 1e9)
-    let base_cost = (base_price as u128).checked_mul(amount as u128)?;
-    let two_sold_plus_amount = (2u128)
-        .checked_mul(tokens_sold as u128)?
-        .checked_add(amount as u128)?;
-    let slope_cost = (slope_scaled as u128)
-        .checked_mul(amount as u128)?
-        .checked_mul(two_sold_plus_amount)?
-        .checked_div(2_000_000_000u128)?;  // divide by 2 * 1e9
-    let total = base_cost.checked_add(slope_cost)?;
-    if total > u64::MAX as u128 { return None; }
-    Some(total as u64)
-}
 
-/// Calculate how many tokens you get for `sol_amount` lamports on the bonding curve.
-/// Inverse of bonding_curve_cost using quadratic formula.
-pub fn bonding_curve_tokens_for_sol(
-    base_price: u64,
-    slope_scaled: u64,
-    tokens_sold: u64,
-    sol_amount: u64,
-) -> Option<u64> {
-    if slope_scaled == 0 {
-        // Linear pricing: tokens = sol_amount / base_price
-        return Some(sol_amount.checked_div(base_price)?);
-    }
-    // Solving: slope_scaled * amount^2 / (2*1e9) + (base_price + slope_scaled * tokens_sold / 1e9) * amount = sol_amount
-    // Using quadratic formula: a*x^2 + b*x - c = 0
-    // a = slope_scaled / (2 * 1e9)
-    // b = base_price + slope_scaled * tokens_sold / 1e9
-    // c = sol_amount
-    // x = (-b + sqrt(b^2 + 4ac)) / (2a)
-    
-    // Work in u128 to avoid overflow
-    let a_num = slope_scaled as u128;  // numerator, will divide by 2e9 later
-    let b = (base_price as u128)
-        .checked_add(
-            (slope_scaled as u128)
-                .checked_mul(tokens_sold as u128)?
-                .checked_div(1_000_000_000u128)?
-        )?;
-    let c = sol_amount as u128;
-    
-    // discriminant = b^2 + 4 * (a_num / 2e9) * c = b^2 + 2 * a_num * c / 1e9
-    let b_squared = b.checked_mul(b)?;
-    let four_ac = (2u128)
-        .checked_mul(a_num)?
-        .checked_mul(c)?
-        .checked_div(1_000_000_000u128)?;
-    let discriminant = b_squared.checked_add(four_ac)?;
-    
-    // Integer square root
-    let sqrt_disc = isqrt_u128(discriminant);
-    
-    // amount = (-b + sqrt(disc)) / (2a) = (sqrt(disc) - b) * 1e9 / a_num
-    if sqrt_disc <= b { return Some(0); }
-    let numerator = (sqrt_disc - b).checked_mul(1_000_000_000u128)?;
-    let result = numerator.checked_div(a_num)?;
-    
-    if result > u64::MAX as u128 { return None; }
-    Some(result as u64)
+/// Release schedule measured from the end of the presale.
+/// Nothing is released before the cliff; after it, tokens vest linearly
+/// until `duration_seconds`. A zero duration means no vesting.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct VestingSchedule {
+    pub cliff_seconds: u32,
+    pub duration_seconds: u32,
 }
 
-/// Calculate the current spot price on the bonding curve.
-pub fn bonding_curve_price(base_price: u64, slope_scaled: u64, tokens_sold: u64) -> u64 {
-    let slope_component = (slope_scaled as u128)
-        .checked_mul(tokens_sold as u128)
-        .and_then(|v| v.checked_div(1_000_000_000u128))
-        .unwrap_or(0);
-    let price = (base_price as u128).saturating_add(slope_component);
-    if price > u64::MAX as u128 { u64::MAX } else { price as u64 }
-}
+impl VestingSchedule {
+    pub const SIZE: usize = 4 + 4;
 
-/// Integer square root for u128 (Newton's method).
-fn isqrt_u128(n: u128) -> u128 {
-    if n == 0 { return 0; }
-    let mut x = n;
-    let mut y = (x + 1) / 2;
-    while y < x {
-        x = y;
-        y = (x + n / x) / 2;
+    pub fn is_enabled(&self) -> bool {
+        self.duration_seconds > 0
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.cliff_seconds <= self.duration_seconds
+    }
+
+    /// Portion of `total` released at `now` for a schedule starting at `start`.
+    pub fn vested_amount(&self, total: u64, start: i64, now: i64) -> u64 {
+        if !self.is_enabled() {
+            return total;
+        }
+        let elapsed = now.saturating_sub(start);
+        if elapsed < self.cliff_seconds as i64 {
+            return 0;
+        }
+        if elapsed >= self.duration_seconds as i64 {
+            return total;
+        }
+        // elapsed < duration, so the result is < total and fits in u64.
+        ((total as u128) * (elapsed as u128) / (self.duration_seconds as u128)) as u64
     }
-    x
 }
 
+// ─────────────────────────────────────────────────────────────────────────────
+// Curve math lives in `sames-quote` so off-chain quotes can't drift from it.
+// ─────────────────────────────────────────────────────────────────────────────
+pub use sames_quote::{
+    bonding_curve_cost, bonding_curve_price, bonding_curve_sell_proceeds,
+    bonding_curve_spot_entry_price, bonding_curve_tokens_for_sol, cpmm_sell_proceeds, entry_price,
+    platform_fee, sale_below_entry, ENTRY_PRICE_SCALE,
+};
+
 // ─────────────────────────────────────────────────────────────────────────────
 // LaunchPool — one per token launch
 // ─────────────────────────────────────────────────────────────────────────────
@@ -181,8 +125,38 @@ pub struct LaunchPool {
     /// Vault bump (SOL escrow PDA).
     pub vault_bump: u8,
 
+    /// Keep enforcing the price floor after graduation, pricing market-bound
+    /// transfers off the graduated AMM pool's reserves.
+    pub floor_after_graduation: bool,
+
+    /// Vesting for presale allocations, starting at `end_time`. Vesting
+    /// allocations are held in escrow and released through `claim_vested`.
+    pub presale_vesting: VestingSchedule,
+
+    /// Share of `total_supply` reserved for the creator, in basis points.
+    pub creator_allocation_bps: u16,
+
+    /// Lock-up for the creator allocation, starting at `end_time`. Unlike
+    /// presale vesting it runs on time alone and does not end at graduation.
+    pub creator_vesting: VestingSchedule,
+
+    /// Creator allocation already released from escrow.
+    pub creator_tokens_claimed: u64,
+
+    /// Actions halted for this launch by the guardian (`pause` bits).
+    pub paused: u16,
+
+    /// Curve-issued tokens no BuyerRecord holds as inventory: carried out
+    /// of a record's wallet by transfer and handed over by
+    /// `release_curve_inventory`. Anyone may sell these back.
+    pub curve_tokens_unassigned: u64,
+
+    /// Set when the launch is cancelled: after this, `close_launch` may
+    /// sweep deposits that were never refunded.
+    pub refund_deadline: i64,
+
     /// Reserved space for future upgrades.
-    pub _reserved: [u8; 64],
+    pub _reserved: [u8; 19],
 }
 
 impl LaunchPool {
@@ -204,7 +178,18 @@ impl LaunchPool {
         + 1   // status (enum)
         + 1   // bump
         + 1   // vault_bump
-        + 64; // _reserved
+        + 1   // floor_after_graduation
+        + VestingSchedule::SIZE // presale_vesting
+        + 2   // creator_allocation_bps
+        + VestingSchedule::SIZE // creator_vesting
+        + 8   // creator_tokens_claimed
+        + 2   // paused
+        + 8   // curve_tokens_unassigned
+        + 8   // refund_deadline
+        + 19; // _reserved
+
+    /// How long buyers of a cancelled launch have to claim their refunds.
+    pub const REFUND_WINDOW: i64 = 30 * 24 * 60 * 60;
 
     pub fn is_presale_active(&self, now: i64) -> bool {
         self.status == LaunchStatus::Presale && now >= self.start_time && now < self.end_time
@@ -225,6 +210,64 @@ impl LaunchPool {
         self.status == LaunchStatus::BondingCurve
             && self.curve_sol_collected >= self.graduation_threshold
     }
+
+    /// Tokens reserved for the creator out of `total_supply`.
+    pub fn creator_allocation(&self) -> u64 {
+        ((self.total_supply as u128) * (self.creator_allocation_bps as u128) / 10_000) as u64
+    }
+
+    /// Part of `total_supply` split pro-rata among presale buyers.
+    pub fn presale_supply(&self) -> u64 {
+        self.total_supply - self.creator_allocation()
+    }
+
+    /// Creator allocation vested but not yet claimed.
+    pub fn claimable_creator_tokens(&self, now: i64) -> u64 {
+        let allocation = self.creator_allocation();
+        self.creator_vesting
+            .vested_amount(allocation, self.end_time, now)
+            .saturating_sub(self.creator_tokens_claimed)
+    }
+
+    /// Creators may cancel during the presale, or after it as long as
+    /// nobody has traded on the curve.
+    pub fn is_cancellable(&self) -> bool {
+        match self.status {
+            LaunchStatus::Presale => true,
+            LaunchStatus::BondingCurve => self.tokens_sold_curve == 0 && self.curve_sol_collected == 0,
+            LaunchStatus::Graduated | LaunchStatus::Closed => false,
+        }
+    }
+
+    /// A cancelled launch can be closed once every deposit is refunded, or
+    /// unclaimed ones are forfeited after `refund_deadline`.
+    pub fn are_refunds_settled(&self, now: i64) -> bool {
+        self.total_sol_collected == 0 || now >= self.refund_deadline
+    }
+
+    /// Presale tokens can't move until the curve goes live.
+    pub fn are_transfers_locked(&self) -> bool {
+        self.status == LaunchStatus::Presale
+    }
+
+    /// Part of a presale allocation still locked by the vesting schedule,
+    /// measured from the end of the presale. Lock-ups end at graduation
+    /// (or when the launch is closed).
+    pub fn locked_presale_tokens(&self, allocated: u64, now: i64) -> u64 {
+        match self.status {
+            LaunchStatus::Graduated | LaunchStatus::Closed => 0,
+            _ => allocated - self.presale_vesting.vested_amount(allocated, self.end_time, now),
+        }
+    }
+
+    /// Whether the transfer hook enforces the price floor in the current phase.
+    pub fn is_floor_active(&self) -> bool {
+        match self.status {
+            LaunchStatus::BondingCurve => true,
+            LaunchStatus::Graduated => self.floor_after_graduation,
+            _ => false,
+        }
+    }
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -243,15 +286,17 @@ pub struct BuyerRecord {
     /// SOL deposited by this buyer during presale (lamports).
     pub sol_deposited: u64,
 
-    /// Entry price in lamports per token.
+    /// Entry price in lamports per token, fixed point (`ENTRY_PRICE_SCALE`).
     /// For presale buyers: the presale price.
     /// For curve buyers: their average purchase price.
-    pub entry_price: u64,
+    pub entry_price: u128,
 
     /// Number of tokens allocated/purchased by this buyer.
     pub tokens_allocated: u64,
 
-    /// Number of tokens this buyer has sold.
+    /// Curve inventory that has left this record: sold back to the curve,
+    /// or carried off by transfer and released into the launch's unassigned
+    /// inventory.
     pub tokens_sold: u64,
 
     /// Total SOL spent on bonding curve buys (for avg price calculation).
@@ -263,8 +308,27 @@ pub struct BuyerRecord {
     /// Bump seed for this PDA.
     pub bump: u8,
 
-    /// Reserved for future use.
-    pub _reserved: [u8; 32],
+    /// Tokens received by wallet-to-wallet transfer (cost basis merged in by the hook).
+    pub tokens_received: u64,
+
+    /// Every other token that left this wallet: sent by transfer (to wallets
+    /// or markets), or sold to the curve against unassigned inventory.
+    /// Transfers count here in full until their curve inventory is released.
+    pub tokens_sent: u64,
+
+    /// Part of `tokens_allocated` still held in the vesting escrow.
+    pub tokens_vesting: u64,
+
+    /// Wallet that paid `rent_paid` of this account's rent, which may be
+    /// someone other than the buyer. Closing returns it to them.
+    pub rent_payer: Pubkey,
+
+    /// Lamports of rent `rent_payer` paid; the buyer gets any beyond this.
+    pub rent_paid: u64,
+
+    /// Curve-issued tokens that reached the wallet by transfer and are still
+    /// in it: what the record may sell against unassigned inventory.
+    pub curve_tokens_received: u64,
 }
 
 impl BuyerRecord {
@@ -272,21 +336,165 @@ impl BuyerRecord {
         + 32  // launch_pool
         + 32  // buyer
         + 8   // sol_deposited
-        + 8   // entry_price
+        + 16  // entry_price
         + 8   // tokens_allocated
         + 8   // tokens_sold
         + 8   // curve_sol_spent
         + 8   // curve_tokens_bought
         + 1   // bump
-        + 32; // _reserved
+        + 8   // tokens_received
+        + 8   // tokens_sent
+        + 8   // tokens_vesting
+        + 32  // rent_payer
+        + 8   // rent_paid
+        + 8;  // curve_tokens_received
+
+    /// Tokens this record still accounts for at its entry price.
+    pub fn holdings(&self) -> u64 {
+        self.tokens_allocated
+            .saturating_add(self.curve_tokens_bought)
+            .saturating_add(self.tokens_received)
+            .saturating_sub(self.tokens_sold)
+            .saturating_sub(self.tokens_sent)
+    }
+
+    /// Whether the record can be closed without losing state the program
+    /// still needs: an unfinalized deposit or unclaimed refund, escrowed
+    /// tokens, curve inventory not yet released to the launch, or the cost
+    /// basis of tokens the hook still floors.
+    pub fn is_closable(&self, launch_pool: &LaunchPool) -> bool {
+        if self.tokens_vesting > 0 || self.unreleased_inventory() > 0 {
+            return false;
+        }
+        match launch_pool.status {
+            LaunchStatus::Presale => false,
+            LaunchStatus::Closed => self.sol_deposited == 0,
+            LaunchStatus::BondingCurve | LaunchStatus::Graduated => {
+                let awaiting_allocation = self.sol_deposited > 0 && self.tokens_allocated == 0;
+                !awaiting_allocation && (!launch_pool.is_floor_active() || self.holdings() == 0)
+            }
+        }
+    }
+
+    /// Curve-bought tokens still in this record: what the curve buys back
+    /// from it before drawing on the launch's unassigned inventory. Presale
+    /// allocations were never part of the curve's reserve. Records from
+    /// before sells were bounded may have sold more than they bought; they
+    /// have nothing left to sell.
+    pub fn curve_inventory(&self) -> u64 {
+        self.curve_tokens_bought.saturating_sub(self.tokens_sold)
+    }
+
+    /// Count `amount` tokens leaving the wallet, `from_inventory` of them
+    /// out of its curve inventory.
+    pub fn debit(&mut self, amount: u64, from_inventory: u64) -> Option<()> {
+        let other = amount.checked_sub(from_inventory)?;
+        if from_inventory > self.curve_inventory() {
+            return None;
+        }
+        self.tokens_sold = self.tokens_sold.checked_add(from_inventory)?;
+        self.tokens_sent = self.tokens_sent.checked_add(other)?;
+        Some(())
+    }
+
+    /// Curve inventory that transfers carried out of the wallet and that
+    /// `release_curve_inventory` hasn't handed to the launch yet. Other
+    /// holdings count as leaving first, so the holder keeps their claim on
+    /// the curve for as long as they can.
+    pub fn unreleased_inventory(&self) -> u64 {
+        self.curve_inventory()
+            .saturating_sub(self.liquid_holdings())
+            .min(self.tokens_sent)
+    }
+
+    /// Unassigned inventory this record may sell against: no more than the
+    /// curve-issued tokens that reached the wallet by transfer, so presale
+    /// allocations never drain curve buyers' SOL, whoever holds them.
+    pub fn unassigned_allowance(&self) -> u64 {
+        self.curve_tokens_received
+    }
+
+    /// Count `amount` tokens sent by transfer, returning how many of them
+    /// were curve-issued. Tokens of other origin leave first, then curve
+    /// tokens received from others, then the record's own inventory, which
+    /// stays on the record until it is released.
+    pub fn send(&mut self, amount: u64) -> Option<u64> {
+        let own = self.curve_inventory().saturating_sub(self.unreleased_inventory());
+        let curve_held = own.saturating_add(self.curve_tokens_received);
+        let other = self.liquid_holdings().saturating_sub(curve_held);
+        let curve_tokens = amount.saturating_sub(other).min(curve_held);
+        self.curve_tokens_received -= curve_tokens.min(self.curve_tokens_received);
+        self.debit(amount, 0)?;
+        Some(curve_tokens)
+    }
+
+    /// Recount `amount` sent tokens as curve inventory that left the record.
+    /// Holdings don't change.
+    pub fn release_inventory(&mut self, amount: u64) -> Option<()> {
+        self.tokens_sent = self.tokens_sent.checked_sub(amount)?;
+        self.tokens_sold = self.tokens_sold.checked_add(amount)?;
+        Some(())
+    }
 
-    /// Calculate average entry price across presale + curve buys.
-    pub fn average_entry_price(&self) -> u64 {
-        let total_sol = self.sol_deposited.saturating_add(self.curve_sol_spent);
-        let total_tokens = self.tokens_allocated.saturating_add(self.curve_tokens_bought);
-        if total_tokens == 0 { return 0; }
-        // avg_price = total_sol / total_tokens
-        ((total_sol as u128) * 1_000_000_000 / (total_tokens as u128) / 1_000_000_000) as u64
+    /// Holdings that sit in the buyer's wallet rather than the vesting escrow.
+    pub fn liquid_holdings(&self) -> u64 {
+        self.holdings().saturating_sub(self.tokens_vesting)
+    }
+
+    /// Fold `amount` tokens acquired at `price` (fixed point) into the
+    /// weighted-average entry price. Call before adding `amount` to the
+    /// record's counters.
+    pub fn merge_entry_price(&mut self, amount: u64, price: u128) -> Option<()> {
+        self.entry_price = sames_quote::merge_entry_price(self.holdings(), self.entry_price, amount, price)?;
+        Some(())
+    }
+}
+
+/// BuyerRecord as stored before entry prices went fixed point, when
+/// `entry_price` was whole lamports per token (rounded up). Same
+/// discriminator and address; `migrate_buyer_record` rewrites these in the
+/// current layout.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct LegacyBuyerRecord {
+    pub launch_pool: Pubkey,
+    pub buyer: Pubkey,
+    pub sol_deposited: u64,
+    pub entry_price: u64,
+    pub tokens_allocated: u64,
+    pub tokens_sold: u64,
+    pub curve_sol_spent: u64,
+    pub curve_tokens_bought: u64,
+    pub bump: u8,
+    pub tokens_received: u64,
+    pub tokens_sent: u64,
+    pub tokens_vesting: u64,
+    pub _reserved: [u8; 8],
+}
+
+impl LegacyBuyerRecord {
+    /// Account size, discriminator included.
+    pub const SIZE: usize = 153;
+
+    /// The same record in the current layout. Whole-lamport prices convert
+    /// exactly, so no floor moves. The buyer paid all the rent so far.
+    pub fn migrate(self) -> BuyerRecord {
+        BuyerRecord {
+            launch_pool: self.launch_pool,
+            buyer: self.buyer,
+            sol_deposited: self.sol_deposited,
+            entry_price: self.entry_price as u128 * ENTRY_PRICE_SCALE,
+            tokens_allocated: self.tokens_allocated,
+            tokens_sold: self.tokens_sold,
+            curve_sol_spent: self.curve_sol_spent,
+            curve_tokens_bought: self.curve_tokens_bought,
+            bump: self.bump,
+            tokens_received: self.tokens_received,
+            tokens_sent: self.tokens_sent,
+            tokens_vesting: self.tokens_vesting,
+            rent_payer: self.buyer,
+            rent_paid: 0,
+            curve_tokens_received: 0,
+        }
     }
 }
 
@@ -294,16 +502,288 @@ impl BuyerRecord {
 // MarketRegistry — whitelisted DEX / market accounts
 // ─────────────────────────────────────────────────────────────────────────────
 
+/// How the hook prices a sell into a market.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MarketKind {
+    /// Tokens sold into this account are priced off the launch's bonding curve.
+    BondingCurveVault,
+    /// Constant-product pool, priced from its two reserve token accounts.
+    Cpmm,
+    /// Orderbook market. It has no reserves to price from, so floored sells
+    /// into it are rejected.
+    Orderbook,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MarketEntry {
+    /// Token account that receives the tokens when someone sells into this market.
+    pub address: Pubkey,
+    pub kind: MarketKind,
+    /// Token-side reserve (`Cpmm` only, default otherwise).
+    pub token_reserve: Pubkey,
+    /// SOL-side (wrapped SOL) reserve (`Cpmm` only, default otherwise).
+    pub sol_reserve: Pubkey,
+}
+
+impl MarketEntry {
+    pub const SIZE: usize = 32 + 1 + 32 + 32;
+
+    /// Whether reserves holding `token_mint` and `sol_mint` pair the
+    /// launch's `mint` with wrapped SOL, as a `Cpmm` market's must.
+    pub fn reserves_match(mint: &Pubkey, token_mint: &Pubkey, sol_mint: &Pubkey) -> bool {
+        token_mint == mint && is_native_mint(sol_mint)
+    }
+
+    /// Reserve accounts are set exactly when the kind needs them.
+    pub fn is_valid(&self) -> bool {
+        if self.address == Pubkey::default() {
+            return false;
+        }
+        let has_reserves = self.token_reserve != Pubkey::default() && self.sol_reserve != Pubkey::default();
+        let no_reserves = self.token_reserve == Pubkey::default() && self.sol_reserve == Pubkey::default();
+        match self.kind {
+            MarketKind::Cpmm => has_reserves,
+            MarketKind::BondingCurveVault | MarketKind::Orderbook => no_reserves,
+        }
+    }
+}
+
+/// Wrapped SOL, under either token program.
+pub fn is_native_mint(mint: &Pubkey) -> bool {
+    *mint == anchor_spl::token::spl_token::native_mint::ID || *mint == spl_token_2022::native_mint::ID
+}
+
 #[account]
 #[derive(Debug)]
 pub struct MarketRegistry {
     pub launch_pool: Pubkey,
     pub authority: Pubkey,
-    pub market_accounts: Vec<Pubkey>,
+    /// Grows and shrinks by realloc as markets are added and removed.
+    pub markets: Vec<MarketEntry>,
     pub bump: u8,
 }
 
 impl MarketRegistry {
-    pub const MAX_MARKETS: usize = 16;
-    pub const MAX_SIZE: usize = 8 + 32 + 32 + 4 + (32 * Self::MAX_MARKETS) + 1;
+    /// Upper bound on registered markets.
+    pub const MAX_MARKETS: usize = 64;
+    /// Every CPMM adds its two reserves to the accounts of each transfer,
+    /// so these are capped well below the CPI account limit.
+    pub const MAX_CPMM_MARKETS: usize = 8;
+
+    /// Account size holding `markets` entries.
+    pub const fn space(markets: usize) -> usize {
+        8 + 32 + 32 + 4 + MarketEntry::SIZE * markets + 1
+    }
+
+    pub fn find_market(&self, address: &Pubkey) -> Option<&MarketEntry> {
+        self.markets.iter().find(|m| m.address == *address)
+    }
+
+    pub fn cpmm_count(&self) -> usize {
+        self.markets.iter().filter(|m| m.kind == MarketKind::Cpmm).count()
+    }
+}
+
+/// MarketRegistry as stored before markets carried a kind, when it was a
+/// plain list of addresses in a fixed-size account. Same discriminator and
+/// address; `migrate_market_registry` rewrites these in the current layout.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct LegacyMarketRegistry {
+    pub launch_pool: Pubkey,
+    pub authority: Pubkey,
+    pub market_accounts: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl LegacyMarketRegistry {
+    /// Account size, discriminator included: room for 16 addresses,
+    /// however many were registered. No current registry is this size.
+    pub const SIZE: usize = 8 + 32 + 32 + 4 + 32 * 16 + 1;
+
+    /// The same registry in the current layout. The old layout doesn't say
+    /// how to price a market, so each becomes an `Orderbook`: floored sells
+    /// into it are rejected until the authority replaces it with its kind.
+    pub fn migrate(self) -> MarketRegistry {
+        MarketRegistry {
+            launch_pool: self.launch_pool,
+            authority: self.authority,
+            markets: self
+                .market_accounts
+                .into_iter()
+                .map(|address| MarketEntry {
+                    address,
+                    kind: MarketKind::Orderbook,
+                    token_reserve: Pubkey::default(),
+                    sol_reserve: Pubkey::default(),
+                })
+                .collect(),
+            bump: self.bump,
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// ProtocolConfig — protocol-wide settings (singleton)
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Bits of the `paused` masks on ProtocolConfig and LaunchPool. Each halts
+/// one action, so e.g. sells can stay open while buys are stopped.
+pub mod pause {
+    pub const PRESALE_BUY: u16 = 1 << 0;
+    pub const CURVE_BUY: u16 = 1 << 1;
+    pub const CURVE_SELL: u16 = 1 << 2;
+    pub const GRADUATE: u16 = 1 << 3;
+    /// Token transfers, enforced by the transfer hook.
+    pub const TRANSFER: u16 = 1 << 4;
+    pub const REFUND: u16 = 1 << 5;
+
+    pub const BUYS: u16 = PRESALE_BUY | CURVE_BUY;
+    pub const ALL: u16 = PRESALE_BUY | CURVE_BUY | CURVE_SELL | GRADUATE | TRANSFER | REFUND;
+}
+
+/// A DEX whose pools the hook treats as markets for every launch.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DexProgram {
+    pub program_id: Pubkey,
+    /// Pool-vault authority PDA of `program_id`, derived from the seeds
+    /// given to `add_dex_program`. A token account owned by exactly this key
+    /// is a market, whether or not the authority account exists.
+    pub authority: Pubkey,
+}
+
+impl DexProgram {
+    pub const SIZE: usize = 32 + 32;
+
+    /// Whether a token account owned by `owner` belongs to this DEX.
+    pub fn owns(&self, owner: &Pubkey) -> bool {
+        *owner == self.authority
+    }
+}
+
+/// Protocol parameters the admin can tune without a program upgrade.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ProtocolParams {
+    /// Fee on bonding curve sells, in basis points.
+    pub platform_fee_bps: u16,
+    /// Presale window duration in seconds.
+    pub presale_duration: i64,
+    /// Bonding curve slope (scaled by 1e9) given to new launches.
+    pub default_slope: u64,
+    /// Graduation threshold in lamports given to new launches.
+    pub default_graduation_threshold: u64,
+    /// Largest share of supply a creator may reserve, in basis points.
+    pub max_creator_allocation_bps: u16,
+}
+
+impl ProtocolParams {
+    pub const SIZE: usize = 2 + 8 + 8 + 8 + 2;
+}
+
+impl Default for ProtocolParams {
+    /// The values the program shipped with as compile-time constants.
+    fn default() -> Self {
+        Self {
+            // 1%
+            platform_fee_bps: 100,
+            presale_duration: 30,
+            // With base_price=1000 lamports and slope=100, price doubles after 10M tokens sold.
+            default_slope: 100,
+            // 69 SOL
+            default_graduation_threshold: 69_000_000_000,
+            // 20%
+            max_creator_allocation_bps: 2_000,
+        }
+    }
+}
+
+/// The range each protocol parameter may take. The admin moves them with
+/// `update_protocol_bounds`, but never past `LIMITS`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ProtocolBounds {
+    /// Smallest value each parameter may take.
+    pub min: ProtocolParams,
+    /// Largest value each parameter may take.
+    pub max: ProtocolParams,
+}
+
+impl ProtocolBounds {
+    pub const SIZE: usize = 2 * ProtocolParams::SIZE;
+
+    /// Outer limits no bounds may exceed. They are compiled in on purpose:
+    /// they cap what a compromised or careless admin key can do, so moving
+    /// them takes a program upgrade, which anyone can review beforehand.
+    pub const LIMITS: Self = Self {
+        min: ProtocolParams {
+            platform_fee_bps: 0,
+            presale_duration: 10,
+            default_slope: 0,
+            default_graduation_threshold: 1_000_000_000,
+            max_creator_allocation_bps: 0,
+        },
+        max: ProtocolParams {
+            platform_fee_bps: 500,
+            presale_duration: 7 * 24 * 60 * 60,
+            default_slope: 1_000_000,
+            default_graduation_threshold: 10_000_000_000_000,
+            max_creator_allocation_bps: 5_000,
+        },
+    };
+
+    /// Whether every parameter lies within its range.
+    pub fn contains(&self, params: &ProtocolParams) -> bool {
+        let (min, max) = (&self.min, &self.max);
+        (min.platform_fee_bps..=max.platform_fee_bps).contains(&params.platform_fee_bps)
+            && (min.presale_duration..=max.presale_duration).contains(&params.presale_duration)
+            && (min.default_slope..=max.default_slope).contains(&params.default_slope)
+            && (min.default_graduation_threshold..=max.default_graduation_threshold)
+                .contains(&params.default_graduation_threshold)
+            && (min.max_creator_allocation_bps..=max.max_creator_allocation_bps)
+                .contains(&params.max_creator_allocation_bps)
+    }
+
+    /// Whether every range is non-empty and inside `LIMITS`.
+    pub fn is_valid(&self) -> bool {
+        Self::LIMITS.contains(&self.min) && Self::LIMITS.contains(&self.max) && self.contains(&self.min)
+    }
+}
+
+impl Default for ProtocolBounds {
+    fn default() -> Self {
+        Self::LIMITS
+    }
+}
+
+#[account]
+#[derive(Debug)]
+pub struct ProtocolConfig {
+    pub admin: Pubkey,
+    /// Proposed new admin; takes over once it calls `accept_admin`.
+    /// `Pubkey::default()` when no transfer is pending.
+    pub pending_admin: Pubkey,
+    pub bump: u8,
+    pub params: ProtocolParams,
+    /// Ranges `params` must stay within.
+    pub bounds: ProtocolBounds,
+    /// Key allowed to pause and unpause, protocol-wide or per launch.
+    pub guardian: Pubkey,
+    /// Actions halted for every launch (`pause` bits).
+    pub paused: u16,
+    /// DEX programs recognized by the transfer hook, on top of each
+    /// launch's own MarketRegistry.
+    pub dex_programs: Vec<DexProgram>,
+}
+
+impl ProtocolConfig {
+    pub const MAX_DEX_PROGRAMS: usize = 16;
+    pub const MAX_SIZE: usize = 8 + 32 + 32 + 1 + ProtocolParams::SIZE + ProtocolBounds::SIZE + 32 + 2
+        + 4 + DexProgram::SIZE * Self::MAX_DEX_PROGRAMS;
+
+    /// Whether `action` (a `pause` bit) is halted, globally or for `launch_pool`.
+    pub fn is_paused(&self, launch_pool: &LaunchPool, action: u16) -> bool {
+        (self.paused | launch_pool.paused) & action != 0
+    }
+
+    pub fn is_dex_account(&self, owner: &Pubkey) -> bool {
+        self.dex_programs.iter().any(|dex| dex.owns(owner))
+    }
 }
@@ -0,0 +1,39 @@
+//! Account fixtures for tests that build state by hand rather than through
+//! the program. Tests override what they need with struct update syntax.
+
+#![allow(dead_code)]
+
+use anchor_lang::prelude::Pubkey;
+use sames::state::{LaunchPool, LaunchStatus, VestingSchedule};
+
+/// A presale launch with every field zeroed.
+pub fn launch_pool() -> LaunchPool {
+    LaunchPool {
+        creator: Pubkey::default(),
+        mint: Pubkey::default(),
+        token_name: String::new(),
+        token_symbol: String::new(),
+        total_supply: 0,
+        price_lamports: 0,
+        slope_scaled: 0,
+        tokens_sold_curve: 0,
+        curve_sol_collected: 0,
+        start_time: 0,
+        end_time: 0,
+        total_sol_collected: 0,
+        buyer_count: 0,
+        graduation_threshold: 0,
+        status: LaunchStatus::Presale,
+        bump: 0,
+        vault_bump: 0,
+        floor_after_graduation: false,
+        presale_vesting: VestingSchedule::default(),
+        creator_allocation_bps: 0,
+        creator_vesting: VestingSchedule::default(),
+        creator_tokens_claimed: 0,
+        paused: 0,
+        curve_tokens_unassigned: 0,
+        refund_deadline: 0,
+        _reserved: [0; 19],
+    }
+}
@@ -0,0 +1,111 @@
+//! Fixed-point entry prices, and migrating records stored while they were
+//! whole lamports per token.
+
+mod svm;
+
+use anchor_lang::prelude::{Pubkey, Rent};
+use anchor_lang::{AnchorSerialize, Discriminator};
+use sames::errors::SamesError;
+use sames::events::{BuyerRecordMigrated, CurveTrade};
+use sames::state::{entry_price, BuyerRecord, LaunchPool, LegacyBuyerRecord, ProtocolConfig, ENTRY_PRICE_SCALE};
+use sames_sdk::{instructions, pda, quote};
+use svm::launch::*;
+
+#[test]
+fn curve_buys_keep_fractional_entry_prices() {
+    let env = Env::new();
+    let (alice, carol) = (env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    env.buy_curve(&launch, &carol, LAMPORTS_PER_SOL).unwrap();
+    let buy = env.svm.events::<CurveTrade>().remove(0);
+    let entry = env.record(&launch, &carol).unwrap().entry_price;
+    assert_eq!(entry, entry_price(buy.sol_amount, buy.token_amount).unwrap());
+    assert_eq!(buy.entry_price, entry);
+    assert_ne!(entry % ENTRY_PRICE_SCALE, 0, "a curve average isn't a whole lamport");
+
+    // The floor is the exact average, held against what the sale pays
+    // after the fee: the lowest base price at which selling 1_000 tokens
+    // realizes it lets Carol sell, one lamport less doesn't.
+    let fee_bps = env.svm.anchor::<ProtocolConfig>(&pda::protocol_config().0).params.platform_fee_bps;
+    let blocked = |price| {
+        let pool = LaunchPool { price_lamports: price, ..env.pool(&launch) };
+        quote::sell(&pool, fee_bps, 1_000, entry).unwrap().blocked_by_floor
+    };
+    let (mut lowest, mut high) = (0, u32::MAX as u64);
+    while lowest < high {
+        let mid = (lowest + high) / 2;
+        if blocked(mid) { lowest = mid + 1 } else { high = mid }
+    }
+    env.update_price(&launch, lowest - 1).unwrap();
+    assert_error(env.sell_curve(&launch, &carol, 1_000), SamesError::SellBelowEntry);
+    env.update_price(&launch, lowest).unwrap();
+    env.sell_curve(&launch, &carol, 1_000).unwrap();
+}
+
+#[test]
+fn legacy_records_migrate_once() {
+    let env = Env::new();
+    let (alice, carol, payer) = (env.user(), env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    env.buy_curve(&launch, &carol, LAMPORTS_PER_SOL).unwrap();
+
+    // Store Carol's record the way the program did before: entry price in
+    // whole lamports (rounded up), eight bytes shorter.
+    let address = pda::buyer_record(&launch.pool, &carol).0;
+    let record = env.record(&launch, &carol).unwrap();
+    let whole_lamports = record.entry_price.div_ceil(ENTRY_PRICE_SCALE) as u64;
+    let data = legacy_data(&record, whole_lamports);
+    assert_eq!(data.len(), LegacyBuyerRecord::SIZE);
+    let mut account = env.svm.account(&address).unwrap();
+    account.data = data.clone();
+    account.lamports = Rent::default().minimum_balance(LegacyBuyerRecord::SIZE);
+    env.svm.set_account(address, account.clone());
+
+    // Until it's migrated the record can't be loaded, so nothing moves.
+    assert_error(env.sell_curve(&launch, &carol, 1_000), SamesError::InvalidBuyerRecord);
+    assert_error(env.transfer(&launch, &carol, &alice, 1_000), SamesError::InvalidBuyerRecord);
+
+    // Only genuine records migrate.
+    let forged = Pubkey::new_unique();
+    env.svm.set_account(forged, account);
+    let migrate = |record: &Pubkey| env.send(&[instructions::migrate_buyer_record(&payer, record)], &[&payer]);
+    assert_error(migrate(&forged), SamesError::InvalidBuyerRecord);
+
+    let payer_before = env.svm.lamports(&payer);
+    migrate(&address).unwrap();
+    let migrated = env.record(&launch, &carol).unwrap();
+    assert_eq!(migrated.entry_price, whole_lamports as u128 * ENTRY_PRICE_SCALE);
+    let event = env.svm.events::<BuyerRecordMigrated>().remove(0);
+    assert_eq!((event.buyer, event.entry_price), (carol, migrated.entry_price));
+    assert_eq!(legacy_data(&migrated, whole_lamports), data, "every other field carries over");
+    let rent = Rent::default().minimum_balance(BuyerRecord::MAX_SIZE);
+    assert_eq!(env.svm.lamports(&address), rent);
+    let top_up = rent - Rent::default().minimum_balance(LegacyBuyerRecord::SIZE);
+    assert_eq!(payer_before - env.svm.lamports(&payer), top_up);
+    assert_eq!((migrated.rent_payer, migrated.rent_paid), (payer, top_up));
+
+    env.sell_curve(&launch, &carol, 1_000).unwrap();
+    assert_error(migrate(&address), SamesError::BuyerRecordMigrated);
+}
+
+/// `record` serialized in the legacy layout, with `entry_price` lamports.
+fn legacy_data(record: &BuyerRecord, entry_price: u64) -> Vec<u8> {
+    let legacy = LegacyBuyerRecord {
+        launch_pool: record.launch_pool,
+        buyer: record.buyer,
+        sol_deposited: record.sol_deposited,
+        entry_price,
+        tokens_allocated: record.tokens_allocated,
+        tokens_sold: record.tokens_sold,
+        curve_sol_spent: record.curve_sol_spent,
+        curve_tokens_bought: record.curve_tokens_bought,
+        bump: record.bump,
+        tokens_received: record.tokens_received,
+        tokens_sent: record.tokens_sent,
+        tokens_vesting: record.tokens_vesting,
+        _reserved: [0u8; 8],
+    };
+    let mut data = BuyerRecord::DISCRIMINATOR.to_vec();
+    legacy.serialize(&mut data).unwrap();
+    data
+}
@@ -0,0 +1,476 @@
+//! Every `SamesError` the program can raise, provoked through the
+//! instruction that raises it. The hook-only errors (`HookSellBelowEntry`,
+//! `HookPriceDerivationFailed`, `NotTransferring`) are covered in
+//! `transfer_hook.rs`.
+//!
+//! `PresaleNotStarted` and `InvalidBuyerRecord` guard states no instruction
+//! produces (presales start when they're created; records are bound to their
+//! owner by their seeds), so those tests forge the state first.
+
+mod svm;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_spl::token::spl_token::native_mint::ID as NATIVE_MINT;
+use sames::errors::SamesError;
+use sames::instruction::CreateLaunch;
+use sames::state::{
+    pause, BuyerRecord, MarketEntry, MarketKind, ProtocolBounds, ProtocolConfig, ProtocolParams, VestingSchedule,
+};
+use sames_sdk::{instructions, pda};
+use svm::launch::*;
+use svm::Account;
+
+fn cpmm_market(env: &Env, launch: &Launch) -> MarketEntry {
+    MarketEntry {
+        address: Pubkey::new_unique(),
+        kind: MarketKind::Cpmm,
+        token_reserve: env.forge_token_account(launch.mint, 0),
+        sol_reserve: env.forge_token_account(NATIVE_MINT, 0),
+    }
+}
+
+/// `ix` with the account at `index` replaced by `key`.
+fn with_account(mut ix: Instruction, index: usize, key: Pubkey) -> Instruction {
+    ix.accounts[index].pubkey = key;
+    ix
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Launch creation
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[test]
+fn create_launch_validates_its_arguments() {
+    let env = Env::new();
+    let locked = VestingSchedule { cliff_seconds: 0, duration_seconds: 100 };
+    let cases = [
+        (CreateLaunch { token_name: "x".repeat(33), ..launch_args() }, SamesError::NameTooLong),
+        (CreateLaunch { token_symbol: "x".repeat(11), ..launch_args() }, SamesError::SymbolTooLong),
+        (CreateLaunch { total_supply: 0, ..launch_args() }, SamesError::ZeroSupply),
+        (CreateLaunch { price_lamports: 0, ..launch_args() }, SamesError::ZeroPrice),
+        (
+            CreateLaunch { presale_vesting: VestingSchedule { cliff_seconds: 2, duration_seconds: 1 }, ..launch_args() },
+            SamesError::InvalidVestingSchedule,
+        ),
+        (
+            CreateLaunch { creator_vesting: VestingSchedule { cliff_seconds: 2, duration_seconds: 1 }, ..launch_args() },
+            SamesError::InvalidVestingSchedule,
+        ),
+        (
+            CreateLaunch { creator_allocation_bps: 2_001, creator_vesting: locked, ..launch_args() },
+            SamesError::InvalidCreatorAllocation,
+        ),
+        // An allocation needs a lock-up.
+        (CreateLaunch { creator_allocation_bps: 1_000, ..launch_args() }, SamesError::InvalidCreatorAllocation),
+    ];
+    for (args, error) in cases {
+        assert_error(env.try_launch(args).map(drop), error);
+    }
+    env.launch(CreateLaunch { creator_allocation_bps: 2_000, creator_vesting: locked, ..launch_args() });
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Presale and allocation
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[test]
+fn presale_buys_need_an_open_window() {
+    let env = Env::new();
+    let alice = env.user();
+    let launch = env.launch(launch_args());
+    assert_error(env.buy_presale(&launch, &alice, 0), SamesError::ZeroDeposit);
+
+    let mut pool = env.pool(&launch);
+    pool.start_time += 10;
+    env.svm.set_anchor(&launch.pool, &pool);
+    assert_error(env.buy_presale(&launch, &alice, LAMPORTS_PER_SOL), SamesError::PresaleNotStarted);
+
+    env.svm.warp(PRESALE_SECONDS);
+    assert_error(env.buy_presale(&launch, &alice, LAMPORTS_PER_SOL), SamesError::PresaleEnded);
+}
+
+#[test]
+fn allocation_waits_for_the_presale_and_happens_once() {
+    let env = Env::new();
+    let (alice, mallory) = (env.user(), env.user());
+    let launch = env.launch(launch_args());
+    env.buy_presale(&launch, &alice, LAMPORTS_PER_SOL).unwrap();
+    assert_error(env.finalize(&launch, &alice), SamesError::PresaleStillActive);
+    assert_error(env.start_curve(&launch), SamesError::PresaleStillActive);
+
+    env.svm.warp(PRESALE_SECONDS);
+    let finalize = [
+        instructions::create_token_account(&mallory, &alice, &launch.mint),
+        instructions::finalize_launch(&mallory, &launch.mint, &alice),
+    ];
+    assert_error(env.send(&finalize, &[&mallory]), SamesError::UnauthorizedCreator);
+    let start = instructions::start_bonding_curve(&mallory, &launch.mint);
+    assert_error(env.send(&[start], &[&mallory]), SamesError::UnauthorizedCreator);
+
+    env.finalize(&launch, &alice).unwrap();
+    assert_error(env.finalize(&launch, &alice), SamesError::AlreadyFinalized);
+    env.start_curve(&launch).unwrap();
+    assert_error(env.start_curve(&launch), SamesError::AlreadyFinalized);
+}
+
+#[test]
+fn vesting_claims_need_a_live_launch() {
+    let env = Env::new();
+    let alice = env.user();
+    let launch = env.launch(launch_args());
+    env.buy_presale(&launch, &alice, LAMPORTS_PER_SOL).unwrap();
+    env.svm.warp(PRESALE_SECONDS);
+    env.finalize(&launch, &alice).unwrap();
+    let claim = [instructions::claim_vested(&alice, &launch.mint)];
+    assert_error(env.send(&claim, &[&alice]), SamesError::TransfersLocked);
+
+    // No vesting schedule: everything was paid out at allocation.
+    env.start_curve(&launch).unwrap();
+    assert_error(env.send(&claim, &[&alice]), SamesError::TokensStillVesting);
+
+    env.send(&[instructions::cancel_launch(&launch.creator, &launch.mint, false)], &[&launch.creator]).unwrap();
+    assert_error(env.send(&claim, &[&alice]), SamesError::LaunchClosed);
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Curve trading and graduation
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[test]
+fn curve_trades_need_a_live_curve() {
+    let env = Env::new();
+    let alice = env.user();
+    let launch = env.launch(launch_args());
+    assert_error(env.buy_curve(&launch, &alice, LAMPORTS_PER_SOL), SamesError::NotBondingCurve);
+    assert_error(env.graduate(&launch), SamesError::NotBondingCurve);
+
+    // Allocated, but the curve hasn't started.
+    env.buy_presale(&launch, &alice, LAMPORTS_PER_SOL).unwrap();
+    env.svm.warp(PRESALE_SECONDS);
+    env.finalize(&launch, &alice).unwrap();
+    assert_error(env.sell_curve(&launch, &alice, 1_000), SamesError::NotBondingCurve);
+}
+
+#[test]
+fn curve_trades_validate_amounts() {
+    let env = Env::new();
+    let (alice, carol) = (env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    assert_error(env.buy_curve(&launch, &carol, 0), SamesError::ZeroDeposit);
+    // Less than the first token costs.
+    assert_error(env.buy_curve(&launch, &carol, 1), SamesError::ZeroDeposit);
+
+    env.buy_curve(&launch, &carol, LAMPORTS_PER_SOL).unwrap();
+    let held = env.balance(&launch, &carol);
+    assert_error(env.sell_curve(&launch, &carol, 0), SamesError::ZeroSellAmount);
+    assert_error(env.sell_curve(&launch, &carol, held + 1), SamesError::InsufficientBalance);
+    // Alice's presale allocation never came from the curve.
+    assert_error(env.sell_curve(&launch, &alice, 1_000), SamesError::ExceedsCurveInventory);
+    assert_error(env.graduate(&launch), SamesError::NotReadyToGraduate);
+}
+
+#[test]
+fn curve_sells_keep_the_price_floor() {
+    let env = Env::new();
+    let (alice, carol) = (env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    env.buy_curve(&launch, &carol, LAMPORTS_PER_SOL).unwrap();
+
+    env.update_price(&launch, 1).unwrap();
+    assert_error(env.sell_curve(&launch, &carol, 1_000), SamesError::SellBelowEntry);
+
+    // A base price this high overflows the proceeds.
+    env.update_price(&launch, u64::MAX).unwrap();
+    assert_error(env.sell_curve(&launch, &carol, 2_000), SamesError::MathOverflow);
+}
+
+#[test]
+fn curve_accounts_are_checked() {
+    let env = Env::new();
+    let (alice, carol, mallory) = (env.user(), env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    let other = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    env.buy_curve(&launch, &carol, LAMPORTS_PER_SOL).unwrap();
+
+    let sell = with_account(instructions::sell_curve(&carol, &launch.mint, 1_000), 2, other.mint);
+    assert_error(env.send(&[sell], &[&carol]), SamesError::InvalidMint);
+
+    // A record at Mallory's address that names someone else.
+    env.buy_curve(&launch, &mallory, LAMPORTS_PER_SOL).unwrap();
+    let address = pda::buyer_record(&launch.pool, &mallory).0;
+    let mut record: BuyerRecord = env.svm.anchor(&address);
+    record.buyer = carol;
+    env.svm.set_anchor(&address, &record);
+    assert_error(env.sell_curve(&launch, &mallory, 1_000), SamesError::InvalidBuyerRecord);
+}
+
+#[test]
+fn transfers_reject_malformed_records() {
+    let env = Env::new();
+    let (alice, bob) = (env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    let address = pda::buyer_record(&launch.pool, &bob).0;
+    env.svm.set_account(address, Account {
+        lamports: LAMPORTS_PER_SOL,
+        data: vec![1; BuyerRecord::MAX_SIZE],
+        owner: sames::ID,
+        executable: false,
+        rent_epoch: 0,
+    });
+    assert_error(env.transfer(&launch, &alice, &bob, 1_000), SamesError::InvalidBuyerRecord);
+}
+
+#[test]
+fn floored_tokens_need_a_receiver_record() {
+    let env = Env::new();
+    let (carol, dave) = (env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[]);
+    env.buy_curve(&launch, &carol, LAMPORTS_PER_SOL).unwrap();
+    let vault_before = env.vault_lamports(&launch);
+    assert_error(env.transfer(&launch, &carol, &dave, 1_000), SamesError::ReceiverRecordMissing);
+    assert!(env.record(&launch, &dave).is_none());
+    assert_eq!(env.vault_lamports(&launch), vault_before);
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Creator-only instructions
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[test]
+fn creator_instructions_check_the_creator() {
+    let env = Env::new();
+    let mallory = env.user();
+    let launch = env.launch(launch_args());
+    let market = MarketEntry {
+        kind: MarketKind::Orderbook,
+        token_reserve: Pubkey::default(),
+        sol_reserve: Pubkey::default(),
+        ..cpmm_market(&env, &launch)
+    };
+    for ix in [
+        instructions::update_price(&mallory, &launch.mint, 1),
+        instructions::add_market(&mallory, &launch.mint, market),
+        instructions::remove_market(&mallory, &launch.mint, market.address),
+        instructions::replace_market(&mallory, &launch.mint, market.address, market),
+        instructions::cancel_launch(&mallory, &launch.mint, false),
+        instructions::close_launch(&mallory, &launch.mint, false),
+    ] {
+        assert_error(env.send(&[ix], &[&mallory]), SamesError::UnauthorizedCreator);
+    }
+    assert_error(env.update_price(&launch, 0), SamesError::ZeroPrice);
+}
+
+#[test]
+fn market_registry_validates_entries() {
+    let env = Env::new();
+    let launch = env.launch(launch_args());
+    let send = |ix| env.send(&[ix], &[&launch.creator]);
+    let add = |market| instructions::add_market(&launch.creator, &launch.mint, market);
+
+    let cpmm = || cpmm_market(&env, &launch);
+    let unpaired = MarketEntry { sol_reserve: Pubkey::default(), ..cpmm() };
+    assert_error(send(add(unpaired)), SamesError::InvalidMarket);
+    let with_reserves = MarketEntry { kind: MarketKind::Orderbook, ..cpmm() };
+    assert_error(send(add(with_reserves)), SamesError::InvalidMarket);
+
+    // Reserves must be token accounts of this launch's mint and wrapped SOL.
+    let other_token = MarketEntry { token_reserve: env.forge_token_account(NATIVE_MINT, 0), ..cpmm() };
+    assert_error(send(add(other_token)), SamesError::InvalidMarket);
+    let not_sol = MarketEntry { sol_reserve: env.forge_token_account(launch.mint, 0), ..cpmm() };
+    assert_error(send(add(not_sol)), SamesError::InvalidMarket);
+    let mut without_reserves = add(cpmm());
+    without_reserves.accounts.truncate(without_reserves.accounts.len() - 2);
+    assert_error(send(without_reserves), SamesError::InvalidMarket);
+
+    let first = cpmm();
+    send(add(first)).unwrap();
+    assert_error(send(add(first)), SamesError::MarketAlreadyRegistered);
+    let unknown = Pubkey::new_unique();
+    assert_error(
+        send(instructions::remove_market(&launch.creator, &launch.mint, unknown)),
+        SamesError::MarketNotFound,
+    );
+    assert_error(
+        send(instructions::replace_market(&launch.creator, &launch.mint, unknown, cpmm())),
+        SamesError::MarketNotFound,
+    );
+
+    // Each Cpmm market adds its reserves to every transfer, so they're capped.
+    for _ in 1..8 {
+        send(add(cpmm())).unwrap();
+    }
+    assert_error(send(add(cpmm())), SamesError::MarketRegistryFull);
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Protocol administration
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[test]
+fn admin_instructions_check_the_admin() {
+    let env = Env::new();
+    let mallory = env.user();
+    for ix in [
+        instructions::update_protocol_config(&mallory, params()),
+        instructions::update_protocol_bounds(&mallory, ProtocolBounds::LIMITS),
+        instructions::propose_admin(&mallory, mallory),
+        instructions::add_dex_program(&mallory, Pubkey::new_unique(), vec![]),
+        instructions::remove_dex_program(&mallory, Pubkey::new_unique()),
+        instructions::set_guardian(&mallory, mallory),
+    ] {
+        assert_error(env.send(&[ix], &[&mallory]), SamesError::UnauthorizedAdmin);
+    }
+    // Nobody has been proposed.
+    assert_error(env.send(&[instructions::accept_admin(&mallory)], &[&mallory]), SamesError::UnauthorizedAdmin);
+
+    let bad = ProtocolParams { platform_fee_bps: 501, ..params() };
+    assert_error(
+        env.send(&[instructions::update_protocol_config(&env.admin, bad)], &[&env.admin]),
+        SamesError::InvalidProtocolParams,
+    );
+}
+
+#[test]
+fn protocol_bounds_stay_inside_the_limits_and_hold_the_params() {
+    let env = Env::new();
+    let send = |bounds| env.send(&[instructions::update_protocol_bounds(&env.admin, bounds)], &[&env.admin]);
+    let limits = ProtocolBounds::LIMITS;
+    let max_fee = |platform_fee_bps| ProtocolBounds { max: ProtocolParams { platform_fee_bps, ..limits.max }, ..limits };
+    let min_fee = |platform_fee_bps| ProtocolBounds { min: ProtocolParams { platform_fee_bps, ..limits.min }, ..limits };
+
+    // Past the hard limit, empty, or excluding the current 100 bps fee.
+    assert_error(send(max_fee(501)), SamesError::InvalidProtocolBounds);
+    let empty = ProtocolBounds { min: min_fee(300).min, max: max_fee(200).max };
+    assert_error(send(empty), SamesError::InvalidProtocolBounds);
+    assert_error(send(max_fee(50)), SamesError::InvalidProtocolBounds);
+    assert_error(send(min_fee(150)), SamesError::InvalidProtocolBounds);
+    send(min_fee(100)).unwrap();
+}
+
+#[test]
+fn dex_allowlist_is_validated_and_bounded() {
+    let env = Env::new();
+    let send = |ix| env.send(&[ix], &[&env.admin]);
+    let add = |program_id, seeds| instructions::add_dex_program(&env.admin, program_id, seeds);
+
+    assert_error(send(add(anchor_lang::system_program::ID, vec![])), SamesError::InvalidDexProgram);
+    assert_error(send(add(sames::ID, vec![])), SamesError::InvalidDexProgram);
+    // Seeds that land on the curve don't name a PDA.
+    let dex = Pubkey::new_unique();
+    let on_curve = (0..=u8::MAX)
+        .map(|bump| vec![b"pool".to_vec(), vec![bump]])
+        .find(|seeds| {
+            let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+            Pubkey::create_program_address(&seeds, &dex).is_err()
+        })
+        .expect("some bump is on the curve");
+    assert_error(send(add(dex, on_curve)), SamesError::InvalidDexProgram);
+    // Every entry names its pool authority.
+    assert_error(send(add(dex, vec![])), SamesError::InvalidDexProgram);
+
+    let seeds = |program_id| {
+        let (_, bump) = Pubkey::find_program_address(&[b"pool"], &program_id);
+        vec![b"pool".to_vec(), vec![bump]]
+    };
+    send(add(dex, seeds(dex))).unwrap();
+    assert_error(send(add(dex, seeds(dex))), SamesError::InvalidDexProgram);
+    assert_error(
+        send(instructions::remove_dex_program(&env.admin, Pubkey::new_unique())),
+        SamesError::InvalidDexProgram,
+    );
+    for _ in 1..ProtocolConfig::MAX_DEX_PROGRAMS {
+        let program_id = Pubkey::new_unique();
+        send(add(program_id, seeds(program_id))).unwrap();
+    }
+    let program_id = Pubkey::new_unique();
+    assert_error(send(add(program_id, seeds(program_id))), SamesError::DexAllowlistFull);
+}
+
+#[test]
+fn pausing_is_guarded() {
+    let env = Env::new();
+    let (alice, mallory) = (env.user(), env.user());
+    let launch = env.launch(launch_args());
+    assert_error(
+        env.send(&[instructions::set_protocol_pause(&mallory, pause::ALL)], &[&mallory]),
+        SamesError::UnauthorizedGuardian,
+    );
+    assert_error(
+        env.send(&[instructions::set_launch_pause(&mallory, &launch.mint, pause::ALL)], &[&mallory]),
+        SamesError::UnauthorizedGuardian,
+    );
+    assert_error(
+        env.send(&[instructions::set_protocol_pause(&env.admin, 1 << 6)], &[&env.admin]),
+        SamesError::InvalidPauseFlags,
+    );
+    assert_error(
+        env.send(&[instructions::set_launch_pause(&env.admin, &launch.mint, 1 << 6)], &[&env.admin]),
+        SamesError::InvalidPauseFlags,
+    );
+
+    env.send(&[instructions::set_protocol_pause(&env.admin, pause::PRESALE_BUY)], &[&env.admin]).unwrap();
+    assert_error(env.buy_presale(&launch, &alice, LAMPORTS_PER_SOL), SamesError::Paused);
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Cancellation, refunds and cleanup
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[test]
+fn cancellation_ends_at_the_first_curve_trade() {
+    let env = Env::new();
+    let (alice, carol) = (env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    env.buy_curve(&launch, &carol, LAMPORTS_PER_SOL).unwrap();
+    let cancel = instructions::cancel_launch(&launch.creator, &launch.mint, false);
+    assert_error(env.send(&[cancel], &[&launch.creator]), SamesError::NotCancellable);
+
+    let refund = instructions::claim_refund(&alice, &launch.mint, true, false);
+    assert_error(env.send(&[refund], &[&alice]), SamesError::NotClosed);
+    let close = instructions::close_launch(&launch.creator, &launch.mint, false);
+    assert_error(env.send(&[close], &[&launch.creator]), SamesError::NotClosed);
+}
+
+#[test]
+fn cancelling_needs_the_escrow_once_it_holds_tokens() {
+    let env = Env::new();
+    let alice = env.user();
+    let locked = VestingSchedule { cliff_seconds: 0, duration_seconds: 100 };
+    let args = CreateLaunch { creator_allocation_bps: 1_000, creator_vesting: locked, ..launch_args() };
+    let launch = env.live_launch(args, &[(alice, LAMPORTS_PER_SOL)]);
+    let cancel = instructions::cancel_launch(&launch.creator, &launch.mint, false);
+    assert_error(env.send(&[cancel], &[&launch.creator]), SamesError::MissingTokenAccount);
+}
+
+#[test]
+fn refunds_are_paid_once() {
+    let env = Env::new();
+    let (alice, bob) = (env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL), (bob, LAMPORTS_PER_SOL)]);
+    env.send(&[instructions::cancel_launch(&launch.creator, &launch.mint, false)], &[&launch.creator]).unwrap();
+
+    // Alice's allocation is in her wallet and has to be burned.
+    let without_tokens = instructions::claim_refund(&alice, &launch.mint, false, false);
+    assert_error(env.send(&[without_tokens], &[&alice]), SamesError::MissingTokenAccount);
+    let refund = [instructions::claim_refund(&alice, &launch.mint, true, false)];
+    env.send(&refund, &[&alice]).unwrap();
+    assert_error(env.send(&refund, &[&alice]), SamesError::NothingToRefund);
+
+    let close = instructions::close_launch(&launch.creator, &launch.mint, false);
+    assert_error(env.send(&[close], &[&launch.creator]), SamesError::RefundsOutstanding);
+
+    env.send(&[instructions::set_protocol_pause(&env.admin, pause::REFUND)], &[&env.admin]).unwrap();
+    let refund = instructions::claim_refund(&bob, &launch.mint, true, false);
+    assert_error(env.send(&[refund], &[&bob]), SamesError::Paused);
+}
+
+#[test]
+fn buyer_records_stay_while_needed() {
+    let env = Env::new();
+    let alice = env.user();
+    let launch = env.launch(launch_args());
+    env.buy_presale(&launch, &alice, LAMPORTS_PER_SOL).unwrap();
+    let close = instructions::close_buyer_record(&alice, &launch.mint, &alice);
+    assert_error(env.send(&[close], &[&alice]), SamesError::BuyerRecordInUse);
+}
@@ -0,0 +1,477 @@
+//! Spoofing vectors for the accounts the transfer hook trusts.
+//!
+//! Every case must be rejected rather than treated as "no price floor".
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use anchor_lang::error::{Error, ErrorCode};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_lang::{system_program, Discriminator};
+use sames::errors::SamesError;
+use sames::hook::{buyer_record_address, resolve_buyer_record, TransferHook, TransferHookBumps};
+use sames::state::{
+    BuyerRecord, DexProgram, LaunchPool, LaunchStatus, MarketRegistry, ProtocolBounds, ProtocolConfig, ProtocolParams,
+};
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Fixtures
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Build an `AccountInfo` whose backing memory lives for the whole test.
+fn account(key: Pubkey, owner: Pubkey, data: Vec<u8>) -> AccountInfo<'static> {
+    AccountInfo::new(
+        Box::leak(Box::new(key)),
+        false,
+        true,
+        Box::leak(Box::new(1_000_000_000u64)),
+        Box::leak(data.into_boxed_slice()),
+        Box::leak(Box::new(owner)),
+        false,
+        0,
+    )
+}
+
+/// The program's own account, as the event CPI needs it.
+fn program_account() -> AccountInfo<'static> {
+    let mut info = account(sames::ID, bpf_loader_upgradeable::ID, vec![]);
+    info.executable = true;
+    info
+}
+
+fn serialize<T: AccountSerialize>(value: &T) -> Vec<u8> {
+    let mut data = Vec::new();
+    value.try_serialize(&mut data).unwrap();
+    data
+}
+
+fn pda(seeds: &[&[u8]]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(seeds, &sames::ID)
+}
+
+fn launch_pool(mint: Pubkey, bump: u8, vault_bump: u8) -> LaunchPool {
+    LaunchPool {
+        mint,
+        total_supply: 1_000_000,
+        price_lamports: 1_000,
+        slope_scaled: 100,
+        status: LaunchStatus::BondingCurve,
+        bump,
+        vault_bump,
+        ..common::launch_pool()
+    }
+}
+
+fn market_registry(launch_pool: Pubkey, bump: u8) -> MarketRegistry {
+    MarketRegistry {
+        launch_pool,
+        authority: Pubkey::new_unique(),
+        markets: Vec::new(),
+        bump,
+    }
+}
+
+fn protocol_config(bump: u8) -> ProtocolConfig {
+    ProtocolConfig {
+        admin: Pubkey::new_unique(),
+        pending_admin: Pubkey::default(),
+        bump,
+        params: ProtocolParams::default(),
+        bounds: ProtocolBounds::LIMITS,
+        guardian: Pubkey::new_unique(),
+        paused: 0,
+        dex_programs: Vec::new(),
+    }
+}
+
+fn buyer_record(launch_pool: Pubkey, buyer: Pubkey, bump: u8) -> BuyerRecord {
+    BuyerRecord {
+        launch_pool,
+        buyer,
+        sol_deposited: 1_000,
+        entry_price: 1_000,
+        tokens_allocated: 1,
+        tokens_sold: 0,
+        curve_sol_spent: 0,
+        curve_tokens_bought: 0,
+        bump,
+        tokens_received: 0,
+        tokens_sent: 0,
+        tokens_vesting: 0,
+        rent_payer: buyer,
+        rent_paid: 0,
+        curve_tokens_received: 0,
+    }
+}
+
+fn assert_sames_error<T: std::fmt::Debug>(result: Result<T>, expected: SamesError) {
+    assert_error_code(result, expected.into());
+}
+
+fn assert_error_code<T: std::fmt::Debug>(result: Result<T>, expected: u32) {
+    match result.expect_err("spoofed account was accepted") {
+        Error::AnchorError(e) => assert_eq!(e.error_code_number, expected, "{}", e.error_name),
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+/// A launch with every account the hook expects, laid out as Token-2022
+/// passes them. Tests break one account at a time.
+struct HookFixture {
+    mint: Pubkey,
+    launch_pool: Pubkey,
+    accounts: Vec<AccountInfo<'static>>,
+}
+
+const LAUNCH_POOL_INDEX: usize = 5;
+const MARKET_REGISTRY_INDEX: usize = 7;
+const PROTOCOL_CONFIG_INDEX: usize = 9;
+
+impl HookFixture {
+    fn new() -> Self {
+        let mint = Pubkey::new_unique();
+        let (launch_pool_key, pool_bump) = pda(&[b"launch_pool", mint.as_ref()]);
+        let (_, vault_bump) = pda(&[b"vault", launch_pool_key.as_ref()]);
+        let (registry_key, registry_bump) = pda(&[b"market_registry", launch_pool_key.as_ref()]);
+        let (extra_metas, _) = pda(&[b"extra-account-metas", mint.as_ref()]);
+        let (config_key, config_bump) = pda(&[b"protocol_config"]);
+        let (event_authority, _) = pda(&[b"__event_authority"]);
+        let sender = Pubkey::new_unique();
+        let receiver = Pubkey::new_unique();
+
+        let accounts = vec![
+            account(Pubkey::new_unique(), spl_token_2022::ID, vec![]),
+            account(mint, spl_token_2022::ID, vec![]),
+            account(Pubkey::new_unique(), spl_token_2022::ID, vec![]),
+            account(sender, system_program::ID, vec![]),
+            account(extra_metas, sames::ID, vec![]),
+            account(
+                launch_pool_key,
+                sames::ID,
+                serialize(&launch_pool(mint, pool_bump, vault_bump)),
+            ),
+            account(buyer_record_address(&launch_pool_key, &sender).0, system_program::ID, vec![]),
+            account(
+                registry_key,
+                sames::ID,
+                serialize(&market_registry(launch_pool_key, registry_bump)),
+            ),
+            account(buyer_record_address(&launch_pool_key, &receiver).0, system_program::ID, vec![]),
+            account(config_key, sames::ID, serialize(&protocol_config(config_bump))),
+            account(event_authority, system_program::ID, vec![]),
+            program_account(),
+        ];
+        Self { mint, launch_pool: launch_pool_key, accounts }
+    }
+
+    fn replace(&mut self, index: usize, info: AccountInfo<'static>) {
+        self.accounts[index] = info;
+    }
+
+    fn try_accounts(self) -> Result<()> {
+        let infos: &'static [AccountInfo<'static>] = Box::leak(self.accounts.into_boxed_slice());
+        let mut remaining = infos;
+        TransferHook::try_accounts(
+            &sames::ID,
+            &mut remaining,
+            &[],
+            &mut TransferHookBumps::default(),
+            &mut BTreeSet::new(),
+        )
+        .map(|_| ())
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// BuyerRecord
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[test]
+fn buyer_record_never_created_has_no_floor() {
+    let launch_pool = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let (key, _) = buyer_record_address(&launch_pool, &owner);
+    let info = account(key, system_program::ID, vec![]);
+
+    assert!(resolve_buyer_record(&info, &launch_pool, &owner).unwrap().is_none());
+}
+
+#[test]
+fn buyer_record_genuine_is_loaded() {
+    let launch_pool = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let (key, bump) = buyer_record_address(&launch_pool, &owner);
+    let info = account(key, sames::ID, serialize(&buyer_record(launch_pool, owner, bump)));
+
+    let record = resolve_buyer_record(&info, &launch_pool, &owner).unwrap().unwrap();
+    assert_eq!(record.entry_price, 1_000);
+}
+
+#[test]
+fn buyer_record_of_another_wallet_is_rejected() {
+    let launch_pool = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+    // An empty record of some other wallet must not stand in for "no record".
+    let (key, _) = buyer_record_address(&launch_pool, &other);
+    let info = account(key, system_program::ID, vec![]);
+
+    assert_sames_error(
+        resolve_buyer_record(&info, &launch_pool, &owner),
+        SamesError::InvalidBuyerRecord,
+    );
+}
+
+#[test]
+fn buyer_record_at_a_random_empty_address_is_rejected() {
+    let launch_pool = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let info = account(Pubkey::new_unique(), system_program::ID, vec![]);
+
+    assert_sames_error(
+        resolve_buyer_record(&info, &launch_pool, &owner),
+        SamesError::InvalidBuyerRecord,
+    );
+}
+
+#[test]
+fn buyer_record_owned_by_another_program_is_rejected() {
+    let launch_pool = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let (key, bump) = buyer_record_address(&launch_pool, &owner);
+    let info = account(
+        key,
+        Pubkey::new_unique(),
+        serialize(&buyer_record(launch_pool, owner, bump)),
+    );
+
+    assert_sames_error(
+        resolve_buyer_record(&info, &launch_pool, &owner),
+        SamesError::InvalidBuyerRecord,
+    );
+}
+
+#[test]
+fn buyer_record_with_wrong_discriminator_is_rejected() {
+    let launch_pool = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let (key, bump) = buyer_record_address(&launch_pool, &owner);
+    let mut data = serialize(&buyer_record(launch_pool, owner, bump));
+    data[..8].copy_from_slice(&MarketRegistry::DISCRIMINATOR);
+    let info = account(key, sames::ID, data);
+
+    assert_sames_error(
+        resolve_buyer_record(&info, &launch_pool, &owner),
+        SamesError::InvalidBuyerRecord,
+    );
+}
+
+#[test]
+fn buyer_record_truncated_is_rejected() {
+    let launch_pool = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let (key, _) = buyer_record_address(&launch_pool, &owner);
+    let info = account(key, sames::ID, BuyerRecord::DISCRIMINATOR[..4].to_vec());
+
+    assert_sames_error(
+        resolve_buyer_record(&info, &launch_pool, &owner),
+        SamesError::InvalidBuyerRecord,
+    );
+}
+
+#[test]
+fn buyer_record_for_another_launch_is_rejected() {
+    let launch_pool = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let (key, bump) = buyer_record_address(&launch_pool, &owner);
+    let info = account(
+        key,
+        sames::ID,
+        serialize(&buyer_record(Pubkey::new_unique(), owner, bump)),
+    );
+
+    assert_sames_error(
+        resolve_buyer_record(&info, &launch_pool, &owner),
+        SamesError::InvalidBuyerRecord,
+    );
+}
+
+#[test]
+fn buyer_record_for_another_buyer_is_rejected() {
+    let launch_pool = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let (key, bump) = buyer_record_address(&launch_pool, &owner);
+    let info = account(
+        key,
+        sames::ID,
+        serialize(&buyer_record(launch_pool, Pubkey::new_unique(), bump)),
+    );
+
+    assert_sames_error(
+        resolve_buyer_record(&info, &launch_pool, &owner),
+        SamesError::InvalidBuyerRecord,
+    );
+}
+
+#[test]
+fn buyer_record_system_owned_with_data_is_rejected() {
+    let launch_pool = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let (key, bump) = buyer_record_address(&launch_pool, &owner);
+    let info = account(
+        key,
+        system_program::ID,
+        serialize(&buyer_record(launch_pool, owner, bump)),
+    );
+
+    assert_sames_error(
+        resolve_buyer_record(&info, &launch_pool, &owner),
+        SamesError::InvalidBuyerRecord,
+    );
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// LaunchPool and MarketRegistry
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[test]
+fn hook_accounts_genuine_are_accepted() {
+    HookFixture::new().try_accounts().unwrap();
+}
+
+#[test]
+fn hook_accounts_need_no_launch_pool_writes() {
+    // Lists written before the launch pool became read-only pass it
+    // writable; newer lists pass it read-only. Both must work.
+    let mut fixture = HookFixture::new();
+    fixture.accounts[LAUNCH_POOL_INDEX].is_writable = false;
+    fixture.try_accounts().unwrap();
+}
+
+#[test]
+fn launch_pool_owned_by_another_program_is_rejected() {
+    let mut fixture = HookFixture::new();
+    let data = fixture.accounts[LAUNCH_POOL_INDEX].try_borrow_data().unwrap().to_vec();
+    fixture.replace(
+        LAUNCH_POOL_INDEX,
+        account(fixture.launch_pool, Pubkey::new_unique(), data),
+    );
+
+    assert_error_code(fixture.try_accounts(), ErrorCode::AccountOwnedByWrongProgram.into());
+}
+
+#[test]
+fn launch_pool_with_wrong_discriminator_is_rejected() {
+    let mut fixture = HookFixture::new();
+    let mut data = fixture.accounts[LAUNCH_POOL_INDEX].try_borrow_data().unwrap().to_vec();
+    data[..8].copy_from_slice(&BuyerRecord::DISCRIMINATOR);
+    fixture.replace(LAUNCH_POOL_INDEX, account(fixture.launch_pool, sames::ID, data));
+
+    assert_error_code(fixture.try_accounts(), ErrorCode::AccountDiscriminatorMismatch.into());
+}
+
+#[test]
+fn launch_pool_not_at_its_pda_is_rejected() {
+    let mut fixture = HookFixture::new();
+    let data = fixture.accounts[LAUNCH_POOL_INDEX].try_borrow_data().unwrap().to_vec();
+    fixture.replace(LAUNCH_POOL_INDEX, account(Pubkey::new_unique(), sames::ID, data));
+
+    assert_error_code(fixture.try_accounts(), ErrorCode::ConstraintSeeds.into());
+}
+
+#[test]
+fn launch_pool_of_another_mint_is_rejected() {
+    let mut fixture = HookFixture::new();
+    // Genuine pool for a different mint, placed at this mint's PDA address.
+    let other = launch_pool(Pubkey::new_unique(), pda(&[b"launch_pool", fixture.mint.as_ref()]).1, 0);
+    fixture.replace(
+        LAUNCH_POOL_INDEX,
+        account(fixture.launch_pool, sames::ID, serialize(&other)),
+    );
+
+    assert_sames_error(fixture.try_accounts(), SamesError::InvalidMint);
+}
+
+#[test]
+fn market_registry_owned_by_another_program_is_rejected() {
+    let mut fixture = HookFixture::new();
+    let info = &fixture.accounts[MARKET_REGISTRY_INDEX];
+    let (key, data) = (info.key(), info.try_borrow_data().unwrap().to_vec());
+    fixture.replace(MARKET_REGISTRY_INDEX, account(key, Pubkey::new_unique(), data));
+
+    assert_error_code(fixture.try_accounts(), ErrorCode::AccountOwnedByWrongProgram.into());
+}
+
+#[test]
+fn market_registry_with_wrong_discriminator_is_rejected() {
+    let mut fixture = HookFixture::new();
+    let info = &fixture.accounts[MARKET_REGISTRY_INDEX];
+    let (key, mut data) = (info.key(), info.try_borrow_data().unwrap().to_vec());
+    data[..8].copy_from_slice(&LaunchPool::DISCRIMINATOR);
+    fixture.replace(MARKET_REGISTRY_INDEX, account(key, sames::ID, data));
+
+    assert_error_code(fixture.try_accounts(), ErrorCode::AccountDiscriminatorMismatch.into());
+}
+
+#[test]
+fn market_registry_of_another_launch_is_rejected() {
+    let mut fixture = HookFixture::new();
+    let other_pool = Pubkey::new_unique();
+    let (key, bump) = pda(&[b"market_registry", other_pool.as_ref()]);
+    fixture.replace(
+        MARKET_REGISTRY_INDEX,
+        account(key, sames::ID, serialize(&market_registry(other_pool, bump))),
+    );
+
+    assert_error_code(fixture.try_accounts(), ErrorCode::ConstraintSeeds.into());
+}
+
+#[test]
+fn market_registry_pointing_at_another_launch_is_rejected() {
+    let mut fixture = HookFixture::new();
+    let info = &fixture.accounts[MARKET_REGISTRY_INDEX];
+    let key = info.key();
+    let bump = pda(&[b"market_registry", fixture.launch_pool.as_ref()]).1;
+    fixture.replace(
+        MARKET_REGISTRY_INDEX,
+        account(key, sames::ID, serialize(&market_registry(Pubkey::new_unique(), bump))),
+    );
+
+    assert_sames_error(fixture.try_accounts(), SamesError::InvalidMarket);
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// ProtocolConfig and the DEX allowlist
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[test]
+fn protocol_config_owned_by_another_program_is_rejected() {
+    let mut fixture = HookFixture::new();
+    let info = &fixture.accounts[PROTOCOL_CONFIG_INDEX];
+    let (key, data) = (info.key(), info.try_borrow_data().unwrap().to_vec());
+    fixture.replace(PROTOCOL_CONFIG_INDEX, account(key, Pubkey::new_unique(), data));
+
+    assert_error_code(fixture.try_accounts(), ErrorCode::AccountOwnedByWrongProgram.into());
+}
+
+#[test]
+fn protocol_config_not_at_its_pda_is_rejected() {
+    let mut fixture = HookFixture::new();
+    let data = fixture.accounts[PROTOCOL_CONFIG_INDEX].try_borrow_data().unwrap().to_vec();
+    fixture.replace(PROTOCOL_CONFIG_INDEX, account(Pubkey::new_unique(), sames::ID, data));
+
+    assert_error_code(fixture.try_accounts(), ErrorCode::ConstraintSeeds.into());
+}
+
+#[test]
+fn dex_with_authority_matches_only_that_authority() {
+    let program_id = Pubkey::new_unique();
+    let (authority, _) = Pubkey::find_program_address(&[b"vault_authority"], &program_id);
+    let dex = DexProgram { program_id, authority };
+
+    assert!(dex.owns(&authority));
+    // Another account of the same program isn't the pool authority.
+    assert!(!dex.owns(&Pubkey::new_unique()));
+}
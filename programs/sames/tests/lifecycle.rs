@@ -0,0 +1,421 @@
+//! End-to-end launches: create → presale → allocation → curve → graduation,
+//! vesting claims, cancellation with refunds, and protocol administration.
+
+mod svm;
+
+use anchor_lang::prelude::Pubkey;
+use sames::errors::SamesError;
+use sames::events::{
+    AdminChanged, AdminProposed, AllocationClaimed, BuyerRecordClosed, CurveTrade, DexProgramAdded,
+    DexProgramRemoved, Graduated, GuardianSet, LaunchCancelled, LaunchClosed, LaunchCreated, PauseSet,
+    PresaleBuy, ProtocolBoundsUpdated, ProtocolParamsUpdated, RefundClaimed,
+};
+use sames::instruction::CreateLaunch;
+use sames::state::{
+    pause, LaunchPool, LaunchStatus, MarketKind, ProtocolBounds, ProtocolConfig, ProtocolParams, VestingSchedule,
+};
+use sames_sdk::{instructions, pda};
+use svm::launch::*;
+
+#[test]
+fn presale_to_graduation() {
+    let env = Env::new();
+    let (alice, bob, carol, dave) = (env.user(), env.user(), env.user(), env.user());
+    let launch = env.launch(launch_args());
+    let created = env.svm.events::<LaunchCreated>();
+    assert_eq!(created.len(), 1);
+    assert_eq!(created[0].mint, launch.mint);
+    assert_eq!(created[0].end_time, env.svm.now() + PRESALE_SECONDS);
+
+    env.buy_presale(&launch, &alice, LAMPORTS_PER_SOL).unwrap();
+    env.buy_presale(&launch, &bob, 3 * LAMPORTS_PER_SOL).unwrap();
+    assert_eq!(env.svm.events::<PresaleBuy>()[0].total_sol_collected, 4 * LAMPORTS_PER_SOL);
+    assert_eq!(env.vault_lamports(&launch), 4 * LAMPORTS_PER_SOL);
+    assert_eq!(env.pool(&launch).buyer_count, 2);
+
+    env.svm.warp(PRESALE_SECONDS);
+    env.finalize(&launch, &alice).unwrap();
+    env.finalize(&launch, &bob).unwrap();
+    assert_eq!(env.balance(&launch, &alice), SUPPLY / 4);
+    assert_eq!(env.balance(&launch, &bob), SUPPLY / 4 * 3);
+    let record = env.record(&launch, &alice).unwrap();
+    assert_eq!((record.tokens_allocated, record.entry_price), (SUPPLY / 4, PRESALE_ENTRY));
+
+    env.start_curve(&launch).unwrap();
+    assert_eq!(env.pool(&launch).status, LaunchStatus::BondingCurve);
+
+    // Buy, then sell part of it back.
+    env.buy_curve(&launch, &carol, LAMPORTS_PER_SOL).unwrap();
+    let buy = env.svm.events::<CurveTrade>().remove(0);
+    assert!(buy.is_buy);
+    assert!(buy.sol_amount <= LAMPORTS_PER_SOL);
+    assert_eq!(env.balance(&launch, &carol), buy.token_amount);
+    assert_eq!(env.record(&launch, &carol).unwrap().entry_price, buy.entry_price);
+
+    let carol_before = env.svm.lamports(&carol);
+    env.sell_curve(&launch, &carol, 1_000).unwrap();
+    let sell = env.svm.events::<CurveTrade>().remove(0);
+    assert!(!sell.is_buy);
+    assert_eq!(sell.fee, (sell.sol_amount + sell.fee) / 100);
+    assert_eq!(env.svm.lamports(&carol), carol_before + sell.sol_amount);
+    assert_eq!(env.balance(&launch, &carol), buy.token_amount - 1_000);
+    assert_eq!(env.record(&launch, &carol).unwrap().tokens_sold, 1_000);
+
+    // The vault holds the presale deposits, the curve's reserve and the fee.
+    let pool = env.pool(&launch);
+    assert_eq!(pool.tokens_sold_curve, buy.token_amount - 1_000);
+    assert_eq!(
+        env.vault_lamports(&launch),
+        pool.total_sol_collected + pool.curve_sol_collected + sell.fee
+    );
+
+    assert_error(env.graduate(&launch), SamesError::NotReadyToGraduate);
+    env.buy_curve(&launch, &dave, 3 * LAMPORTS_PER_SOL).unwrap();
+    assert!(env.pool(&launch).curve_sol_collected >= GRADUATION_THRESHOLD);
+
+    env.graduate(&launch).unwrap();
+    let graduated = env.svm.events::<Graduated>().remove(0);
+    let pool = env.pool(&launch);
+    assert_eq!(pool.status, LaunchStatus::Graduated);
+    assert_eq!(graduated.curve_sol_collected, pool.curve_sol_collected);
+    assert_eq!(graduated.tokens_sold_curve, pool.tokens_sold_curve);
+
+    // The curve is closed and the floor is gone, so even a market the hook
+    // can't price accepts presale tokens.
+    assert_error(env.sell_curve(&launch, &dave, 1_000), SamesError::NotBondingCurve);
+    let desk = env.user();
+    let market = env.register_market(&launch, &desk, MarketKind::Orderbook);
+    env.transfer(&launch, &alice, &desk, 1_000).unwrap();
+    assert_eq!(env.svm.token_balance(&market), 1_000);
+}
+
+#[test]
+fn vesting_claims() {
+    let env = Env::new();
+    let alice = env.user();
+    let launch = env.live_launch(
+        CreateLaunch {
+            presale_vesting: VestingSchedule { cliff_seconds: 100, duration_seconds: 200 },
+            creator_allocation_bps: 1_000,
+            creator_vesting: VestingSchedule { cliff_seconds: 0, duration_seconds: 100 },
+            ..launch_args()
+        },
+        &[(alice, LAMPORTS_PER_SOL)],
+    );
+    let escrow = pda::vesting_escrow(&launch.pool).0;
+    let creator_escrow = pda::creator_escrow(&launch.pool).0;
+    let presale_supply = SUPPLY / 10 * 9;
+    let creator_allocation = SUPPLY / 10;
+
+    // Allocations sit in escrow until they vest, the creator's in an escrow
+    // of its own.
+    assert_eq!(env.balance(&launch, &alice), 0);
+    assert_eq!(env.svm.token_balance(&escrow), presale_supply);
+    assert_eq!(env.svm.token_balance(&creator_escrow), creator_allocation);
+    assert_eq!(env.record(&launch, &alice).unwrap().tokens_vesting, presale_supply);
+
+    let claim = [instructions::claim_vested(&alice, &launch.mint)];
+    assert_error(env.send(&claim, &[&alice]), SamesError::TokensStillVesting);
+
+    env.svm.warp(150);
+    env.send(&claim, &[&alice]).unwrap();
+    let claimed = env.svm.events::<AllocationClaimed>().remove(0);
+    assert_eq!(claimed.amount, presale_supply / 4 * 3);
+    assert_eq!(claimed.still_vesting, presale_supply / 4);
+    assert_eq!(env.balance(&launch, &alice), presale_supply / 4 * 3);
+
+    // The creator's schedule has fully elapsed.
+    let creator_claim = [
+        instructions::create_token_account(&launch.creator, &launch.creator, &launch.mint),
+        instructions::claim_creator_allocation(&launch.creator, &launch.mint),
+    ];
+    env.send(&creator_claim, &[&launch.creator]).unwrap();
+    assert_eq!(env.balance(&launch, &launch.creator), creator_allocation);
+    assert_eq!(env.pool(&launch).creator_tokens_claimed, creator_allocation);
+    assert_eq!(env.svm.token_balance(&creator_escrow), 0);
+    assert_eq!(env.svm.token_balance(&escrow), presale_supply / 4);
+    assert_error(env.send(&creator_claim, &[&launch.creator]), SamesError::TokensStillVesting);
+
+    env.svm.warp(50);
+    env.send(&claim, &[&alice]).unwrap();
+    assert_eq!(env.balance(&launch, &alice), presale_supply);
+    assert_eq!(env.record(&launch, &alice).unwrap().tokens_vesting, 0);
+    assert_eq!(env.svm.token_balance(&escrow), 0);
+}
+
+#[test]
+fn cancel_refund_and_close() {
+    let env = Env::new();
+    let (alice, bob) = (env.user(), env.user());
+    let launch = env.live_launch(
+        CreateLaunch {
+            creator_allocation_bps: 1_000,
+            creator_vesting: VestingSchedule { cliff_seconds: 0, duration_seconds: 100 },
+            ..launch_args()
+        },
+        &[(alice, LAMPORTS_PER_SOL), (bob, 2 * LAMPORTS_PER_SOL)],
+    );
+    let escrow = pda::creator_escrow(&launch.pool).0;
+    assert_eq!(env.svm.token_balance(&escrow), SUPPLY / 10);
+
+    // Nobody traded on the curve yet, so the creator can still back out.
+    env.send(&[instructions::cancel_launch(&launch.creator, &launch.mint, true)], &[&launch.creator]).unwrap();
+    assert_eq!(env.svm.events::<LaunchCancelled>()[0].refundable_lamports, 3 * LAMPORTS_PER_SOL);
+    assert_eq!(env.pool(&launch).status, LaunchStatus::Closed);
+    assert_eq!(env.svm.token_balance(&escrow), 0);
+
+    let alice_before = env.svm.lamports(&alice);
+    env.send(&[instructions::claim_refund(&alice, &launch.mint, true, false)], &[&alice]).unwrap();
+    let refund = env.svm.events::<RefundClaimed>().remove(0);
+    assert_eq!((refund.sol_amount, refund.tokens_burned), (LAMPORTS_PER_SOL, SUPPLY / 10 * 3));
+    assert_eq!(env.svm.lamports(&alice), alice_before + LAMPORTS_PER_SOL);
+    assert_eq!(env.balance(&launch, &alice), 0);
+
+    let close = [instructions::close_launch(&launch.creator, &launch.mint, true)];
+    assert_error(env.send(&close, &[&launch.creator]), SamesError::RefundsOutstanding);
+    env.send(&[instructions::claim_refund(&bob, &launch.mint, true, false)], &[&bob]).unwrap();
+    assert_eq!(env.pool(&launch).total_sol_collected, 0);
+
+    // Closing returns every launch account's lamports to the creator.
+    let launch_accounts = [
+        launch.pool,
+        pda::vault(&launch.pool).0,
+        pda::market_registry(&launch.pool).0,
+        pda::extra_account_metas(&launch.mint).0,
+        escrow,
+        pda::vesting_escrow(&launch.pool).0,
+    ];
+    let reclaimed: u64 = launch_accounts.iter().map(|key| env.svm.lamports(key)).sum();
+    let creator_before = env.svm.lamports(&launch.creator);
+    env.send(&close, &[&launch.creator]).unwrap();
+    let closed = env.svm.events::<LaunchClosed>().remove(0);
+    assert_eq!((closed.launch_pool, closed.graduated), (launch.pool, false));
+    assert_eq!(env.svm.lamports(&launch.creator), creator_before + reclaimed);
+    for key in launch_accounts {
+        assert!(env.svm.account(&key).is_none(), "{key} is closed");
+    }
+
+    let record = pda::buyer_record(&launch.pool, &alice).0;
+    let rent = env.svm.lamports(&record);
+    let alice_before = env.svm.lamports(&alice);
+    env.send(&[instructions::close_buyer_record(&alice, &launch.mint, &alice)], &[&alice]).unwrap();
+    let closed = env.svm.events::<BuyerRecordClosed>().remove(0);
+    assert_eq!((closed.launch_pool, closed.buyer), (launch.pool, alice));
+    assert_eq!((closed.rent_payer, closed.rent_refunded), (alice, rent));
+    assert!(env.svm.account(&record).is_none());
+    assert_eq!(env.svm.lamports(&alice), alice_before + rent);
+}
+
+#[test]
+fn buyer_records_return_rent_to_whoever_paid_it() {
+    let env = Env::new();
+    let (alice, bob, dave) = (env.user(), env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+
+    // Bob opens Dave's record so he can send him tokens.
+    env.send(&[instructions::create_buyer_record(&bob, &dave, &launch.mint)], &[&bob]).unwrap();
+    let record = env.record(&launch, &dave).unwrap();
+    let rent = env.svm.lamports(&pda::buyer_record(&launch.pool, &dave).0);
+    assert_eq!((record.rent_payer, record.rent_paid), (bob, rent));
+
+    // Dave can close it, but the rent goes back to Bob.
+    let close = |rent_payer| env.send(&[instructions::close_buyer_record(&dave, &launch.mint, rent_payer)], &[&dave]);
+    assert!(close(&dave).is_err());
+    let (bob_before, dave_before) = (env.svm.lamports(&bob), env.svm.lamports(&dave));
+    close(&bob).unwrap();
+    let closed = env.svm.events::<BuyerRecordClosed>().remove(0);
+    assert_eq!((closed.buyer, closed.rent_payer, closed.rent_refunded), (dave, bob, rent));
+    assert_eq!(env.svm.lamports(&bob), bob_before + rent);
+    assert_eq!(env.svm.lamports(&dave), dave_before);
+}
+
+#[test]
+fn unclaimed_refunds_are_swept_after_the_deadline() {
+    let env = Env::new();
+    let (alice, bob) = (env.user(), env.user());
+    let launch = env.live_launch(
+        CreateLaunch { presale_vesting: VestingSchedule { cliff_seconds: 0, duration_seconds: 100 }, ..launch_args() },
+        &[(alice, LAMPORTS_PER_SOL), (bob, LAMPORTS_PER_SOL)],
+    );
+    let escrow = pda::vesting_escrow(&launch.pool).0;
+
+    // Alice gives away what has vested, so the rest of her allocation
+    // stays in escrow and she never claims a refund.
+    env.svm.warp(50);
+    env.send(&[instructions::claim_vested(&alice, &launch.mint)], &[&alice]).unwrap();
+    env.transfer(&launch, &alice, &bob, env.balance(&launch, &alice)).unwrap();
+    env.send(&[instructions::cancel_launch(&launch.creator, &launch.mint, false)], &[&launch.creator]).unwrap();
+    let deadline = env.svm.now() + LaunchPool::REFUND_WINDOW;
+    assert_eq!(env.svm.events::<LaunchCancelled>()[0].refund_deadline, deadline);
+    assert_eq!(env.pool(&launch).refund_deadline, deadline);
+
+    env.send(&[instructions::claim_refund(&bob, &launch.mint, false, true)], &[&bob]).unwrap();
+    assert_eq!(env.pool(&launch).total_sol_collected, LAMPORTS_PER_SOL);
+    assert!(env.svm.token_balance(&escrow) > 0);
+
+    let close = [instructions::close_launch(&launch.creator, &launch.mint, true)];
+    env.svm.warp(LaunchPool::REFUND_WINDOW - 1);
+    assert_error(env.send(&close, &[&launch.creator]), SamesError::RefundsOutstanding);
+
+    // Past the deadline, Alice's deposit goes to the creator and what's left
+    // of her allocation is burned.
+    env.svm.warp(1);
+    let vault = env.vault_lamports(&launch);
+    assert!(vault >= LAMPORTS_PER_SOL);
+    env.send(&close, &[&launch.creator]).unwrap();
+    assert_eq!(env.svm.events::<LaunchClosed>()[0].swept_lamports, vault);
+    assert!(env.svm.account(&launch.pool).is_none());
+    assert!(env.svm.account(&escrow).is_none());
+}
+
+#[test]
+fn graduated_launches_return_what_the_hook_doesnt_need() {
+    let env = Env::new();
+    let (alice, bob, carol, pool_owner) = (env.user(), env.user(), env.user(), env.user());
+    let launch = env.live_launch(
+        CreateLaunch {
+            creator_allocation_bps: 1_000,
+            creator_vesting: VestingSchedule { cliff_seconds: 0, duration_seconds: 100 },
+            ..launch_args()
+        },
+        &[(alice, LAMPORTS_PER_SOL)],
+    );
+    env.register_cpmm(&launch, &pool_owner, LAMPORTS_PER_SOL);
+    env.buy_curve(&launch, &carol, 3 * LAMPORTS_PER_SOL).unwrap();
+    env.graduate(&launch).unwrap();
+
+    // The creator allocation is still in escrow.
+    let close = [instructions::close_launch(&launch.creator, &launch.mint, true)];
+    assert_error(env.send(&close, &[&launch.creator]), SamesError::EscrowNotEmpty);
+    env.svm.warp(100);
+    let creator_claim = [
+        instructions::create_token_account(&launch.creator, &launch.creator, &launch.mint),
+        instructions::claim_creator_allocation(&launch.creator, &launch.mint),
+    ];
+    env.send(&creator_claim, &[&launch.creator]).unwrap();
+
+    let escrow = pda::creator_escrow(&launch.pool).0;
+    let (registry, meta_list) = (pda::market_registry(&launch.pool).0, pda::extra_account_metas(&launch.mint).0);
+    let escrows = [escrow, pda::vesting_escrow(&launch.pool).0];
+    let rent_before = escrows.iter().chain([&registry, &meta_list]).map(|key| env.svm.lamports(key)).sum::<u64>();
+    let creator_before = env.svm.lamports(&launch.creator);
+    env.send(&close, &[&launch.creator]).unwrap();
+    let closed = env.svm.events::<LaunchClosed>().remove(0);
+    assert_eq!((closed.graduated, closed.swept_lamports), (true, 0));
+    let rent_after = env.svm.lamports(&registry) + env.svm.lamports(&meta_list);
+    assert_eq!(env.svm.lamports(&launch.creator), creator_before + rent_before - rent_after);
+    assert!(escrows.iter().all(|key| env.svm.account(key).is_none()));
+    assert!(env.registry(&launch).markets.is_empty());
+
+    // What the hook reads stays, so the token keeps moving.
+    assert_eq!(env.pool(&launch).status, LaunchStatus::Graduated);
+    env.transfer(&launch, &alice, &bob, 1_000).unwrap();
+    env.send(&[instructions::close_launch(&launch.creator, &launch.mint, false)], &[&launch.creator]).unwrap();
+}
+
+#[test]
+fn refunds_follow_the_tokens_burned() {
+    let env = Env::new();
+    let (alice, bob) = (env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    let allocation = env.balance(&launch, &alice);
+    env.create_record(&launch, &bob).unwrap();
+    env.transfer(&launch, &alice, &bob, allocation / 4).unwrap();
+    env.send(&[instructions::cancel_launch(&launch.creator, &launch.mint, false)], &[&launch.creator]).unwrap();
+
+    // Alice only has three quarters of her allocation left to burn.
+    let refund = [instructions::claim_refund(&alice, &launch.mint, true, false)];
+    env.send(&refund, &[&alice]).unwrap();
+    let claimed = env.svm.events::<RefundClaimed>().remove(0);
+    assert_eq!((claimed.sol_amount, claimed.tokens_burned), (LAMPORTS_PER_SOL / 4 * 3, allocation / 4 * 3));
+    assert_eq!(env.balance(&launch, &alice), 0);
+    assert_error(env.send(&refund, &[&alice]), SamesError::NothingToRefund);
+
+    // The rest is paid out once the tokens come back.
+    env.transfer(&launch, &bob, &alice, allocation / 4).unwrap();
+    env.send(&refund, &[&alice]).unwrap();
+    let claimed = env.svm.events::<RefundClaimed>().remove(0);
+    assert_eq!((claimed.sol_amount, claimed.tokens_burned), (LAMPORTS_PER_SOL / 4, allocation / 4));
+    assert_eq!(env.pool(&launch).total_sol_collected, 0);
+}
+
+#[test]
+fn admin_handover_and_pause() {
+    let mut env = Env::new();
+    let (new_admin, guardian, alice) = (env.user(), env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    let other = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+
+    // Every change is announced, so indexers can follow the config.
+    let updated = ProtocolParams { platform_fee_bps: 200, ..params() };
+    env.send(&[instructions::update_protocol_config(&env.admin, updated)], &[&env.admin]).unwrap();
+    assert_eq!(env.svm.events::<ProtocolParamsUpdated>()[0].params, updated);
+    let dex = Pubkey::new_unique();
+    let (authority, bump) = Pubkey::find_program_address(&[b"pool"], &dex);
+    env.send(&[instructions::add_dex_program(&env.admin, dex, vec![b"pool".to_vec(), vec![bump]])], &[&env.admin])
+        .unwrap();
+    let added = env.svm.events::<DexProgramAdded>().remove(0);
+    assert_eq!((added.program_id, added.authority), (dex, authority));
+    env.send(&[instructions::remove_dex_program(&env.admin, dex)], &[&env.admin]).unwrap();
+    assert_eq!(env.svm.events::<DexProgramRemoved>()[0].program_id, dex);
+
+    env.send(&[instructions::propose_admin(&env.admin, new_admin)], &[&env.admin]).unwrap();
+    let proposed = env.svm.events::<AdminProposed>().remove(0);
+    assert_eq!((proposed.admin, proposed.pending_admin), (env.admin, new_admin));
+    assert_error(env.send(&[instructions::accept_admin(&alice)], &[&alice]), SamesError::UnauthorizedAdmin);
+    env.send(&[instructions::accept_admin(&new_admin)], &[&new_admin]).unwrap();
+    let changed = env.svm.events::<AdminChanged>().remove(0);
+    assert_eq!((changed.previous, changed.admin), (env.admin, new_admin));
+    let config: ProtocolConfig = env.svm.anchor(&pda::protocol_config().0);
+    assert_eq!(config.admin, new_admin);
+    assert_error(
+        env.send(&[instructions::set_guardian(&env.admin, guardian)], &[&env.admin]),
+        SamesError::UnauthorizedAdmin,
+    );
+    env.admin = new_admin;
+    env.send(&[instructions::set_guardian(&env.admin, guardian)], &[&env.admin]).unwrap();
+    assert_eq!(env.svm.events::<GuardianSet>()[0].guardian, guardian);
+
+    // Protocol-wide pause of curve buys; sells still go through.
+    env.send(&[instructions::set_protocol_pause(&guardian, pause::CURVE_BUY)], &[&guardian]).unwrap();
+    let paused = env.svm.events::<PauseSet>().remove(0);
+    assert_eq!((paused.launch_pool, paused.flags), (None, pause::CURVE_BUY));
+    assert_error(env.buy_curve(&launch, &alice, LAMPORTS_PER_SOL), SamesError::Paused);
+    env.send(&[instructions::set_protocol_pause(&guardian, 0)], &[&guardian]).unwrap();
+    env.buy_curve(&launch, &alice, LAMPORTS_PER_SOL).unwrap();
+
+    // A launch pause only stops that launch.
+    env.send(&[instructions::set_launch_pause(&guardian, &launch.mint, pause::ALL)], &[&guardian]).unwrap();
+    let paused = env.svm.events::<PauseSet>().remove(0);
+    assert_eq!((paused.launch_pool, paused.flags), (Some(launch.pool), pause::ALL));
+    assert_error(env.sell_curve(&launch, &alice, 1_000), SamesError::Paused);
+    assert_error(env.graduate(&launch), SamesError::Paused);
+    env.buy_curve(&other, &alice, LAMPORTS_PER_SOL).unwrap();
+    env.send(&[instructions::set_launch_pause(&guardian, &launch.mint, 0)], &[&guardian]).unwrap();
+    env.sell_curve(&launch, &alice, 1_000).unwrap();
+}
+
+#[test]
+fn protocol_bounds_limit_the_params() {
+    let env = Env::new();
+    let send = |ix| env.send(&[ix], &[&env.admin]);
+    let config = || env.svm.anchor::<ProtocolConfig>(&pda::protocol_config().0);
+    assert_eq!(config().bounds, ProtocolBounds::LIMITS);
+
+    // The admin narrows the fee range without an upgrade...
+    let limits = ProtocolBounds::LIMITS;
+    let narrowed = ProtocolBounds { max: ProtocolParams { platform_fee_bps: 150, ..limits.max }, ..limits };
+    send(instructions::update_protocol_bounds(&env.admin, narrowed)).unwrap();
+    assert_eq!(env.svm.events::<ProtocolBoundsUpdated>()[0].bounds, narrowed);
+    assert_eq!(config().bounds, narrowed);
+
+    // ...and params are checked against the stored bounds from then on.
+    let fee = |platform_fee_bps| ProtocolParams { platform_fee_bps, ..params() };
+    assert_error(send(instructions::update_protocol_config(&env.admin, fee(200))), SamesError::InvalidProtocolParams);
+    send(instructions::update_protocol_config(&env.admin, fee(150))).unwrap();
+
+    // Widening back up to the hard limits is allowed.
+    send(instructions::update_protocol_bounds(&env.admin, limits)).unwrap();
+    send(instructions::update_protocol_config(&env.admin, fee(500))).unwrap();
+    assert_eq!(config().params.platform_fee_bps, 500);
+}
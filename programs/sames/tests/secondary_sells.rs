@@ -0,0 +1,167 @@
+//! Holders who got their tokens on a market, without a BuyerRecord, selling
+//! back into the curve against the inventory transfers carried off.
+
+mod svm;
+
+use anchor_lang::prelude::Pubkey;
+use sames::errors::SamesError;
+use sames::events::{CurveInventoryReleased, CurveTrade};
+use sames::state::MarketKind;
+use sames_sdk::instructions;
+use svm::launch::*;
+
+#[test]
+fn market_buyers_sell_back_without_a_floor() {
+    let env = Env::new();
+    let (alice, carol, bob, desk, erin) = (env.user(), env.user(), env.user(), env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    env.register_market(&launch, &desk, MarketKind::BondingCurveVault);
+    env.buy_curve(&launch, &carol, LAMPORTS_PER_SOL).unwrap();
+    env.buy_curve(&launch, &bob, LAMPORTS_PER_SOL).unwrap();
+
+    // Carol's curve tokens leave her record for the market, and from there
+    // reach Erin, who has no record. The hook leaves the LaunchPool alone;
+    // releasing Carol's record hands her inventory over.
+    let bought = env.balance(&launch, &carol);
+    env.transfer(&launch, &carol, &desk, bought).unwrap();
+    assert_eq!(env.pool(&launch).curve_tokens_unassigned, 0);
+    assert_eq!(env.record(&launch, &carol).unwrap().unreleased_inventory(), bought);
+    env.release(&launch, &carol).unwrap();
+    assert_eq!(env.pool(&launch).curve_tokens_unassigned, bought);
+    let released = env.svm.events::<CurveInventoryReleased>().remove(0);
+    assert_eq!((released.buyer, released.released, released.curve_tokens_unassigned), (carol, bought, bought));
+    let record = env.record(&launch, &carol).unwrap();
+    assert_eq!((record.curve_inventory(), record.holdings()), (0, 0));
+    env.transfer(&launch, &desk, &erin, bought).unwrap();
+    assert!(env.record(&launch, &erin).is_none());
+
+    // No record, no floor: even a price below every entry goes through.
+    env.update_price(&launch, 1).unwrap();
+    env.sell_curve(&launch, &erin, bought).unwrap();
+    let sell = env.svm.events::<CurveTrade>().remove(0);
+    assert_eq!((sell.trader, sell.token_amount, sell.entry_price), (erin, bought, 0));
+    assert_eq!(env.pool(&launch).curve_tokens_unassigned, 0);
+    assert!(env.record(&launch, &erin).is_none());
+
+    // Bob still holds his claim on the curve.
+    assert_eq!(env.pool(&launch).tokens_sold_curve, env.record(&launch, &bob).unwrap().curve_inventory());
+}
+
+#[test]
+fn presale_tokens_carry_no_inventory() {
+    let env = Env::new();
+    let (alice, carol, desk) = (env.user(), env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    env.register_market(&launch, &desk, MarketKind::BondingCurveVault);
+    env.buy_curve(&launch, &carol, LAMPORTS_PER_SOL).unwrap();
+
+    env.transfer(&launch, &alice, &desk, 1_000).unwrap();
+    env.release(&launch, &alice).unwrap();
+    assert_eq!(env.pool(&launch).curve_tokens_unassigned, 0);
+    assert_error(env.sell_curve(&launch, &desk, 1_000), SamesError::ExceedsCurveInventory);
+}
+
+#[test]
+fn presale_holders_cant_sell_their_allocation_to_the_curve() {
+    let env = Env::new();
+    let (alice, carol, desk) = (env.user(), env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    env.register_market(&launch, &desk, MarketKind::BondingCurveVault);
+    env.buy_curve(&launch, &carol, LAMPORTS_PER_SOL).unwrap();
+    env.transfer(&launch, &carol, &desk, 5_000).unwrap();
+    env.release(&launch, &carol).unwrap();
+    assert_eq!(env.pool(&launch).curve_tokens_unassigned, 5_000);
+
+    // Alice only holds her presale allocation: the unassigned inventory
+    // belongs to whoever holds Carol's tokens, not to her.
+    let record = env.record(&launch, &alice).unwrap();
+    assert_eq!((record.curve_inventory(), record.unassigned_allowance()), (0, 0));
+    assert_error(env.sell_curve(&launch, &alice, 1_000), SamesError::ExceedsCurveInventory);
+    assert_eq!(env.pool(&launch).curve_tokens_unassigned, 5_000);
+}
+
+#[test]
+fn records_draw_on_unassigned_inventory_after_their_own() {
+    let env = Env::new();
+    let (alice, carol, dave, erin) = (env.user(), env.user(), env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    env.buy_curve(&launch, &carol, LAMPORTS_PER_SOL).unwrap();
+    env.buy_curve(&launch, &dave, LAMPORTS_PER_SOL).unwrap();
+    let own = env.record(&launch, &dave).unwrap().curve_inventory();
+    // Erin lifts the curve, so Dave sells above his entry.
+    env.buy_curve(&launch, &erin, LAMPORTS_PER_SOL).unwrap();
+
+    // Carol's presale-free wallet sends only curve tokens.
+    env.transfer(&launch, &carol, &dave, 5_000).unwrap();
+    env.release(&launch, &carol).unwrap();
+    assert_eq!(env.pool(&launch).curve_tokens_unassigned, 5_000);
+    let received = env.record(&launch, &dave).unwrap();
+    assert_eq!((received.curve_inventory(), received.tokens_received), (own, 5_000));
+
+    env.sell_curve(&launch, &dave, own + 2_000).unwrap();
+    let record = env.record(&launch, &dave).unwrap();
+    assert_eq!((record.curve_inventory(), record.tokens_sent), (0, 2_000));
+    assert_eq!(env.pool(&launch).curve_tokens_unassigned, 3_000);
+    assert_eq!(env.balance(&launch, &dave), 3_000);
+    assert_eq!(record.unassigned_allowance(), 3_000);
+}
+
+#[test]
+fn presale_tokens_received_by_transfer_carry_no_allowance() {
+    let env = Env::new();
+    let (alice, carol, bob, desk, erin) = (env.user(), env.user(), env.user(), env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    env.register_market(&launch, &desk, MarketKind::BondingCurveVault);
+    env.buy_curve(&launch, &carol, LAMPORTS_PER_SOL).unwrap();
+    env.buy_curve(&launch, &erin, LAMPORTS_PER_SOL).unwrap();
+    env.transfer(&launch, &carol, &desk, 5_000).unwrap();
+    env.release(&launch, &carol).unwrap();
+
+    // Alice's allocation doesn't turn into curve tokens by changing wallets.
+    env.create_record(&launch, &bob).unwrap();
+    env.transfer(&launch, &alice, &bob, 1_000).unwrap();
+    assert_eq!(env.record(&launch, &bob).unwrap().unassigned_allowance(), 0);
+    assert_error(env.sell_curve(&launch, &bob, 1_000), SamesError::ExceedsCurveInventory);
+    assert_eq!(env.pool(&launch).curve_tokens_unassigned, 5_000);
+
+    // Carol's curve tokens do, and only those.
+    env.transfer(&launch, &carol, &bob, 2_000).unwrap();
+    env.release(&launch, &carol).unwrap();
+    assert_eq!(env.record(&launch, &bob).unwrap().unassigned_allowance(), 2_000);
+    assert_error(env.sell_curve(&launch, &bob, 3_000), SamesError::ExceedsCurveInventory);
+    env.sell_curve(&launch, &bob, 2_000).unwrap();
+    let record = env.record(&launch, &bob).unwrap();
+    assert_eq!((record.unassigned_allowance(), record.holdings()), (0, 1_000));
+    assert_eq!(env.pool(&launch).curve_tokens_unassigned, 5_000);
+}
+
+#[test]
+fn sold_out_records_close_once_their_inventory_is_released() {
+    let env = Env::new();
+    let (alice, carol, dave, desk, erin) = (env.user(), env.user(), env.user(), env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    env.register_market(&launch, &desk, MarketKind::BondingCurveVault);
+    env.buy_curve(&launch, &dave, LAMPORTS_PER_SOL).unwrap();
+    env.buy_curve(&launch, &carol, LAMPORTS_PER_SOL).unwrap();
+    env.buy_curve(&launch, &erin, 2 * LAMPORTS_PER_SOL).unwrap();
+    let close = |owner: &Pubkey| {
+        env.send(&[instructions::close_buyer_record(owner, &launch.mint, owner)], &[owner])
+    };
+
+    // Carol's position left for a market: her record holds nothing, but
+    // still owes the launch her curve inventory.
+    let bought = env.balance(&launch, &carol);
+    env.transfer(&launch, &carol, &desk, bought).unwrap();
+    let record = env.record(&launch, &carol).unwrap();
+    assert_eq!((record.holdings(), record.unreleased_inventory()), (0, bought));
+    assert_error(close(&carol), SamesError::BuyerRecordInUse);
+    env.release(&launch, &carol).unwrap();
+    close(&carol).unwrap();
+    assert!(env.record(&launch, &carol).is_none());
+    assert_eq!(env.pool(&launch).curve_tokens_unassigned, bought);
+
+    // Dave sells his straight back to the curve.
+    env.sell_curve(&launch, &dave, env.balance(&launch, &dave)).unwrap();
+    close(&dave).unwrap();
+    assert!(env.record(&launch, &dave).is_none());
+}
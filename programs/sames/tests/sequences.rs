@@ -0,0 +1,42 @@
+//! Seeded runs of the fuzz target's instruction sequences, so `cargo test`
+//! exercises the same invariants as `cargo fuzz run sequences`.
+
+mod svm;
+
+use arbitrary::{Arbitrary, Unstructured};
+use svm::sequence::{run, Action, Sequence};
+
+const SEEDS: u64 = 64;
+const INPUT_BYTES: usize = 1_024;
+
+/// Deterministic input bytes for `seed` (splitmix64).
+fn input(seed: u64) -> Vec<u8> {
+    let mut state = seed;
+    let mut bytes = Vec::with_capacity(INPUT_BYTES);
+    while bytes.len() < INPUT_BYTES {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        bytes.extend_from_slice(&(z ^ (z >> 31)).to_le_bytes());
+    }
+    bytes
+}
+
+#[test]
+fn random_sequences_keep_the_books_balanced() {
+    for seed in 0..SEEDS {
+        let bytes = input(seed);
+        let sequence = Sequence::arbitrary_take_rest(Unstructured::new(&bytes)).expect("enough input");
+        run(&sequence);
+    }
+}
+
+/// Presale buyers dumping into a thin curve, then everyone trying to exit.
+#[test]
+fn presale_holders_sell_into_the_curve() {
+    let mut actions = vec![Action::Buy { user: 2, millis: 500 }, Action::Buy { user: 3, millis: 500 }];
+    actions.extend((0..4).map(|user| Action::Sell { user, share: 255 }));
+    actions.extend((0..4).map(|user| Action::Sell { user, share: 255 }));
+    run(&Sequence { deposits: vec![1_000, 2_000], vesting: false, actions });
+}
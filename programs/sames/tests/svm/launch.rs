@@ -0,0 +1,280 @@
+//! Fixtures: a deployed protocol and launches driven through the SDK's
+//! instruction builders.
+
+use anchor_lang::prelude::{Pubkey, Rent};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::AccountSerialize;
+use anchor_spl::token_2022::spl_token_2022::state::{Account as TokenAccount, AccountState};
+use sames::errors::SamesError;
+use sames::instruction::CreateLaunch;
+use sames::state::{
+    BuyerRecord, LaunchPool, MarketEntry, MarketKind, MarketRegistry, ProtocolBounds, ProtocolConfig,
+    ProtocolParams, VestingSchedule, ENTRY_PRICE_SCALE,
+};
+use sames_sdk::{instructions, pda};
+
+use super::{Account, Svm, TxError};
+
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+pub const DECIMALS: u8 = 6;
+pub const SUPPLY: u64 = 1_000_000_000_000;
+/// Presale price and curve base price, lamports per base unit.
+pub const PRICE: u64 = 1_000;
+/// `PRICE` as a presale buyer's fixed-point entry price.
+pub const PRESALE_ENTRY: u128 = PRICE as u128 * ENTRY_PRICE_SCALE;
+pub const PRESALE_SECONDS: i64 = 60;
+pub const GRADUATION_THRESHOLD: u64 = 2 * LAMPORTS_PER_SOL;
+
+/// Test parameters: the steepest allowed slope, so small buys move the
+/// price, and a low graduation threshold.
+pub fn params() -> ProtocolParams {
+    ProtocolParams {
+        platform_fee_bps: 100,
+        presale_duration: PRESALE_SECONDS,
+        default_slope: 1_000_000,
+        default_graduation_threshold: GRADUATION_THRESHOLD,
+        max_creator_allocation_bps: 2_000,
+    }
+}
+
+pub fn launch_args() -> CreateLaunch {
+    CreateLaunch {
+        token_name: "Same".to_string(),
+        token_symbol: "SAME".to_string(),
+        total_supply: SUPPLY,
+        price_lamports: PRICE,
+        floor_after_graduation: false,
+        presale_vesting: VestingSchedule::default(),
+        creator_allocation_bps: 0,
+        creator_vesting: VestingSchedule::default(),
+    }
+}
+
+/// Assert that `result` failed with `error` from the program.
+#[track_caller]
+pub fn assert_error(result: Result<(), TxError>, error: SamesError) {
+    let code = anchor_lang::error::ERROR_CODE_OFFSET + error as u32;
+    assert_eq!(result, Err(TxError::Program(anchor_lang::prelude::ProgramError::Custom(code))));
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Launch {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub pool: Pubkey,
+}
+
+/// An SVM with the protocol config initialized by its admin.
+pub struct Env {
+    pub svm: Svm,
+    pub admin: Pubkey,
+}
+
+impl Env {
+    pub fn new() -> Self {
+        let svm = Svm::new();
+        let admin = svm.upgrade_authority;
+        svm.airdrop(&admin, 100 * LAMPORTS_PER_SOL);
+        let env = Self { svm, admin };
+        if env.svm.native {
+            env.store_protocol_config();
+        } else {
+            env.send(&[instructions::initialize_protocol_config(&admin, params())], &[&admin])
+                .expect("protocol config initializes");
+        }
+        env
+    }
+
+    /// Store the config `initialize_protocol_config` would create. A native
+    /// program isn't owned by the upgradeable loader, so it can't pass the
+    /// upgrade-authority check.
+    fn store_protocol_config(&self) {
+        let (address, bump) = pda::protocol_config();
+        let config = ProtocolConfig {
+            admin: self.admin,
+            pending_admin: Pubkey::default(),
+            bump,
+            params: params(),
+            bounds: ProtocolBounds::LIMITS,
+            guardian: self.admin,
+            paused: 0,
+            dex_programs: Vec::new(),
+        };
+        let mut data = Vec::new();
+        config.try_serialize(&mut data).expect("config serializes");
+        data.resize(ProtocolConfig::MAX_SIZE, 0);
+        self.svm.set_account(address, Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: sames::ID,
+            executable: false,
+            rent_epoch: 0,
+        });
+    }
+
+    pub fn send(&self, instructions: &[Instruction], signers: &[&Pubkey]) -> Result<(), TxError> {
+        self.svm.process(instructions, signers)
+    }
+
+    /// A funded wallet.
+    pub fn user(&self) -> Pubkey {
+        let user = self.svm.keypair();
+        self.svm.airdrop(&user, 100 * LAMPORTS_PER_SOL);
+        user
+    }
+
+    /// Create a mint, its launch and the hook's account list.
+    pub fn launch(&self, args: CreateLaunch) -> Launch {
+        self.try_launch(args).expect("launch is created")
+    }
+
+    pub fn try_launch(&self, args: CreateLaunch) -> Result<Launch, TxError> {
+        let creator = self.user();
+        let mint = self.svm.keypair();
+        let rent = Rent::default().minimum_balance(instructions::mint_space());
+        let mut ixs = instructions::create_mint(&creator, &mint, DECIMALS, rent);
+        ixs.push(instructions::create_launch(&creator, &mint, args));
+        ixs.push(instructions::initialize_extra_account_meta_list(&creator, &mint));
+        self.send(&ixs, &[&creator, &mint])?;
+        Ok(Launch { mint, creator, pool: pda::launch_pool(&mint).0 })
+    }
+
+    pub fn buy_presale(&self, launch: &Launch, buyer: &Pubkey, lamports: u64) -> Result<(), TxError> {
+        self.send(&[instructions::buy_presale(buyer, &launch.mint, lamports)], &[buyer])
+    }
+
+    /// Allocate `buyer`'s presale tokens, creating their token account.
+    pub fn finalize(&self, launch: &Launch, buyer: &Pubkey) -> Result<(), TxError> {
+        let ixs = [
+            instructions::create_token_account(&launch.creator, buyer, &launch.mint),
+            instructions::finalize_launch(&launch.creator, &launch.mint, buyer),
+        ];
+        self.send(&ixs, &[&launch.creator])
+    }
+
+    pub fn start_curve(&self, launch: &Launch) -> Result<(), TxError> {
+        self.send(&[instructions::start_bonding_curve(&launch.creator, &launch.mint)], &[&launch.creator])
+    }
+
+    /// Run a presale with `deposits`, end it, allocate every buyer and open
+    /// the curve.
+    pub fn live_launch(&self, args: CreateLaunch, deposits: &[(Pubkey, u64)]) -> Launch {
+        let launch = self.launch(args);
+        for (buyer, lamports) in deposits {
+            self.buy_presale(&launch, buyer, *lamports).expect("presale buy");
+        }
+        self.svm.warp(PRESALE_SECONDS);
+        for (buyer, _) in deposits {
+            self.finalize(&launch, buyer).expect("allocation");
+        }
+        self.start_curve(&launch).expect("curve starts");
+        launch
+    }
+
+    pub fn buy_curve(&self, launch: &Launch, buyer: &Pubkey, lamports: u64) -> Result<(), TxError> {
+        let ixs = [
+            instructions::create_token_account(buyer, buyer, &launch.mint),
+            instructions::buy_curve(buyer, &launch.mint, lamports),
+        ];
+        self.send(&ixs, &[buyer])
+    }
+
+    pub fn sell_curve(&self, launch: &Launch, seller: &Pubkey, amount: u64) -> Result<(), TxError> {
+        self.send(&[instructions::sell_curve(seller, &launch.mint, amount)], &[seller])
+    }
+
+    pub fn graduate(&self, launch: &Launch) -> Result<(), TxError> {
+        let caller = self.user();
+        self.send(&[instructions::graduate(&caller, &launch.mint)], &[&caller])
+    }
+
+    pub fn update_price(&self, launch: &Launch, price: u64) -> Result<(), TxError> {
+        self.send(&[instructions::update_price(&launch.creator, &launch.mint, price)], &[&launch.creator])
+    }
+
+    /// Transfer between two owners' token accounts, creating the destination.
+    pub fn transfer(&self, launch: &Launch, from: &Pubkey, to: &Pubkey, amount: u64) -> Result<(), TxError> {
+        let registry = self.registry(launch);
+        let ixs = [
+            instructions::create_token_account(from, to, &launch.mint),
+            instructions::transfer(from, to, &launch.mint, amount, DECIMALS, &registry),
+        ];
+        self.send(&ixs, &[from])
+    }
+
+    /// Open `owner`'s BuyerRecord, paid by `owner`, so it can receive floored tokens.
+    pub fn create_record(&self, launch: &Launch, owner: &Pubkey) -> Result<(), TxError> {
+        self.send(&[instructions::create_buyer_record(owner, owner, &launch.mint)], &[owner])
+    }
+
+    /// A token account of `mint` holding `amount`, written straight into
+    /// the bank: wrapped SOL needs the SPL Token program, which the tests
+    /// don't load.
+    pub fn forge_token_account(&self, mint: Pubkey, amount: u64) -> Pubkey {
+        let address = Pubkey::new_unique();
+        let mut data = vec![0; TokenAccount::LEN];
+        let state = TokenAccount { mint, owner: Pubkey::new_unique(), amount, state: AccountState::Initialized, ..Default::default() };
+        state.pack_into_slice(&mut data);
+        self.svm.set_account(address, Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: anchor_spl::token::ID,
+            executable: false,
+            rent_epoch: 0,
+        });
+        address
+    }
+
+    /// Register `owner`'s token account as a Cpmm market, pairing it with a
+    /// forged wrapped-SOL reserve holding `sol`.
+    pub fn register_cpmm(&self, launch: &Launch, owner: &Pubkey, sol: u64) -> MarketEntry {
+        let address = pda::token_account(owner, &launch.mint);
+        let sol_reserve = self.forge_token_account(anchor_spl::token::spl_token::native_mint::ID, sol);
+        let market = MarketEntry { address, kind: MarketKind::Cpmm, token_reserve: address, sol_reserve };
+        let ixs = [
+            instructions::create_token_account(&launch.creator, owner, &launch.mint),
+            instructions::add_market(&launch.creator, &launch.mint, market),
+        ];
+        self.send(&ixs, &[&launch.creator]).expect("market registers");
+        market
+    }
+
+    /// Release the curve inventory `owner`'s transfers carried off.
+    pub fn release(&self, launch: &Launch, owner: &Pubkey) -> Result<(), TxError> {
+        self.send(&[instructions::release_curve_inventory(owner, &launch.mint)], &[])
+    }
+
+    /// Register `owner`'s token account as a market of `kind`.
+    pub fn register_market(&self, launch: &Launch, owner: &Pubkey, kind: MarketKind) -> Pubkey {
+        let address = pda::token_account(owner, &launch.mint);
+        let market = MarketEntry { address, kind, token_reserve: Pubkey::default(), sol_reserve: Pubkey::default() };
+        let ixs = [
+            instructions::create_token_account(&launch.creator, owner, &launch.mint),
+            instructions::add_market(&launch.creator, &launch.mint, market),
+        ];
+        self.send(&ixs, &[&launch.creator]).expect("market registers");
+        address
+    }
+
+    pub fn pool(&self, launch: &Launch) -> LaunchPool {
+        self.svm.anchor(&launch.pool)
+    }
+
+    pub fn registry(&self, launch: &Launch) -> MarketRegistry {
+        self.svm.anchor(&pda::market_registry(&launch.pool).0)
+    }
+
+    pub fn record(&self, launch: &Launch, owner: &Pubkey) -> Option<BuyerRecord> {
+        let address = pda::buyer_record(&launch.pool, owner).0;
+        self.svm.account(&address).map(|_| self.svm.anchor(&address))
+    }
+
+    pub fn balance(&self, launch: &Launch, owner: &Pubkey) -> u64 {
+        self.svm.token_balance(&pda::token_account(owner, &launch.mint))
+    }
+
+    pub fn vault_lamports(&self, launch: &Launch) -> u64 {
+        self.svm.lamports(&pda::vault(&launch.pool).0)
+    }
+}
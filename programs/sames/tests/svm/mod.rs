@@ -0,0 +1,526 @@
+//! The integration tests' cluster: a `solana-program-test` bank running the
+//! SAMES program, Token-2022 and the associated-token program.
+//!
+//! Token-2022 and the associated-token program run natively from the crates
+//! the program is built against (the Token-2022 build bundled with
+//! program-test predates the account resolution the transfer hook uses).
+//! The SAMES program runs natively too, unless `SBF_OUT_DIR` names a
+//! directory holding `sames.so`, as `cargo test-sbf` sets it: then the built
+//! program is deployed through the upgradeable loader.
+//!
+//! Either way the bank enforces the runtime's rules: signer and writable
+//! privileges, PDA signing, reentrancy, account ownership, rent exemption
+//! and rollback of failed transactions.
+
+#![allow(dead_code)]
+
+pub mod launch;
+pub mod sequence;
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Once;
+
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::prelude::{AccountInfo, Clock, ProgramError, Pubkey};
+use anchor_lang::solana_program::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorDeserialize, Discriminator, Event};
+use anchor_spl::associated_token::spl_associated_token_account;
+use anchor_spl::token_2022::spl_token_2022;
+use base64::Engine;
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::commitment_config::CommitmentLevel;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::Message;
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::{Transaction, TransactionError};
+use tokio::runtime::Runtime;
+
+pub use solana_sdk::account::Account;
+
+/// Start of the simulated clock.
+pub const GENESIS_TIME: i64 = 1_700_000_000;
+
+/// Why a transaction failed: an error returned by a program, or one the
+/// runtime raised.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TxError {
+    Program(ProgramError),
+    Runtime(TransactionError),
+}
+
+/// What a simulated transaction would have done.
+#[derive(Clone, Debug)]
+pub struct Simulation {
+    pub result: Result<(), TransactionError>,
+    pub logs: Vec<String>,
+    pub units_consumed: u64,
+}
+
+thread_local! {
+    /// Data of the native program's `emit_cpi!` self-invocations.
+    static CPI_EVENTS: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+    /// Data the native program's `emit!` logged.
+    static LOG_EVENTS: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// program-test's syscalls for native programs, except that `sol_log_data`
+/// is recorded rather than printed to stdout.
+struct Stubs(Box<dyn SyscallStubs>);
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, message: &str) {
+        self.0.sol_log(message)
+    }
+
+    fn sol_log_compute_units(&self) {
+        self.0.sol_log_compute_units()
+    }
+
+    fn sol_remaining_compute_units(&self) -> u64 {
+        self.0.sol_remaining_compute_units()
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        self.0.sol_invoke_signed(instruction, account_infos, signers_seeds)
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_clock_sysvar(var_addr)
+    }
+
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_schedule_sysvar(var_addr)
+    }
+
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_fees_sysvar(var_addr)
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_rent_sysvar(var_addr)
+    }
+
+    fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_rewards_sysvar(var_addr)
+    }
+
+    fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_last_restart_slot(var_addr)
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.0.sol_get_return_data()
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        self.0.sol_set_return_data(data)
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        LOG_EVENTS.with(|events| events.borrow_mut().push(fields.concat()));
+    }
+
+    fn sol_get_processed_sibling_instruction(&self, index: usize) -> Option<Instruction> {
+        self.0.sol_get_processed_sibling_instruction(index)
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        self.0.sol_get_stack_height()
+    }
+}
+
+/// Placeholder while program-test's stubs are being wrapped.
+struct NoStubs;
+
+impl SyscallStubs for NoStubs {}
+
+/// Native entrypoint of the SAMES program, recording `emit_cpi!` events on
+/// their way in. program-test doesn't record inner instructions, so this is
+/// the only place they can be seen.
+fn sames_entry<'a, 'b>(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>], data: &[u8]) -> ProgramResult {
+    if let Some(event) = data.strip_prefix(&EVENT_IX_TAG_LE) {
+        CPI_EVENTS.with(|events| events.borrow_mut().push(event.to_vec()));
+    }
+    // SAFETY: `entry` wants the slice and the accounts it holds to share a
+    // lifetime; both outlive this call.
+    let accounts = unsafe { std::mem::transmute::<&'a [AccountInfo<'b>], &'a [AccountInfo<'a>]>(accounts) };
+    sames::entry(program_id, accounts, data)
+}
+
+/// Handle to one test's cluster.
+pub struct Svm {
+    /// Upgrade authority of the deployed program. Natively there's no
+    /// deployment and nobody holds the authority.
+    pub upgrade_authority: Pubkey,
+    /// Whether the SAMES program runs natively rather than from `sames.so`.
+    pub native: bool,
+    runtime: Runtime,
+    context: RefCell<ProgramTestContext>,
+    keypairs: RefCell<HashMap<Pubkey, Keypair>>,
+    /// Messages already sent with the current blockhash.
+    sent: RefCell<HashSet<Hash>>,
+    logs: RefCell<Vec<String>>,
+    cpi_events: RefCell<Vec<Vec<u8>>>,
+    log_events: RefCell<Vec<Vec<u8>>>,
+}
+
+impl Svm {
+    /// A fresh bank holding the programs, its clock at `GENESIS_TIME`.
+    pub fn new() -> Self {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().expect("runtime");
+        let mut program_test = ProgramTest::default();
+        program_test.add_program(
+            "spl_token_2022",
+            spl_token_2022::ID,
+            processor!(spl_token_2022::processor::Processor::process),
+        );
+        program_test.add_program(
+            "spl_associated_token_account",
+            spl_associated_token_account::ID,
+            processor!(spl_associated_token_account::processor::process_instruction),
+        );
+
+        let upgrade_authority = Keypair::new();
+        let native = match built_program() {
+            Some(elf) => {
+                deploy(&mut program_test, elf, &upgrade_authority.pubkey());
+                false
+            }
+            None => {
+                program_test.add_program("sames", sames::ID, processor!(sames_entry));
+                true
+            }
+        };
+
+        solana_logger::setup_with_default("error");
+        let context = runtime.block_on(program_test.start_with_context());
+        // program-test installs its stubs while starting the first bank.
+        static STUBS: Once = Once::new();
+        STUBS.call_once(|| {
+            let program_test_stubs = set_syscall_stubs(Box::new(NoStubs));
+            set_syscall_stubs(Box::new(Stubs(program_test_stubs)));
+        });
+        let svm = Self {
+            upgrade_authority: upgrade_authority.pubkey(),
+            native,
+            runtime,
+            context: RefCell::new(context),
+            keypairs: RefCell::default(),
+            sent: RefCell::default(),
+            logs: RefCell::default(),
+            cpi_events: RefCell::default(),
+            log_events: RefCell::default(),
+        };
+        svm.keypairs.borrow_mut().insert(upgrade_authority.pubkey(), upgrade_authority);
+        let clock = svm.clock();
+        svm.context.borrow().set_sysvar(&Clock { unix_timestamp: GENESIS_TIME, ..clock });
+        svm
+    }
+
+    /// A new keypair the SVM signs with when its address is a signer.
+    pub fn keypair(&self) -> Pubkey {
+        let keypair = Keypair::new();
+        let address = keypair.pubkey();
+        self.keypairs.borrow_mut().insert(address, keypair);
+        address
+    }
+
+    pub fn account(&self, key: &Pubkey) -> Option<Account> {
+        let mut context = self.context.borrow_mut();
+        self.runtime
+            .block_on(context.banks_client.get_account_with_commitment(*key, CommitmentLevel::Processed))
+            .expect("banks client")
+    }
+
+    pub fn set_account(&self, key: Pubkey, account: Account) {
+        self.context.borrow_mut().set_account(&key, &account.into());
+    }
+
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.account(key).map_or(0, |account| account.lamports)
+    }
+
+    pub fn airdrop(&self, key: &Pubkey, lamports: u64) {
+        let mut account = self.account(key).unwrap_or_default();
+        account.lamports += lamports;
+        self.set_account(*key, account);
+    }
+
+    /// Decode an Anchor account; panics if it's missing or malformed.
+    pub fn anchor<T: AccountDeserialize>(&self, key: &Pubkey) -> T {
+        let account = self.account(key).unwrap_or_else(|| panic!("account {key} doesn't exist"));
+        T::try_deserialize(&mut account.data.as_slice()).expect("account decodes")
+    }
+
+    /// Overwrite an existing Anchor account's data with `value`, keeping its
+    /// size, lamports and owner. Used to reach states no instruction produces.
+    pub fn set_anchor<T: AccountSerialize>(&self, key: &Pubkey, value: &T) {
+        let mut account = self.account(key).unwrap_or_else(|| panic!("account {key} doesn't exist"));
+        let mut data = Vec::new();
+        value.try_serialize(&mut data).expect("account serializes");
+        account.data[..data.len()].copy_from_slice(&data);
+        self.set_account(*key, account);
+    }
+
+    /// Balance of a Token-2022 account, 0 if it doesn't exist.
+    pub fn token_balance(&self, key: &Pubkey) -> u64 {
+        use spl_token_2022::extension::StateWithExtensions;
+        self.account(key).map_or(0, |account| {
+            StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+                .expect("token account")
+                .base
+                .amount
+        })
+    }
+
+    pub fn clock(&self) -> Clock {
+        let account = self.account(&anchor_lang::solana_program::sysvar::clock::ID).expect("clock sysvar");
+        bincode_clock(&account.data)
+    }
+
+    pub fn now(&self) -> i64 {
+        self.clock().unix_timestamp
+    }
+
+    /// Move to the next slot, `seconds` later.
+    pub fn warp(&self, seconds: i64) {
+        let clock = self.clock();
+        let slot = clock.slot + 1;
+        let mut context = self.context.borrow_mut();
+        context.warp_to_slot(slot).expect("warp forward");
+        context.set_sysvar(&Clock { slot, unix_timestamp: clock.unix_timestamp + seconds, ..clock });
+        self.sent.borrow_mut().clear();
+    }
+
+    /// Run `instructions` as one transaction signed by `signers`; fees are
+    /// paid by the bank's own payer.
+    pub fn process(&self, instructions: &[Instruction], signers: &[&Pubkey]) -> Result<(), TxError> {
+        self.clear_output();
+
+        let mut ixs = vec![ComputeBudgetInstruction::set_compute_unit_limit(1_400_000)];
+        ixs.extend_from_slice(instructions);
+        let payer = self.context.borrow().payer.pubkey();
+        let mut message = Message::new(&ixs, Some(&payer));
+        let required = &message.account_keys[1..message.header.num_required_signatures as usize];
+        if required.iter().any(|key| !signers.contains(&key)) {
+            return Err(TxError::Runtime(TransactionError::SignatureFailure));
+        }
+
+        // An identical transaction would be rejected as already processed;
+        // move to a new slot for a fresh blockhash instead.
+        message.recent_blockhash = self.context.borrow().last_blockhash;
+        if !self.sent.borrow_mut().insert(message.hash()) {
+            self.warp(0);
+            message.recent_blockhash = self.context.borrow().last_blockhash;
+            self.sent.borrow_mut().insert(message.hash());
+        }
+
+        let mut tx = Transaction::new_unsigned(message);
+        {
+            let keypairs = self.keypairs.borrow();
+            let context = self.context.borrow();
+            let mut tx_signers: Vec<&Keypair> = vec![&context.payer];
+            for key in &tx.message.account_keys[1..tx.message.header.num_required_signatures as usize] {
+                tx_signers.push(keypairs.get(key).unwrap_or_else(|| panic!("no keypair for signer {key}")));
+            }
+            tx.sign(&tx_signers, tx.message.recent_blockhash);
+        }
+        let size = 1 + tx.signatures.len() * 64 + tx.message.serialize().len();
+        assert!(size <= PACKET_DATA_SIZE, "transaction is {size} bytes, over the {PACKET_DATA_SIZE}-byte limit");
+        self.execute(tx)
+    }
+
+    /// Blockhash for transactions signed outside the SVM.
+    pub fn latest_blockhash(&self) -> Hash {
+        self.context.borrow().last_blockhash
+    }
+
+    /// Run a transaction its sender built and signed, fee payer included.
+    pub fn process_transaction(&self, tx: Transaction) -> Result<(), TxError> {
+        self.clear_output();
+        self.execute(tx)
+    }
+
+    /// Simulate a transaction its sender built and signed, leaving the bank
+    /// as it was.
+    pub fn simulate_transaction(&self, tx: Transaction) -> Simulation {
+        let mut context = self.context.borrow_mut();
+        let outcome = self.runtime.block_on(context.banks_client.simulate_transaction(tx)).expect("banks client");
+        let (logs, units_consumed) =
+            outcome.simulation_details.map_or((Vec::new(), 0), |details| (details.logs, details.units_consumed));
+        Simulation { result: outcome.result.expect("transaction is simulated"), logs, units_consumed }
+    }
+
+    fn clear_output(&self) {
+        self.logs.borrow_mut().clear();
+        self.cpi_events.borrow_mut().clear();
+        CPI_EVENTS.with(|events| events.borrow_mut().clear());
+        LOG_EVENTS.with(|events| events.borrow_mut().clear());
+    }
+
+    fn execute(&self, tx: Transaction) -> Result<(), TxError> {
+        let mut context = self.context.borrow_mut();
+        let outcome = self
+            .runtime
+            .block_on(context.banks_client.process_transaction_with_metadata(tx))
+            .expect("banks client");
+        if let Some(metadata) = outcome.metadata {
+            *self.logs.borrow_mut() = metadata.log_messages;
+        }
+        *self.log_events.borrow_mut() = LOG_EVENTS.with(|events| std::mem::take(&mut *events.borrow_mut()));
+        let events = CPI_EVENTS.with(|events| std::mem::take(&mut *events.borrow_mut()));
+        match outcome.result {
+            Ok(()) => {
+                *self.cpi_events.borrow_mut() = events;
+                Ok(())
+            }
+            Err(TransactionError::InstructionError(index, error)) => Err(ProgramError::try_from(error.clone())
+                .map_or(TxError::Runtime(TransactionError::InstructionError(index, error)), TxError::Program)),
+            Err(error) => Err(TxError::Runtime(error)),
+        }
+    }
+
+    /// Log lines of the last transaction.
+    pub fn logs(&self) -> Vec<String> {
+        self.logs.borrow().clone()
+    }
+
+    /// `emit_cpi!` events of type `T` from the last (successful) transaction.
+    /// Only the native program's are seen.
+    pub fn events<T: Event + AnchorDeserialize + Discriminator>(&self) -> Vec<T> {
+        assert!(self.native, "emit_cpi! events are only recorded when the program runs natively");
+        decode_events(&self.cpi_events.borrow())
+    }
+
+    /// `emit!` events of type `T` from the last transaction, failed or not.
+    pub fn log_events<T: Event + AnchorDeserialize + Discriminator>(&self) -> Vec<T> {
+        if self.native {
+            decode_events(&self.log_events.borrow())
+        } else {
+            decode_events(&program_data(&self.logs.borrow(), &sames::ID))
+        }
+    }
+}
+
+impl Default for Svm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `sames.so` from `SBF_OUT_DIR`, if it's set and holds one.
+fn built_program() -> Option<Vec<u8>> {
+    let path = PathBuf::from(std::env::var_os("SBF_OUT_DIR")?).join("sames.so");
+    std::fs::read(path).ok()
+}
+
+/// Deploy `elf` as the SAMES program through the upgradeable loader.
+fn deploy(program_test: &mut ProgramTest, elf: Vec<u8>, upgrade_authority: &Pubkey) {
+    let rent = Rent::default();
+    let program_data = Pubkey::find_program_address(&[sames::ID.as_ref()], &bpf_loader_upgradeable::ID).0;
+    let program = bincode_state(&UpgradeableLoaderState::Program { programdata_address: program_data });
+    let mut data = bincode_state(&UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address: Some(*upgrade_authority),
+    });
+    data.resize(UpgradeableLoaderState::size_of_programdata_metadata(), 0);
+    data.extend_from_slice(&elf);
+    program_test.add_account(program_data, Account {
+        lamports: rent.minimum_balance(data.len()),
+        data,
+        owner: bpf_loader_upgradeable::ID,
+        executable: false,
+        rent_epoch: 0,
+    });
+    program_test.add_account(sames::ID, Account {
+        lamports: rent.minimum_balance(program.len()),
+        data: program,
+        owner: bpf_loader_upgradeable::ID,
+        executable: true,
+        rent_epoch: 0,
+    });
+}
+
+fn bincode_state(state: &UpgradeableLoaderState) -> Vec<u8> {
+    // bincode: u32 variant index, then the fields.
+    let mut data = Vec::new();
+    match state {
+        UpgradeableLoaderState::Program { programdata_address } => {
+            data.extend_from_slice(&2u32.to_le_bytes());
+            data.extend_from_slice(programdata_address.as_ref());
+        }
+        UpgradeableLoaderState::ProgramData { slot, upgrade_authority_address } => {
+            data.extend_from_slice(&3u32.to_le_bytes());
+            data.extend_from_slice(&slot.to_le_bytes());
+            match upgrade_authority_address {
+                Some(key) => {
+                    data.push(1);
+                    data.extend_from_slice(key.as_ref());
+                }
+                None => data.push(0),
+            }
+        }
+        _ => unreachable!("only programs are deployed"),
+    }
+    data
+}
+
+/// The clock sysvar's fields are five little-endian words.
+fn bincode_clock(data: &[u8]) -> Clock {
+    let word = |index: usize| <[u8; 8]>::try_from(&data[index * 8..index * 8 + 8]).expect("clock field");
+    Clock {
+        slot: u64::from_le_bytes(word(0)),
+        epoch_start_timestamp: i64::from_le_bytes(word(1)),
+        epoch: u64::from_le_bytes(word(2)),
+        leader_schedule_epoch: u64::from_le_bytes(word(3)),
+        unix_timestamp: i64::from_le_bytes(word(4)),
+    }
+}
+
+/// Payloads of the `Program data:` lines `program_id` logged itself, found
+/// by following the invoke and return lines around them.
+fn program_data(logs: &[String], program_id: &Pubkey) -> Vec<Vec<u8>> {
+    let program = program_id.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut payloads = Vec::new();
+    for line in logs {
+        let Some(rest) = line.strip_prefix("Program ") else { continue };
+        if let Some(data) = rest.strip_prefix("data: ") {
+            if stack.last() == Some(&program.as_str()) {
+                let fields = data.split(' ').map(|field| base64::engine::general_purpose::STANDARD.decode(field));
+                payloads.push(fields.collect::<Result<Vec<_>, _>>().expect("base64 log data").concat());
+            }
+            continue;
+        }
+        let mut words = rest.split(' ');
+        match (words.next(), words.next()) {
+            (Some(id), Some("invoke")) => stack.push(id),
+            (Some(_), Some("success" | "failed:")) => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+    payloads
+}
+
+fn decode_events<T: AnchorDeserialize + Discriminator>(events: &[Vec<u8>]) -> Vec<T> {
+    events
+        .iter()
+        .filter_map(|data| data.strip_prefix(&T::DISCRIMINATOR))
+        .map(|mut body| T::deserialize(&mut body).expect("event decodes"))
+        .collect()
+}
@@ -0,0 +1,144 @@
+//! Random instruction sequences, shared by the fuzz target and the seeded
+//! sequence tests: a launch with a few presale buyers, then curve buys and
+//! sells, vesting claims, transfers, time passing and graduation in any
+//! order. The launch's accounting is checked after every step, whether the
+//! step succeeded or not.
+
+use anchor_lang::prelude::Pubkey;
+use arbitrary::Arbitrary;
+use sames::instruction::CreateLaunch;
+use sames::state::{bonding_curve_sell_proceeds, VestingSchedule};
+use sames_sdk::{instructions, pda};
+
+use super::launch::*;
+
+/// Wallets the actions pick from by index.
+pub const USERS: usize = 4;
+
+#[derive(Arbitrary, Clone, Debug)]
+pub struct Sequence {
+    /// Presale deposits of the first wallets, in thousandths of a SOL.
+    pub deposits: Vec<u16>,
+    /// Vest presale allocations instead of releasing them at once.
+    pub vesting: bool,
+    pub actions: Vec<Action>,
+}
+
+#[derive(Arbitrary, Clone, Copy, Debug)]
+pub enum Action {
+    /// Spend thousandths of a SOL on the curve.
+    Buy { user: u8, millis: u16 },
+    /// Sell `share`/255 of the wallet's tokens to the curve.
+    Sell { user: u8, share: u8 },
+    /// Send `share`/255 of the wallet's tokens to another wallet.
+    Transfer { from: u8, to: u8, share: u8 },
+    ClaimVested { user: u8 },
+    Warp { seconds: u8 },
+    Graduate,
+}
+
+/// Run `sequence` against a fresh SVM, panicking on the first step that
+/// leaves the launch's accounting inconsistent.
+pub fn run(sequence: &Sequence) {
+    let env = Env::new();
+    let users: Vec<Pubkey> = (0..USERS).map(|_| env.user()).collect();
+    let deposits: Vec<(Pubkey, u64)> = users
+        .iter()
+        .zip(&sequence.deposits)
+        .map(|(user, millis)| (*user, (*millis as u64).max(1) * LAMPORTS_PER_SOL / 1_000))
+        .collect();
+    let presale_vesting = match sequence.vesting {
+        true => VestingSchedule { cliff_seconds: 0, duration_seconds: 600 },
+        false => VestingSchedule::default(),
+    };
+    let launch = env.live_launch(CreateLaunch { presale_vesting, ..launch_args() }, &deposits);
+
+    let mut holders = users.clone();
+    holders.extend([launch.creator, pda::vesting_escrow(&launch.pool).0, pda::creator_escrow(&launch.pool).0]);
+    let circulating = || -> u64 { holders.iter().map(|holder| env.svm.token_balance(&token_account(&launch, holder))).sum() };
+    // Everything minted outside the curve: presale allocations.
+    let issued_before_curve = circulating();
+
+    let user = |index: u8| users[index as usize % USERS];
+    let share = |owner: &Pubkey, share: u8| (env.balance(&launch, owner) as u128 * share as u128 / 255) as u64;
+    for (step, action) in sequence.actions.iter().enumerate() {
+        // Failures are expected: sells past the floor, claims with nothing
+        // vested, trades after graduation. Only the state they leave counts.
+        let _ = match *action {
+            Action::Buy { user: index, millis } => {
+                env.buy_curve(&launch, &user(index), millis as u64 * LAMPORTS_PER_SOL / 1_000)
+            }
+            Action::Sell { user: index, share: part } => {
+                let seller = user(index);
+                env.sell_curve(&launch, &seller, share(&seller, part))
+            }
+            Action::Transfer { from, to, share: part } => {
+                let (from, to) = (user(from), user(to));
+                if env.record(&launch, &to).is_none() {
+                    let _ = env.create_record(&launch, &to);
+                }
+                env.transfer(&launch, &from, &to, share(&from, part))
+                    .and_then(|()| env.release(&launch, &from))
+            }
+            Action::ClaimVested { user: index } => {
+                let owner = user(index);
+                env.send(&[instructions::claim_vested(&owner, &launch.mint)], &[&owner])
+            }
+            Action::Warp { seconds } => {
+                env.svm.warp(seconds as i64);
+                Ok(())
+            }
+            Action::Graduate => env.graduate(&launch),
+        };
+
+        let pool = env.pool(&launch);
+        let context = || format!("after step {step} ({action:?}) of {sequence:?}");
+        let liability = bonding_curve_sell_proceeds(
+            pool.price_lamports,
+            pool.slope_scaled,
+            pool.tokens_sold_curve,
+            pool.tokens_sold_curve,
+        )
+        .unwrap_or_else(|| panic!("the curve can't price buying back its tokens {}", context()));
+        assert!(
+            pool.curve_sol_collected >= liability,
+            "curve collected {} but owes {liability} {}",
+            pool.curve_sol_collected,
+            context()
+        );
+        assert!(
+            env.vault_lamports(&launch) >= liability,
+            "vault holds {} but the curve owes {liability} {}",
+            env.vault_lamports(&launch),
+            context()
+        );
+        assert_eq!(
+            circulating(),
+            issued_before_curve + pool.tokens_sold_curve,
+            "tokens in circulation don't match the curve's issuance {}",
+            context()
+        );
+
+        // Every token the curve still owes for is some record's inventory
+        // or unassigned, so presale tokens never take curve buyers' SOL.
+        let mut inventory = pool.curve_tokens_unassigned;
+        for owner in &users {
+            let Some(record) = env.record(&launch, owner) else { continue };
+            assert!(
+                record.tokens_sold <= record.curve_tokens_bought,
+                "{owner} sold the curve more than it bought {}",
+                context()
+            );
+            inventory += record.curve_inventory();
+        }
+        assert_eq!(inventory, pool.tokens_sold_curve, "curve inventory doesn't add up {}", context());
+    }
+}
+
+fn token_account(launch: &Launch, owner: &Pubkey) -> Pubkey {
+    if [pda::vesting_escrow(&launch.pool).0, pda::creator_escrow(&launch.pool).0].contains(owner) {
+        *owner
+    } else {
+        pda::token_account(owner, &launch.mint)
+    }
+}
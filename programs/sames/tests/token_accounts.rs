@@ -0,0 +1,129 @@
+//! Token accounts the launch mints into and burns from must be the owner's
+//! associated token account for the launch mint, curve sells are bounded
+//! by what that account actually holds, and each escrow pays out only its
+//! own allocations.
+
+mod svm;
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::{ProgramError, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use sames::errors::SamesError;
+use sames::instruction::CreateLaunch;
+use sames::state::VestingSchedule;
+use sames_sdk::{instructions, pda};
+use svm::launch::*;
+use svm::TxError;
+
+#[track_caller]
+fn assert_anchor_error(result: Result<(), TxError>, error: ErrorCode) {
+    assert_eq!(result, Err(TxError::Program(ProgramError::Custom(error as u32))));
+}
+
+/// `ix` with its token account (`index`) replaced by `key`.
+fn with_token_account(mut ix: Instruction, index: usize, key: Pubkey) -> Instruction {
+    ix.accounts[index].pubkey = key;
+    ix
+}
+
+/// Index of `key` among `ix`'s accounts.
+fn position(ix: &Instruction, key: &Pubkey) -> usize {
+    ix.accounts.iter().position(|meta| meta.pubkey == *key).expect("account is passed")
+}
+
+#[test]
+fn allocations_go_to_the_buyers_own_account() {
+    let env = Env::new();
+    let (alice, mallory) = (env.user(), env.user());
+    let launch = env.launch(launch_args());
+    env.buy_presale(&launch, &alice, LAMPORTS_PER_SOL).unwrap();
+    env.svm.warp(PRESALE_SECONDS);
+
+    let finalize = instructions::finalize_launch(&launch.creator, &launch.mint, &alice);
+    let index = position(&finalize, &pda::token_account(&alice, &launch.mint));
+    let ixs = [
+        instructions::create_token_account(&launch.creator, &mallory, &launch.mint),
+        with_token_account(finalize, index, pda::token_account(&mallory, &launch.mint)),
+    ];
+    assert_anchor_error(env.send(&ixs, &[&launch.creator]), ErrorCode::ConstraintTokenOwner);
+
+    env.finalize(&launch, &alice).unwrap();
+    assert_eq!(env.balance(&launch, &alice), SUPPLY);
+}
+
+#[test]
+fn curve_buys_mint_to_the_buyers_own_account() {
+    let env = Env::new();
+    let (alice, carol) = (env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+
+    let buy = instructions::buy_curve(&carol, &launch.mint, LAMPORTS_PER_SOL);
+    let index = position(&buy, &pda::token_account(&carol, &launch.mint));
+    let into_alice = with_token_account(buy.clone(), index, pda::token_account(&alice, &launch.mint));
+    assert_anchor_error(env.send(&[into_alice], &[&carol]), ErrorCode::ConstraintTokenOwner);
+
+    // Carol's own token account, but not her associated one.
+    env.buy_curve(&launch, &carol, 1_000_000).unwrap();
+    let copy = Pubkey::new_unique();
+    env.svm.set_account(copy, env.svm.account(&pda::token_account(&carol, &launch.mint)).unwrap());
+    let into_copy = with_token_account(buy, index, copy);
+    assert_anchor_error(env.send(&[into_copy], &[&carol]), ErrorCode::ConstraintAssociated);
+}
+
+#[test]
+fn curve_sells_burn_from_the_sellers_own_account() {
+    let env = Env::new();
+    let (alice, carol) = (env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    env.buy_curve(&launch, &carol, LAMPORTS_PER_SOL).unwrap();
+
+    let sell = instructions::sell_curve(&carol, &launch.mint, 1_000);
+    let index = position(&sell, &pda::token_account(&carol, &launch.mint));
+    let from_alice = with_token_account(sell, index, pda::token_account(&alice, &launch.mint));
+    assert_anchor_error(env.send(&[from_alice], &[&carol]), ErrorCode::ConstraintTokenOwner);
+    assert_eq!(env.balance(&launch, &alice), SUPPLY);
+}
+
+#[test]
+fn curve_sells_are_bounded_by_the_wallet_balance() {
+    let env = Env::new();
+    let (alice, carol, dave) = (env.user(), env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    env.buy_curve(&launch, &carol, LAMPORTS_PER_SOL).unwrap();
+    let bought = env.balance(&launch, &carol);
+
+    // Tokens sent away can't be sold, even though Carol bought them.
+    env.create_record(&launch, &dave).unwrap();
+    env.transfer(&launch, &carol, &dave, bought / 2).unwrap();
+    let held = env.balance(&launch, &carol);
+    assert_error(env.sell_curve(&launch, &carol, held + 1), SamesError::InsufficientBalance);
+    env.sell_curve(&launch, &carol, held).unwrap();
+    assert_eq!(env.balance(&launch, &carol), 0);
+}
+
+#[test]
+fn creator_claims_draw_only_on_the_creator_escrow() {
+    let env = Env::new();
+    let alice = env.user();
+    let vesting = VestingSchedule { cliff_seconds: 0, duration_seconds: 100 };
+    let args = CreateLaunch {
+        presale_vesting: vesting,
+        creator_allocation_bps: 1_000,
+        creator_vesting: vesting,
+        ..launch_args()
+    };
+    let launch = env.live_launch(args, &[(alice, LAMPORTS_PER_SOL)]);
+    env.svm.warp(100);
+
+    let claim = instructions::claim_creator_allocation(&launch.creator, &launch.mint);
+    let index = position(&claim, &pda::creator_escrow(&launch.pool).0);
+    let ixs = [
+        instructions::create_token_account(&launch.creator, &launch.creator, &launch.mint),
+        with_token_account(claim, index, pda::vesting_escrow(&launch.pool).0),
+    ];
+    assert_anchor_error(env.send(&ixs, &[&launch.creator]), ErrorCode::ConstraintSeeds);
+    let cancel = instructions::cancel_launch(&launch.creator, &launch.mint, true);
+    let index = position(&cancel, &pda::creator_escrow(&launch.pool).0);
+    let cancel = with_token_account(cancel, index, pda::vesting_escrow(&launch.pool).0);
+    assert_anchor_error(env.send(&[cancel], &[&launch.creator]), ErrorCode::ConstraintSeeds);
+}
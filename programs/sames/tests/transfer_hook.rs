@@ -0,0 +1,246 @@
+//! The transfer hook, driven by real Token-2022 transfers: the presale
+//! lock, cost-basis carry between wallets, the price floor on market sells
+//! and the guardian's transfer pause.
+
+mod svm;
+
+use anchor_lang::prelude::{AccountMeta, Pubkey, Rent};
+use anchor_lang::{AnchorSerialize, Discriminator};
+use anchor_lang::solana_program::instruction::Instruction;
+use sames::errors::SamesError;
+use sames::events::{BlockReason, BuyerRecordCreated, CostBasisCarried, HookBlocked, MarketRegistryMigrated};
+use sames::state::{pause, BuyerRecord, LegacyMarketRegistry, MarketEntry, MarketKind, MarketRegistry};
+use sames_sdk::{instructions, pda};
+use spl_transfer_hook_interface::instruction::TransferHookInstruction;
+use svm::launch::*;
+
+#[test]
+fn presale_tokens_are_locked() {
+    let env = Env::new();
+    let (alice, bob) = (env.user(), env.user());
+    let launch = env.launch(launch_args());
+    env.buy_presale(&launch, &alice, LAMPORTS_PER_SOL).unwrap();
+    env.svm.warp(PRESALE_SECONDS);
+    env.finalize(&launch, &alice).unwrap();
+
+    // Allocated, but the curve isn't live yet.
+    assert_error(env.transfer(&launch, &alice, &bob, 1_000), SamesError::TransfersLocked);
+    let blocked = env.svm.log_events::<HookBlocked>().remove(0);
+    assert_eq!(blocked.reason, BlockReason::Locked);
+    assert_eq!((blocked.owner, blocked.amount), (alice, 1_000));
+
+    env.start_curve(&launch).unwrap();
+    env.create_record(&launch, &bob).unwrap();
+    env.transfer(&launch, &alice, &bob, 1_000).unwrap();
+    assert_eq!(env.balance(&launch, &bob), 1_000);
+}
+
+#[test]
+fn wallet_transfers_carry_the_cost_basis() {
+    let env = Env::new();
+    let (alice, carol, dave) = (env.user(), env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    env.buy_curve(&launch, &carol, LAMPORTS_PER_SOL).unwrap();
+    let carol_entry = env.record(&launch, &carol).unwrap().entry_price;
+    assert!(carol_entry > PRESALE_ENTRY);
+
+    // Dave has no record yet, so floored tokens can't reach him.
+    assert_error(env.transfer(&launch, &alice, &dave, 3_000), SamesError::ReceiverRecordMissing);
+    let blocked = env.svm.log_events::<HookBlocked>().remove(0);
+    assert_eq!((blocked.reason, blocked.entry_price), (BlockReason::NoReceiverRecord, PRESALE_ENTRY));
+
+    // Once he opens one, paying the rent himself, the floor follows the tokens.
+    let vault_before = env.vault_lamports(&launch);
+    env.create_record(&launch, &dave).unwrap();
+    let created = env.svm.events::<BuyerRecordCreated>().remove(0);
+    assert_eq!((created.buyer, created.rent_payer), (dave, dave));
+    env.transfer(&launch, &alice, &dave, 3_000).unwrap();
+    let record = env.record(&launch, &dave).unwrap();
+    assert_eq!((record.entry_price, record.tokens_received), (PRESALE_ENTRY, 3_000));
+    let carried = env.svm.events::<CostBasisCarried>().remove(0);
+    assert_eq!((carried.sender, carried.receiver, carried.amount), (alice, dave, 3_000));
+    assert_eq!((carried.sender_entry_price, carried.receiver_entry_price), (PRESALE_ENTRY, PRESALE_ENTRY));
+    assert_eq!(env.vault_lamports(&launch), vault_before);
+    assert_eq!(env.record(&launch, &alice).unwrap().tokens_sent, 3_000);
+
+    // Tokens from a higher entry raise Dave's floor to the weighted average.
+    env.transfer(&launch, &carol, &dave, 1_000).unwrap();
+    let record = env.record(&launch, &dave).unwrap();
+    assert_eq!(record.tokens_received, 4_000);
+    assert_eq!(record.entry_price, (3_000 * PRESALE_ENTRY + 1_000 * carol_entry).div_ceil(4_000));
+}
+
+#[test]
+fn market_sells_below_entry_are_blocked() {
+    let env = Env::new();
+    let (alice, carol, desk) = (env.user(), env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    let market = env.register_market(&launch, &desk, MarketKind::BondingCurveVault);
+    env.buy_curve(&launch, &carol, LAMPORTS_PER_SOL).unwrap();
+
+    // The curve has moved above Alice's presale price.
+    env.transfer(&launch, &alice, &desk, 1_000).unwrap();
+    assert_eq!(env.svm.token_balance(&market), 1_000);
+    assert_eq!(env.record(&launch, &alice).unwrap().tokens_sent, 1_000);
+
+    env.update_price(&launch, 1).unwrap();
+    assert_error(env.transfer(&launch, &alice, &desk, 1_000), SamesError::HookSellBelowEntry);
+    let blocked = env.svm.log_events::<HookBlocked>().remove(0);
+    assert_eq!(blocked.reason, BlockReason::BelowEntry);
+    assert_eq!((blocked.destination, blocked.entry_price), (market, PRESALE_ENTRY));
+    assert!(blocked.realized_price < PRESALE_ENTRY);
+    assert_eq!(env.svm.token_balance(&market), 1_000);
+}
+
+#[test]
+fn cpmm_reserves_must_pair_the_token_with_sol() {
+    let env = Env::new();
+    let (alice, pool) = (env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    let market = env.register_cpmm(&launch, &pool, 10 * LAMPORTS_PER_SOL);
+    env.transfer(&launch, &alice, &pool, 1_000).unwrap();
+
+    // A SOL reserve that stopped holding wrapped SOL can't price anything.
+    let mut reserve = env.svm.account(&market.sol_reserve).unwrap();
+    reserve.data[..32].copy_from_slice(Pubkey::new_unique().as_ref());
+    env.svm.set_account(market.sol_reserve, reserve);
+    assert_error(env.transfer(&launch, &alice, &pool, 1_000), SamesError::HookPriceDerivationFailed);
+    assert_eq!(env.svm.log_events::<HookBlocked>()[0].reason, BlockReason::Unpriceable);
+}
+
+#[test]
+fn orderbook_markets_are_unpriceable() {
+    let env = Env::new();
+    let (alice, desk) = (env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    env.register_market(&launch, &desk, MarketKind::Orderbook);
+
+    assert_error(env.transfer(&launch, &alice, &desk, 1_000), SamesError::HookPriceDerivationFailed);
+    let blocked = env.svm.log_events::<HookBlocked>().remove(0);
+    assert_eq!((blocked.reason, blocked.realized_price), (BlockReason::Unpriceable, 0));
+}
+
+#[test]
+fn allowlisted_dex_pools_are_unpriceable_until_registered() {
+    let env = Env::new();
+    let (alice, bob) = (env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    env.create_record(&launch, &bob).unwrap();
+
+    // The pool authority PDA has no account of its own, as is common.
+    let dex = Pubkey::new_unique();
+    let (authority, bump) = Pubkey::find_program_address(&[b"pool_authority"], &dex);
+    let seeds = vec![b"pool_authority".to_vec(), vec![bump]];
+    env.send(&[instructions::add_dex_program(&env.admin, dex, seeds)], &[&env.admin]).unwrap();
+    assert!(env.svm.account(&authority).is_none());
+
+    assert_error(env.transfer(&launch, &alice, &authority, 1_000), SamesError::HookPriceDerivationFailed);
+    assert_eq!(env.svm.log_events::<HookBlocked>()[0].reason, BlockReason::Unpriceable);
+    // Other wallets aren't pools.
+    env.transfer(&launch, &alice, &bob, 1_000).unwrap();
+}
+
+#[test]
+fn legacy_registries_migrate_as_orderbooks() {
+    let env = Env::new();
+    let (alice, bob, desk, payer) = (env.user(), env.user(), env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    env.create_record(&launch, &bob).unwrap();
+    let market = env.register_market(&launch, &desk, MarketKind::BondingCurveVault);
+
+    // Store the registry the way the program did before: bare addresses in
+    // a fixed-size account.
+    let address = pda::market_registry(&launch.pool).0;
+    let registry = env.registry(&launch);
+    let mut market_accounts = vec![market];
+    market_accounts.extend((1..16).map(|_| Pubkey::new_unique()));
+    let legacy = LegacyMarketRegistry {
+        launch_pool: registry.launch_pool,
+        authority: registry.authority,
+        market_accounts: market_accounts.clone(),
+        bump: registry.bump,
+    };
+    let mut data = MarketRegistry::DISCRIMINATOR.to_vec();
+    legacy.serialize(&mut data).unwrap();
+    assert_eq!(data.len(), LegacyMarketRegistry::SIZE);
+    let mut account = env.svm.account(&address).unwrap();
+    account.data = data;
+    account.lamports = Rent::default().minimum_balance(LegacyMarketRegistry::SIZE);
+    env.svm.set_account(address, account.clone());
+
+    // Until it's migrated the registry can't be loaded, so nothing moves.
+    let transfer = instructions::transfer(&alice, &bob, &launch.mint, 1_000, DECIMALS, &registry);
+    assert!(env.send(&[transfer], &[&alice]).is_err());
+
+    // Only genuine registries migrate.
+    let forged = Pubkey::new_unique();
+    env.svm.set_account(forged, account);
+    let migrate = |signer: &Pubkey, registry: &Pubkey| {
+        env.send(&[instructions::migrate_market_registry(signer, registry)], &[signer])
+    };
+    assert_error(migrate(&payer, &forged), SamesError::InvalidMarketRegistry);
+
+    // Sixteen entries need more space. The creator, who gets the
+    // registry's rent back, pays for it.
+    assert_error(migrate(&payer, &address), SamesError::UnauthorizedCreator);
+    migrate(&launch.creator, &address).unwrap();
+    assert_eq!(env.svm.events::<MarketRegistryMigrated>().remove(0).markets, market_accounts);
+    let migrated = env.registry(&launch);
+    assert_eq!((migrated.launch_pool, migrated.authority, migrated.bump), (registry.launch_pool, registry.authority, registry.bump));
+    assert_eq!(migrated.markets.iter().map(|m| m.address).collect::<Vec<_>>(), market_accounts);
+    assert!(migrated.markets.iter().all(|m| m.kind == MarketKind::Orderbook && m.is_valid()));
+    assert_eq!(env.svm.lamports(&address), Rent::default().minimum_balance(MarketRegistry::space(16)));
+    assert_error(migrate(&payer, &address), SamesError::MarketRegistryMigrated);
+
+    // The old layout didn't say how to price a market, so floored sells
+    // into one wait for the authority to set its kind.
+    env.transfer(&launch, &alice, &bob, 1_000).unwrap();
+    assert_error(env.transfer(&launch, &alice, &desk, 1_000), SamesError::HookPriceDerivationFailed);
+    let entry = MarketEntry { kind: MarketKind::BondingCurveVault, ..migrated.markets[0] };
+    env.send(&[instructions::replace_market(&launch.creator, &launch.mint, market, entry)], &[&launch.creator]).unwrap();
+    assert_eq!(env.registry(&launch).markets[0], entry);
+}
+
+#[test]
+fn guardian_can_pause_transfers() {
+    let env = Env::new();
+    let (alice, bob) = (env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    env.create_record(&launch, &bob).unwrap();
+
+    // The admin starts out as guardian.
+    env.send(&[instructions::set_launch_pause(&env.admin, &launch.mint, pause::TRANSFER)], &[&env.admin]).unwrap();
+    assert_error(env.transfer(&launch, &alice, &bob, 1_000), SamesError::Paused);
+    assert_eq!(env.svm.log_events::<HookBlocked>()[0].reason, BlockReason::Paused);
+
+    env.send(&[instructions::set_launch_pause(&env.admin, &launch.mint, 0)], &[&env.admin]).unwrap();
+    env.transfer(&launch, &alice, &bob, 1_000).unwrap();
+}
+
+#[test]
+fn hook_rejects_direct_invocation() {
+    let env = Env::new();
+    let (alice, bob) = (env.user(), env.user());
+    let launch = env.live_launch(launch_args(), &[(alice, LAMPORTS_PER_SOL)]);
+    env.create_record(&launch, &bob).unwrap();
+    env.transfer(&launch, &alice, &bob, 1_000).unwrap();
+    let bob_record: BuyerRecord = env.svm.anchor(&pda::buyer_record(&launch.pool, &bob).0);
+
+    // Calling the hook outside a transfer would let anyone rewrite records.
+    let mut accounts = vec![
+        AccountMeta::new_readonly(pda::token_account(&alice, &launch.mint), false),
+        AccountMeta::new_readonly(launch.mint, false),
+        AccountMeta::new_readonly(pda::token_account(&bob, &launch.mint), false),
+        AccountMeta::new_readonly(alice, true),
+        AccountMeta::new_readonly(pda::extra_account_metas(&launch.mint).0, false),
+    ];
+    let extras = instructions::transfer_hook_accounts(&launch.mint, &alice, &bob, &env.registry(&launch));
+    accounts.extend_from_slice(&extras[..extras.len() - 2]);
+    let hook = Instruction {
+        program_id: sames::ID,
+        accounts,
+        data: TransferHookInstruction::Execute { amount: 1_000 }.pack(),
+    };
+    assert_error(env.send(&[hook], &[&alice]), SamesError::NotTransferring);
+    assert_eq!(env.record(&launch, &bob).unwrap().tokens_received, bob_record.tokens_received);
+}
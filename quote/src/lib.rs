@@ -0,0 +1,351 @@
+//! Bonding-curve and constant-product math plus trade quotes.
+//!
+//! The program, the SDK and the browser (through the `wasm` feature) all link
+//! this crate, so a quote and the trade it predicts run the same arithmetic.
+
+#[cfg(feature = "wasm")]
+mod wasm;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Bonding curve math
+// ─────────────────────────────────────────────────────────────────────────────
+// We use a linear bonding curve for simplicity:
+//   price = base_price + slope * tokens_sold
+//
+// For a buy of `amount` tokens starting at `tokens_sold`:
+//   cost = integral from tokens_sold to tokens_sold + amount of (base + slope * x) dx
+//        = base * amount + slope * (amount * tokens_sold + amount^2 / 2)
+//
+// This gives increasing price as more tokens are bought.
+
+/// Calculate the cost in lamports to buy `amount` tokens on the bonding curve.
+/// Uses integer math with scaling to avoid overflow.
+/// base_price and slope are in lamports (slope is lamports per token, scaled by 1e9).
+/// Rounded up: the curve never charges less than the area it gives away.
+pub fn bonding_curve_cost(
+    base_price: u64,
+    slope_scaled: u64,  // slope * 1e9 (to handle fractional slopes)
+    tokens_sold: u64,
+    amount: u64,
+) -> Option<u64> {
+    curve_area(base_price, slope_scaled, tokens_sold, amount, true)
+}
+
+/// cost = base_price * amount + slope_scaled * amount * (2*tokens_sold + amount) / (2 * 1e9),
+/// with the slope term rounded up or down.
+fn curve_area(base_price: u64, slope_scaled: u64, tokens_sold: u64, amount: u64, round_up: bool) -> Option<u64> {
+    let base_cost = (base_price as u128).checked_mul(amount as u128)?;
+    let two_sold_plus_amount = (2u128)
+        .checked_mul(tokens_sold as u128)?
+        .checked_add(amount as u128)?;
+    let slope_numerator = (slope_scaled as u128)
+        .checked_mul(amount as u128)?
+        .checked_mul(two_sold_plus_amount)?;
+    let slope_cost = if round_up {
+        slope_numerator.div_ceil(2_000_000_000u128)  // divide by 2 * 1e9
+    } else {
+        slope_numerator / 2_000_000_000u128
+    };
+    let total = base_cost.checked_add(slope_cost)?;
+    if total > u64::MAX as u128 { return None; }
+    Some(total as u64)
+}
+
+/// Calculate how many tokens you get for `sol_amount` lamports on the bonding curve:
+/// the largest amount whose `bonding_curve_cost` is at most `sol_amount`.
+/// The quadratic formula gives an estimate, which is then corrected against
+/// the exact cost.
+pub fn bonding_curve_tokens_for_sol(
+    base_price: u64,
+    slope_scaled: u64,
+    tokens_sold: u64,
+    sol_amount: u64,
+) -> Option<u64> {
+    if slope_scaled == 0 {
+        // Linear pricing: tokens = sol_amount / base_price
+        return sol_amount.checked_div(base_price);
+    }
+    let fits = |amount: u64| {
+        bonding_curve_cost(base_price, slope_scaled, tokens_sold, amount).is_some_and(|cost| cost <= sol_amount)
+    };
+    let estimate = tokens_for_sol_estimate(base_price, slope_scaled, tokens_sold, sol_amount).unwrap_or(0);
+
+    // Gallop away from the estimate until the answer is bracketed by
+    // `fits(low)` and `!fits(high)`, then bisect. Cost is monotonic in the
+    // amount, and `fits(0)` always holds.
+    let (mut low, mut high) = if fits(estimate) {
+        let mut low = estimate;
+        let mut step = 1u64;
+        loop {
+            let Some(next) = estimate.checked_add(step) else {
+                if fits(u64::MAX) { return Some(u64::MAX); }
+                break (low, u64::MAX);
+            };
+            if !fits(next) { break (low, next); }
+            low = next;
+            step = step.saturating_mul(2);
+        }
+    } else {
+        let mut high = estimate;
+        let mut step = 1u64;
+        loop {
+            let next = estimate.saturating_sub(step);
+            if fits(next) { break (next, high); }
+            high = next;
+            step = step.saturating_mul(2);
+        }
+    };
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        if fits(mid) { low = mid; } else { high = mid; }
+    }
+    Some(low)
+}
+
+/// Closed-form approximation of `bonding_curve_tokens_for_sol`. Intermediate
+/// divisions floor, so it can land on either side of the exact answer.
+fn tokens_for_sol_estimate(
+    base_price: u64,
+    slope_scaled: u64,
+    tokens_sold: u64,
+    sol_amount: u64,
+) -> Option<u64> {
+    // Solving: slope_scaled * amount^2 / (2*1e9) + (base_price + slope_scaled * tokens_sold / 1e9) * amount = sol_amount
+    // Using quadratic formula: a*x^2 + b*x - c = 0
+    // a = slope_scaled / (2 * 1e9)
+    // b = base_price + slope_scaled * tokens_sold / 1e9
+    // c = sol_amount
+    // x = (-b + sqrt(b^2 + 4ac)) / (2a)
+    
+    // Work in u128 to avoid overflow
+    let a_num = slope_scaled as u128;  // numerator, will divide by 2e9 later
+    let b = (base_price as u128)
+        .checked_add(
+            (slope_scaled as u128)
+                .checked_mul(tokens_sold as u128)?
+                .checked_div(1_000_000_000u128)?
+        )?;
+    let c = sol_amount as u128;
+    
+    // discriminant = b^2 + 4 * (a_num / 2e9) * c = b^2 + 2 * a_num * c / 1e9
+    let b_squared = b.checked_mul(b)?;
+    let four_ac = (2u128)
+        .checked_mul(a_num)?
+        .checked_mul(c)?
+        .checked_div(1_000_000_000u128)?;
+    let discriminant = b_squared.checked_add(four_ac)?;
+    
+    // Integer square root
+    let sqrt_disc = isqrt_u128(discriminant);
+    
+    // amount = (-b + sqrt(disc)) / (2a) = (sqrt(disc) - b) * 1e9 / a_num
+    if sqrt_disc <= b { return Some(0); }
+    let numerator = (sqrt_disc - b).checked_mul(1_000_000_000u128)?;
+    let result = numerator.checked_div(a_num)?;
+    
+    Some(u64::try_from(result).unwrap_or(u64::MAX))
+}
+
+/// Calculate the current spot price on the bonding curve.
+pub fn bonding_curve_price(base_price: u64, slope_scaled: u64, tokens_sold: u64) -> u64 {
+    let slope_component = (slope_scaled as u128)
+        .checked_mul(tokens_sold as u128)
+        .and_then(|v| v.checked_div(1_000_000_000u128))
+        .unwrap_or(0);
+    let price = (base_price as u128).saturating_add(slope_component);
+    if price > u64::MAX as u128 { u64::MAX } else { price as u64 }
+}
+
+/// Calculate the SOL the curve pays for selling `amount` tokens back from `tokens_sold`.
+/// This is the area under the curve between `tokens_sold - amount` and `tokens_sold`,
+/// rounded down, so a buy and a sell of the same tokens never pay out more than came in.
+pub fn bonding_curve_sell_proceeds(
+    base_price: u64,
+    slope_scaled: u64,
+    tokens_sold: u64,
+    amount: u64,
+) -> Option<u64> {
+    curve_area(base_price, slope_scaled, tokens_sold.checked_sub(amount)?, amount, false)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Constant-product AMM math (graduated pools)
+// ─────────────────────────────────────────────────────────────────────────────
+//   token_reserve * sol_reserve = k
+//   sol_out = sol_reserve * amount / (token_reserve + amount)
+
+/// Calculate the SOL a constant-product pool pays for `amount` tokens,
+/// given its reserves before the trade. Pool fees are ignored.
+pub fn cpmm_sell_proceeds(token_reserve: u64, sol_reserve: u64, amount: u64) -> Option<u64> {
+    let numerator = (sol_reserve as u128).checked_mul(amount as u128)?;
+    let denominator = (token_reserve as u128).checked_add(amount as u128)?;
+    let result = numerator.checked_div(denominator)?;
+    u64::try_from(result).ok()
+}
+
+/// Integer square root for u128 (Newton's method).
+fn isqrt_u128(n: u128) -> u128 {
+    if n == 0 { return 0; }
+    let mut x = n;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Fees and the entry-price floor
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Platform fee taken from `gross` curve proceeds at `fee_bps` basis points.
+pub fn platform_fee(gross: u64, fee_bps: u16) -> Option<u64> {
+    let fee = (gross as u128) * (fee_bps as u128) / 10_000;
+    u64::try_from(fee).ok()
+}
+
+// Entry prices are lamports per base unit in fixed point, scaled by
+// ENTRY_PRICE_SCALE. Whole lamports per base unit are far too coarse for
+// cheap tokens: a token trading at 0.4 lamports would round to 0 (no floor)
+// or to 1 (a floor above anything the holder paid).
+
+/// Fixed-point scale of entry prices: 1 lamport per base unit.
+pub const ENTRY_PRICE_SCALE: u128 = 1_000_000_000_000;
+
+/// Entry price of `amount` tokens acquired for `cost` lamports.
+/// Rounded up, so a floor never sits below what was paid.
+pub fn entry_price(cost: u64, amount: u64) -> Option<u128> {
+    if amount == 0 {
+        return None;
+    }
+    Some((cost as u128 * ENTRY_PRICE_SCALE).div_ceil(amount as u128))
+}
+
+/// Entry price of `held` tokens at `entry_price` joined by `amount` tokens at
+/// `incoming_price`: the weighted average, rounded up so merging never
+/// lowers a floor through truncation. Every entry price after the first goes
+/// through here, whether the tokens were bought or received.
+pub fn merge_entry_price(held: u64, entry_price: u128, amount: u64, incoming_price: u128) -> Option<u128> {
+    let total_tokens = (held as u128).checked_add(amount as u128)?;
+    if total_tokens == 0 {
+        return Some(entry_price);
+    }
+    let total_cost = (held as u128)
+        .checked_mul(entry_price)?
+        .checked_add((amount as u128).checked_mul(incoming_price)?)?;
+    Some(total_cost.div_ceil(total_tokens))
+}
+
+/// Spot price on the bonding curve as an exact fixed-point entry price,
+/// where `bonding_curve_price` rounds down to whole lamports. Saturates.
+pub fn bonding_curve_spot_entry_price(base_price: u64, slope_scaled: u64, tokens_sold: u64) -> u128 {
+    // slope_scaled is lamports per token scaled by 1e9.
+    let slope_component = (slope_scaled as u128)
+        .saturating_mul(tokens_sold as u128)
+        .saturating_mul(ENTRY_PRICE_SCALE / 1_000_000_000);
+    (base_price as u128).saturating_mul(ENTRY_PRICE_SCALE).saturating_add(slope_component)
+}
+
+/// Whether selling `amount` tokens for `sol_out` lamports realizes less
+/// than `entry_price`. Compared without dividing, so nothing truncates.
+pub fn sale_below_entry(sol_out: u64, amount: u64, entry_price: u128) -> bool {
+    (sol_out as u128 * ENTRY_PRICE_SCALE) < entry_price.saturating_mul(amount as u128)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Quotes
+// ─────────────────────────────────────────────────────────────────────────────
+// A quote is what `buy_curve` / `sell_curve` would do against the current
+// curve state, without sending anything. Price impact is how far the trade
+// moves the spot price, in basis points of the spot price before it.
+
+/// Result of buying on the curve with a fixed SOL budget.
+#[cfg_attr(feature = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BuyQuote {
+    pub tokens_out: u64,
+    /// Lamports actually charged; at most the amount offered.
+    pub sol_in: u64,
+    /// Buys carry no platform fee; kept so both quotes read the same.
+    pub fee: u64,
+    pub price_before: u64,
+    pub price_after: u64,
+    pub price_impact_bps: u64,
+}
+
+/// Result of selling tokens back to the curve.
+#[cfg_attr(feature = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SellQuote {
+    pub tokens_in: u64,
+    /// Curve proceeds before the platform fee.
+    pub gross: u64,
+    pub fee: u64,
+    pub sol_out: u64,
+    pub price_before: u64,
+    pub price_after: u64,
+    pub price_impact_bps: u64,
+    /// `sol_out` realizes less than the seller's entry price, so
+    /// `sell_curve` would refuse.
+    pub blocked_by_floor: bool,
+}
+
+/// What `buy_curve` with `sol_amount` would mint and charge.
+/// `None` if the buy would fail (zero tokens or overflow).
+pub fn quote_buy(base_price: u64, slope_scaled: u64, tokens_sold: u64, sol_amount: u64) -> Option<BuyQuote> {
+    let tokens_out = bonding_curve_tokens_for_sol(base_price, slope_scaled, tokens_sold, sol_amount)?;
+    if tokens_out == 0 {
+        return None;
+    }
+    let sol_in = bonding_curve_cost(base_price, slope_scaled, tokens_sold, tokens_out)?;
+    let price_before = bonding_curve_price(base_price, slope_scaled, tokens_sold);
+    let price_after = bonding_curve_price(base_price, slope_scaled, tokens_sold.checked_add(tokens_out)?);
+    Some(BuyQuote {
+        tokens_out,
+        sol_in,
+        fee: 0,
+        price_before,
+        price_after,
+        price_impact_bps: price_impact_bps(price_before, price_after),
+    })
+}
+
+/// What `sell_curve` of `token_amount` would pay with the protocol's
+/// `fee_bps`, for a seller who entered at `entry_price` (fixed point, see
+/// `ENTRY_PRICE_SCALE`).
+/// `None` if the curve can't absorb the sale.
+pub fn quote_sell(
+    base_price: u64,
+    slope_scaled: u64,
+    tokens_sold: u64,
+    fee_bps: u16,
+    token_amount: u64,
+    entry_price: u128,
+) -> Option<SellQuote> {
+    let gross = bonding_curve_sell_proceeds(base_price, slope_scaled, tokens_sold, token_amount)?;
+    let fee = platform_fee(gross, fee_bps)?;
+    let price_before = bonding_curve_price(base_price, slope_scaled, tokens_sold);
+    let price_after = bonding_curve_price(base_price, slope_scaled, tokens_sold - token_amount);
+    Some(SellQuote {
+        tokens_in: token_amount,
+        gross,
+        fee,
+        sol_out: gross - fee,
+        price_before,
+        price_after,
+        price_impact_bps: price_impact_bps(price_before, price_after),
+        blocked_by_floor: sale_below_entry(gross - fee, token_amount, entry_price),
+    })
+}
+
+/// Spot price movement from `before` to `after`, in basis points of `before`.
+fn price_impact_bps(before: u64, after: u64) -> u64 {
+    let moved = before.abs_diff(after) as u128;
+    match moved.checked_mul(10_000).and_then(|v| v.checked_div(before as u128)) {
+        Some(bps) => u64::try_from(bps).unwrap_or(u64::MAX),
+        None if moved == 0 => 0,
+        None => u64::MAX,
+    }
+}
+
@@ -0,0 +1,28 @@
+//! JavaScript bindings. Build with
+//! `wasm-pack build quote --target web --features wasm`; amounts are `bigint`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{BuyQuote, SellQuote};
+
+#[wasm_bindgen(js_name = spotPrice)]
+pub fn spot_price(base_price: u64, slope_scaled: u64, tokens_sold: u64) -> u64 {
+    crate::bonding_curve_price(base_price, slope_scaled, tokens_sold)
+}
+
+#[wasm_bindgen(js_name = quoteBuy)]
+pub fn quote_buy(base_price: u64, slope_scaled: u64, tokens_sold: u64, sol_amount: u64) -> Option<BuyQuote> {
+    crate::quote_buy(base_price, slope_scaled, tokens_sold, sol_amount)
+}
+
+#[wasm_bindgen(js_name = quoteSell)]
+pub fn quote_sell(
+    base_price: u64,
+    slope_scaled: u64,
+    tokens_sold: u64,
+    fee_bps: u16,
+    token_amount: u64,
+    entry_price: u128,
+) -> Option<SellQuote> {
+    crate::quote_sell(base_price, slope_scaled, tokens_sold, fee_bps, token_amount, entry_price)
+}
@@ -0,0 +1,245 @@
+//! Properties of the curve math, and a differential check against an exact
+//! big-integer reference.
+
+use num_bigint::BigUint;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use sames_quote::{
+    bonding_curve_cost, bonding_curve_price, bonding_curve_sell_proceeds,
+    bonding_curve_spot_entry_price, bonding_curve_tokens_for_sol, cpmm_sell_proceeds, entry_price,
+    merge_entry_price, platform_fee, quote_buy, quote_sell, sale_below_entry, ENTRY_PRICE_SCALE,
+};
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Reference implementation
+// ─────────────────────────────────────────────────────────────────────────────
+// The area under price = base + slope / 1e9 * x from `sold` over `amount`
+// tokens, computed without overflow or intermediate rounding:
+//   (2e9 * base * amount + slope * amount * (2 * sold + amount)) / 2e9
+
+const SCALE: u64 = 2_000_000_000;
+
+fn area_numerator(base: u64, slope: u64, sold: u64, amount: u64) -> BigUint {
+    let (base, slope, sold, amount) = (BigUint::from(base), BigUint::from(slope), BigUint::from(sold), BigUint::from(amount));
+    BigUint::from(SCALE) * &base * &amount + slope * &amount * (sold * 2u32 + &amount)
+}
+
+fn to_u64(value: BigUint) -> Option<u64> {
+    u64::try_from(value).ok()
+}
+
+fn reference_cost(base: u64, slope: u64, sold: u64, amount: u64) -> BigUint {
+    (area_numerator(base, slope, sold, amount) + (SCALE - 1)) / SCALE
+}
+
+fn reference_proceeds(base: u64, slope: u64, sold: u64, amount: u64) -> Option<u64> {
+    let start = sold.checked_sub(amount)?;
+    to_u64(area_numerator(base, slope, start, amount) / SCALE)
+}
+
+/// The largest amount whose exact cost is within `sol`, by bisection.
+fn reference_tokens_for_sol(base: u64, slope: u64, sold: u64, sol: u64) -> u64 {
+    let (mut low, mut high) = (0u64, u64::MAX);
+    if reference_cost(base, slope, sold, high) <= BigUint::from(sol) {
+        return high;
+    }
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        if reference_cost(base, slope, sold, mid) <= BigUint::from(sol) {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Strategies
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Values up to `bits` bits wide, spread evenly over orders of magnitude:
+/// uniform draws would almost all be large enough to overflow.
+fn magnitude(bits: u32) -> impl Strategy<Value = u64> {
+    (0..bits).prop_flat_map(|width| 0..=(u64::MAX >> (63 - width)))
+}
+
+/// Base price, slope and curve position around the ones launches use,
+/// and well beyond.
+fn curve() -> impl Strategy<Value = (u64, u64, u64)> {
+    (magnitude(40), magnitude(40), magnitude(50))
+}
+
+fn lamports() -> impl Strategy<Value = u64> {
+    magnitude(60)
+}
+
+proptest! {
+    // ─────────────────────────────────────────────────────────────────────
+    // Buys
+    // ─────────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn buys_never_cost_more_than_offered((base, slope, sold) in curve(), sol in lamports()) {
+        if let Some(tokens) = bonding_curve_tokens_for_sol(base, slope, sold, sol) {
+            let cost = bonding_curve_cost(base, slope, sold, tokens);
+            prop_assert!(cost.is_some_and(|cost| cost <= sol), "{tokens} tokens cost {cost:?} > {sol}");
+        }
+    }
+
+    #[test]
+    fn buys_match_the_reference((base, slope, sold) in curve(), sol in lamports()) {
+        let tokens = bonding_curve_tokens_for_sol(base, slope, sold, sol);
+        if base == 0 && slope == 0 {
+            prop_assert_eq!(tokens, None);
+        } else {
+            prop_assert_eq!(tokens, Some(reference_tokens_for_sol(base, slope, sold, sol)));
+        }
+    }
+
+    #[test]
+    fn cost_matches_the_reference((base, slope, sold) in curve(), amount in magnitude(50)) {
+        prop_assert_eq!(bonding_curve_cost(base, slope, sold, amount), to_u64(reference_cost(base, slope, sold, amount)));
+    }
+
+    #[test]
+    fn proceeds_match_the_reference((base, slope, sold) in curve(), amount in magnitude(50)) {
+        prop_assert_eq!(
+            bonding_curve_sell_proceeds(base, slope, sold, amount),
+            reference_proceeds(base, slope, sold, amount)
+        );
+    }
+
+    // ─────────────────────────────────────────────────────────────────────
+    // Monotonicity
+    // ─────────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn more_sol_buys_no_fewer_tokens((base, slope, sold) in curve(), sol in lamports(), extra in lamports()) {
+        let less = bonding_curve_tokens_for_sol(base, slope, sold, sol);
+        let more = bonding_curve_tokens_for_sol(base, slope, sold, sol.saturating_add(extra));
+        prop_assert!(less <= more);
+    }
+
+    #[test]
+    fn later_buys_get_no_more_tokens((base, slope, sold) in curve(), later in lamports(), sol in lamports()) {
+        let early = bonding_curve_tokens_for_sol(base, slope, sold, sol);
+        let late = bonding_curve_tokens_for_sol(base, slope, sold.saturating_add(later), sol);
+        prop_assert!(late <= early);
+    }
+
+    #[test]
+    fn price_and_cost_never_fall(
+        (base, slope, sold) in curve(),
+        amount in lamports(),
+        extra in lamports(),
+    ) {
+        prop_assert!(bonding_curve_price(base, slope, sold) <= bonding_curve_price(base, slope, sold.saturating_add(extra)));
+        let cost = bonding_curve_cost(base, slope, sold, amount);
+        let more = bonding_curve_cost(base, slope, sold, amount.saturating_add(extra));
+        // `None` is an overflow: more than any u64 cost.
+        prop_assert!(more.is_none() || cost <= more);
+        // Later tokens cost at least as much as earlier ones.
+        let later = bonding_curve_cost(base, slope, sold.saturating_add(extra), amount);
+        prop_assert!(later.is_none() || cost <= later);
+    }
+
+    // ─────────────────────────────────────────────────────────────────────
+    // Round trips
+    // ─────────────────────────────────────────────────────────────────────
+
+    /// However a position is bought and sold back in pieces, the curve pays
+    /// out no more than it took in.
+    #[test]
+    fn buy_then_sell_never_profits(
+        (base, slope, sold) in curve(),
+        buys in vec(lamports(), 1..6),
+        sells in vec(1..=100u64, 0..6),
+        fee_bps in 0..=500u16,
+    ) {
+        let (mut position, mut curve_sold, mut paid) = (0u64, sold, 0u128);
+        for sol in buys {
+            let Some(quote) = quote_buy(base, slope, curve_sold, sol) else { continue };
+            position += quote.tokens_out;
+            curve_sold += quote.tokens_out;
+            paid += quote.sol_in as u128;
+        }
+
+        let mut received = 0u128;
+        let chunks = sells.into_iter().map(Some).chain([None]);
+        for percent in chunks {
+            let amount = percent.map_or(position, |percent| position / 100 * percent);
+            if amount == 0 {
+                continue;
+            }
+            let quote = quote_sell(base, slope, curve_sold, fee_bps, amount, 0);
+            let quote = quote.expect("the curve absorbs what it sold");
+            position -= amount;
+            curve_sold -= amount;
+            received += quote.sol_out as u128;
+        }
+        prop_assert_eq!(curve_sold, sold);
+        prop_assert!(received <= paid, "paid {} and got back {}", paid, received);
+    }
+
+    // ─────────────────────────────────────────────────────────────────────
+    // Entry prices
+    // ─────────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn spot_entry_price_refines_the_spot_price((base, slope, sold) in curve()) {
+        let spot = bonding_curve_spot_entry_price(base, slope, sold);
+        prop_assert_eq!(spot / ENTRY_PRICE_SCALE, bonding_curve_price(base, slope, sold) as u128);
+    }
+
+    #[test]
+    fn entry_price_is_the_smallest_that_covers_the_cost(cost in lamports(), amount in magnitude(50)) {
+        prop_assume!(amount > 0);
+        let price = entry_price(cost, amount).unwrap();
+        let paid = cost as u128 * ENTRY_PRICE_SCALE;
+        prop_assert!(price * amount as u128 >= paid);
+        prop_assert!(price == 0 || (price - 1) * (amount as u128) < paid);
+    }
+
+    #[test]
+    fn merged_entry_price_lies_between_its_parts(
+        held in magnitude(50),
+        amount in magnitude(50),
+        held_price in lamports(),
+        incoming_price in lamports(),
+    ) {
+        let (held_price, incoming_price) = (held_price as u128, incoming_price as u128);
+        let merged = merge_entry_price(held, held_price, amount, incoming_price).unwrap();
+        match (held, amount) {
+            (0, 0) => prop_assert_eq!(merged, held_price),
+            (0, _) => prop_assert_eq!(merged, incoming_price),
+            (_, 0) => prop_assert_eq!(merged, held_price),
+            _ => {
+                prop_assert!(merged >= held_price.min(incoming_price));
+                prop_assert!(merged <= held_price.max(incoming_price));
+            }
+        }
+    }
+
+    // ─────────────────────────────────────────────────────────────────────
+    // Extreme inputs
+    // ─────────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn never_panics(base: u64, slope: u64, sold: u64, amount: u64, sol: u64, fee_bps: u16, entry: u128) {
+        let _ = bonding_curve_cost(base, slope, sold, amount);
+        let _ = bonding_curve_sell_proceeds(base, slope, sold, amount);
+        let _ = bonding_curve_price(base, slope, sold);
+        let _ = cpmm_sell_proceeds(sold, sol, amount);
+        let _ = platform_fee(sol, fee_bps);
+        let _ = quote_buy(base, slope, sold, sol);
+        let _ = quote_sell(base, slope, sold, fee_bps, amount, entry);
+        let _ = entry_price(sol, amount);
+        let _ = merge_entry_price(sold, entry, amount, entry);
+        let _ = bonding_curve_spot_entry_price(base, slope, sold);
+        let _ = sale_below_entry(sol, amount, entry);
+        if let Some(tokens) = bonding_curve_tokens_for_sol(base, slope, sold, sol) {
+            prop_assert!(bonding_curve_cost(base, slope, sold, tokens).is_some_and(|cost| cost <= sol));
+        }
+    }
+}
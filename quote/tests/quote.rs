@@ -0,0 +1,66 @@
+use sames_quote::{
+    bonding_curve_spot_entry_price, entry_price, merge_entry_price, quote_buy, quote_sell, sale_below_entry,
+    ENTRY_PRICE_SCALE,
+};
+
+const BASE: u64 = 1_000;
+const SLOPE: u64 = 1_000_000_000;
+
+#[test]
+fn buy_then_sell_round_trips_minus_fee() {
+    let buy = quote_buy(BASE, SLOPE, 50_000, 1_000_000_000).unwrap();
+    assert!(buy.sol_in <= 1_000_000_000);
+    assert!(buy.price_after > buy.price_before);
+    assert!(buy.price_impact_bps > 0);
+
+    let sell = quote_sell(BASE, SLOPE, 50_000 + buy.tokens_out, 100, buy.tokens_out, 0).unwrap();
+    assert_eq!(sell.gross, buy.sol_in);
+    assert_eq!(sell.fee, buy.sol_in / 100);
+    assert_eq!(sell.sol_out, sell.gross - sell.fee);
+    assert_eq!(sell.price_after, buy.price_before);
+    assert!(!sell.blocked_by_floor);
+}
+
+#[test]
+fn sell_reports_the_entry_floor() {
+    // The floor applies to what the seller gets, after the fee: selling
+    // at the spot price's entry still slides down the curve.
+    let sell = |entry| quote_sell(BASE, SLOPE, 50_000, 100, 10, entry).unwrap();
+    let realized = sell(0).sol_out as u128 * ENTRY_PRICE_SCALE / 10;
+    assert!(!sell(realized).blocked_by_floor);
+    assert!(sell(realized + 1).blocked_by_floor);
+    assert!(sell(bonding_curve_spot_entry_price(BASE, SLOPE, 50_000)).blocked_by_floor);
+    assert!(quote_sell(BASE, SLOPE, 50_000, 0, 50_001, 0).is_none());
+}
+
+#[test]
+fn price_impact_is_relative_to_spot_before() {
+    // Flat curve: price never moves.
+    let flat = quote_buy(BASE, 0, 0, 1_000_000).unwrap();
+    assert_eq!(flat.tokens_out, 1_000);
+    assert_eq!(flat.price_impact_bps, 0);
+
+    // 1 lamport base, 1 lamport/token slope: buying from 0 moves price 1 -> 1 + tokens.
+    let steep = quote_buy(1, SLOPE, 0, 1_000).unwrap();
+    assert_eq!(steep.price_impact_bps, (steep.price_after - 1) * 10_000);
+}
+
+#[test]
+fn entry_prices_keep_sub_lamport_precision() {
+    // Three tokens for a lamport: a third of a lamport each, not 0.
+    let third = entry_price(1, 3).unwrap();
+    assert_eq!(third, ENTRY_PRICE_SCALE.div_ceil(3));
+    assert!(sale_below_entry(0, 3, third));
+    assert!(!sale_below_entry(1, 2, third));
+
+    // One token at a lamport joined by one at half a lamport.
+    let half = entry_price(1, 2).unwrap();
+    assert_eq!(merge_entry_price(1, ENTRY_PRICE_SCALE, 1, half), Some(ENTRY_PRICE_SCALE * 3 / 4));
+
+    // Two tokens off a curve at 1 + x/2 lamports pay 3 lamports: 1.5 each,
+    // which whole-lamport prices would round to 1.
+    let sell = |entry| quote_sell(1, SLOPE / 2, 2, 0, 2, entry).unwrap();
+    assert_eq!(sell(0).sol_out, 3);
+    assert!(!sell(entry_price(3, 2).unwrap()).blocked_by_floor);
+    assert!(sell(entry_price(16, 10).unwrap()).blocked_by_floor);
+}
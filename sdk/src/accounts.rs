@@ -0,0 +1,24 @@
+//! Typed decoders for raw account data, discriminator checked.
+
+use anchor_lang::{AccountDeserialize, Result};
+use sames::state::{BuyerRecord, LaunchPool, MarketRegistry, ProtocolConfig};
+
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+pub fn launch_pool(data: &[u8]) -> Result<LaunchPool> {
+    decode(data)
+}
+
+pub fn buyer_record(data: &[u8]) -> Result<BuyerRecord> {
+    decode(data)
+}
+
+pub fn market_registry(data: &[u8]) -> Result<MarketRegistry> {
+    decode(data)
+}
+
+pub fn protocol_config(data: &[u8]) -> Result<ProtocolConfig> {
+    decode(data)
+}
@@ -0,0 +1,578 @@
+//! One builder per program instruction. Token accounts are the owners'
+//! Token-2022 associated token accounts.
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::system_instruction;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::spl_associated_token_account;
+use anchor_spl::token_2022;
+use spl_token_2022::extension::{transfer_hook, ExtensionType};
+use spl_token_2022::state::Mint;
+use sames::state::{MarketEntry, MarketKind, MarketRegistry, ProtocolBounds, ProtocolParams};
+use sames::{accounts, instruction};
+
+use crate::pda;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: sames::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn event_authority() -> Pubkey {
+    pda::event_authority().0
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Launch lifecycle
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Space for a launch mint: a Token-2022 mint with the transfer hook extension.
+pub fn mint_space() -> usize {
+    ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferHook])
+        .expect("mint with one fixed-size extension")
+}
+
+/// Create and initialize a launch mint: the transfer hook points at this
+/// program and the launch pool is the mint authority. `mint` must sign;
+/// `lamports` is the rent for `mint_space()` bytes.
+pub fn create_mint(payer: &Pubkey, mint: &Pubkey, decimals: u8, lamports: u64) -> Vec<Instruction> {
+    let launch_pool = pda::launch_pool(mint).0;
+    vec![
+        system_instruction::create_account(payer, mint, lamports, mint_space() as u64, &token_2022::ID),
+        transfer_hook::instruction::initialize(&token_2022::ID, mint, None, Some(sames::ID))
+            .expect("Token-2022 program id is valid"),
+        spl_token_2022::instruction::initialize_mint2(&token_2022::ID, mint, &launch_pool, None, decimals)
+            .expect("Token-2022 program id is valid"),
+    ]
+}
+
+/// Create `owner`'s associated token account for `mint` unless it exists.
+pub fn create_token_account(payer: &Pubkey, owner: &Pubkey, mint: &Pubkey) -> Instruction {
+    spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+        payer, owner, mint, &token_2022::ID,
+    )
+}
+
+/// `mint` must already exist (see `create_mint`).
+pub fn create_launch(creator: &Pubkey, mint: &Pubkey, args: instruction::CreateLaunch) -> Instruction {
+    let launch_pool = pda::launch_pool(mint).0;
+    build(
+        accounts::CreateLaunch {
+            creator: *creator,
+            mint: *mint,
+            launch_pool,
+            vault: pda::vault(&launch_pool).0,
+            market_registry: pda::market_registry(&launch_pool).0,
+            protocol_config: pda::protocol_config().0,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        args,
+    )
+}
+
+pub fn buy_presale(buyer: &Pubkey, mint: &Pubkey, sol_amount: u64) -> Instruction {
+    let launch_pool = pda::launch_pool(mint).0;
+    build(
+        accounts::BuyPresale {
+            buyer: *buyer,
+            launch_pool,
+            vault: pda::vault(&launch_pool).0,
+            buyer_record: pda::buyer_record(&launch_pool, buyer).0,
+            system_program: system_program::ID,
+            protocol_config: pda::protocol_config().0,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        instruction::BuyPresale { sol_amount },
+    )
+}
+
+/// Allocate `buyer`'s presale tokens. Signed by the creator.
+pub fn finalize_launch(creator: &Pubkey, mint: &Pubkey, buyer: &Pubkey) -> Instruction {
+    let launch_pool = pda::launch_pool(mint).0;
+    build(
+        accounts::FinalizeLaunch {
+            creator: *creator,
+            launch_pool,
+            mint: *mint,
+            buyer_record: pda::buyer_record(&launch_pool, buyer).0,
+            buyer_token_account: pda::token_account(buyer, mint),
+            vesting_escrow: pda::vesting_escrow(&launch_pool).0,
+            token_program: token_2022::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        instruction::FinalizeLaunch {},
+    )
+}
+
+pub fn claim_vested(buyer: &Pubkey, mint: &Pubkey) -> Instruction {
+    let launch_pool = pda::launch_pool(mint).0;
+    build(
+        accounts::ClaimVested {
+            buyer: *buyer,
+            launch_pool,
+            mint: *mint,
+            buyer_record: pda::buyer_record(&launch_pool, buyer).0,
+            vesting_escrow: pda::vesting_escrow(&launch_pool).0,
+            buyer_token_account: pda::token_account(buyer, mint),
+            token_program: token_2022::ID,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        instruction::ClaimVested {},
+    )
+}
+
+pub fn start_bonding_curve(creator: &Pubkey, mint: &Pubkey) -> Instruction {
+    let launch_pool = pda::launch_pool(mint).0;
+    build(
+        accounts::StartBondingCurve {
+            creator: *creator,
+            launch_pool,
+            mint: *mint,
+            creator_escrow: pda::creator_escrow(&launch_pool).0,
+            token_program: token_2022::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        instruction::StartBondingCurve {},
+    )
+}
+
+pub fn claim_creator_allocation(creator: &Pubkey, mint: &Pubkey) -> Instruction {
+    let launch_pool = pda::launch_pool(mint).0;
+    build(
+        accounts::ClaimCreatorAllocation {
+            creator: *creator,
+            launch_pool,
+            mint: *mint,
+            creator_escrow: pda::creator_escrow(&launch_pool).0,
+            creator_token_account: pda::token_account(creator, mint),
+            token_program: token_2022::ID,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        instruction::ClaimCreatorAllocation {},
+    )
+}
+
+pub fn buy_curve(buyer: &Pubkey, mint: &Pubkey, sol_amount: u64) -> Instruction {
+    let launch_pool = pda::launch_pool(mint).0;
+    build(
+        accounts::BuyCurve {
+            buyer: *buyer,
+            launch_pool,
+            mint: *mint,
+            vault: pda::vault(&launch_pool).0,
+            buyer_record: pda::buyer_record(&launch_pool, buyer).0,
+            buyer_token_account: pda::token_account(buyer, mint),
+            token_program: token_2022::ID,
+            system_program: system_program::ID,
+            protocol_config: pda::protocol_config().0,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        instruction::BuyCurve { sol_amount },
+    )
+}
+
+pub fn sell_curve(seller: &Pubkey, mint: &Pubkey, token_amount: u64) -> Instruction {
+    let launch_pool = pda::launch_pool(mint).0;
+    build(
+        accounts::SellCurve {
+            seller: *seller,
+            launch_pool,
+            mint: *mint,
+            vault: pda::vault(&launch_pool).0,
+            buyer_record: pda::buyer_record(&launch_pool, seller).0,
+            seller_token_account: pda::token_account(seller, mint),
+            token_program: token_2022::ID,
+            system_program: system_program::ID,
+            protocol_config: pda::protocol_config().0,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        instruction::SellCurve { token_amount },
+    )
+}
+
+/// Release the curve inventory `owner`'s transfers carried off, so the
+/// tokens' new holders can sell them back to the curve.
+pub fn release_curve_inventory(owner: &Pubkey, mint: &Pubkey) -> Instruction {
+    let launch_pool = pda::launch_pool(mint).0;
+    build(
+        accounts::ReleaseCurveInventory {
+            launch_pool,
+            buyer_record: pda::buyer_record(&launch_pool, owner).0,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        instruction::ReleaseCurveInventory {},
+    )
+}
+
+pub fn graduate(caller: &Pubkey, mint: &Pubkey) -> Instruction {
+    let launch_pool = pda::launch_pool(mint).0;
+    build(
+        accounts::Graduate {
+            caller: *caller,
+            launch_pool,
+            vault: pda::vault(&launch_pool).0,
+            protocol_config: pda::protocol_config().0,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        instruction::Graduate {},
+    )
+}
+
+pub fn update_price(authority: &Pubkey, mint: &Pubkey, new_price: u64) -> Instruction {
+    build(
+        accounts::UpdatePrice {
+            authority: *authority,
+            launch_pool: pda::launch_pool(mint).0,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        instruction::UpdatePrice { new_price },
+    )
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Cancellation and cleanup
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// `creator_escrow` only if the launch has one (a creator allocation was minted).
+pub fn cancel_launch(creator: &Pubkey, mint: &Pubkey, with_escrow: bool) -> Instruction {
+    let launch_pool = pda::launch_pool(mint).0;
+    build(
+        accounts::CancelLaunch {
+            creator: *creator,
+            launch_pool,
+            mint: *mint,
+            creator_escrow: with_escrow.then(|| pda::creator_escrow(&launch_pool).0),
+            token_program: token_2022::ID,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        instruction::CancelLaunch {},
+    )
+}
+
+/// Pass `with_tokens` / `with_escrow` when the buyer holds allocated tokens
+/// in their wallet / in the vesting escrow; those are burned by the refund.
+pub fn claim_refund(buyer: &Pubkey, mint: &Pubkey, with_tokens: bool, with_escrow: bool) -> Instruction {
+    let launch_pool = pda::launch_pool(mint).0;
+    build(
+        accounts::ClaimRefund {
+            buyer: *buyer,
+            launch_pool,
+            mint: *mint,
+            vault: pda::vault(&launch_pool).0,
+            buyer_record: pda::buyer_record(&launch_pool, buyer).0,
+            buyer_token_account: with_tokens.then(|| pda::token_account(buyer, mint)),
+            vesting_escrow: with_escrow.then(|| pda::vesting_escrow(&launch_pool).0),
+            protocol_config: pda::protocol_config().0,
+            token_program: token_2022::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        instruction::ClaimRefund {},
+    )
+}
+
+/// Pass `with_escrows` once the curve has started, which creates both escrows.
+pub fn close_launch(creator: &Pubkey, mint: &Pubkey, with_escrows: bool) -> Instruction {
+    let launch_pool = pda::launch_pool(mint).0;
+    build(
+        accounts::CloseLaunch {
+            creator: *creator,
+            launch_pool,
+            mint: *mint,
+            vault: pda::vault(&launch_pool).0,
+            market_registry: pda::market_registry(&launch_pool).0,
+            extra_account_meta_list: pda::extra_account_metas(mint).0,
+            vesting_escrow: with_escrows.then(|| pda::vesting_escrow(&launch_pool).0),
+            creator_escrow: with_escrows.then(|| pda::creator_escrow(&launch_pool).0),
+            token_program: token_2022::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        instruction::CloseLaunch {},
+    )
+}
+
+/// Open an empty BuyerRecord for `owner`, so it can receive tokens that
+/// carry a price floor. `payer` covers the rent and gets it back on close.
+pub fn create_buyer_record(payer: &Pubkey, owner: &Pubkey, mint: &Pubkey) -> Instruction {
+    let launch_pool = pda::launch_pool(mint).0;
+    build(
+        accounts::CreateBuyerRecord {
+            payer: *payer,
+            owner: *owner,
+            launch_pool,
+            buyer_record: pda::buyer_record(&launch_pool, owner).0,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        instruction::CreateBuyerRecord {},
+    )
+}
+
+/// `rent_payer` is the record's `rent_payer`: the buyer, unless someone else
+/// opened or migrated the record.
+pub fn close_buyer_record(buyer: &Pubkey, mint: &Pubkey, rent_payer: &Pubkey) -> Instruction {
+    let launch_pool = pda::launch_pool(mint).0;
+    build(
+        accounts::CloseBuyerRecord {
+            buyer: *buyer,
+            launch_pool,
+            buyer_record: pda::buyer_record(&launch_pool, buyer).0,
+            rent_payer: *rent_payer,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        instruction::CloseBuyerRecord {},
+    )
+}
+
+/// Rewrite a `buyer_record` still in the whole-lamport entry price layout;
+/// `payer` covers the rent of the extra space and gets it back on close.
+pub fn migrate_buyer_record(payer: &Pubkey, buyer_record: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateBuyerRecord {
+            payer: *payer,
+            buyer_record: *buyer_record,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        instruction::MigrateBuyerRecord {},
+    )
+}
+
+/// Rewrite a `market_registry` still in the address-list layout. If it
+/// needs more space, `payer` must be its authority, the launch creator.
+pub fn migrate_market_registry(payer: &Pubkey, market_registry: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateMarketRegistry {
+            payer: *payer,
+            market_registry: *market_registry,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        instruction::MigrateMarketRegistry {},
+    )
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Market registry and transfer hook
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn registry_accounts(authority: &Pubkey, mint: &Pubkey) -> accounts::AddMarket {
+    let launch_pool = pda::launch_pool(mint).0;
+    accounts::AddMarket {
+        authority: *authority,
+        launch_pool,
+        market_registry: pda::market_registry(&launch_pool).0,
+        extra_account_meta_list: pda::extra_account_metas(mint).0,
+        system_program: system_program::ID,
+        event_authority: event_authority(),
+        program: sames::ID,
+    }
+}
+
+/// Cpmm markets carry their reserves along so the program can check them.
+pub fn add_market(authority: &Pubkey, mint: &Pubkey, market: MarketEntry) -> Instruction {
+    with_reserves(build(registry_accounts(authority, mint), instruction::AddMarket { market }), &market)
+}
+
+pub fn remove_market(authority: &Pubkey, mint: &Pubkey, address: Pubkey) -> Instruction {
+    // Same accounts as add_market.
+    build(registry_accounts(authority, mint), instruction::RemoveMarket { address })
+}
+
+pub fn replace_market(authority: &Pubkey, mint: &Pubkey, address: Pubkey, market: MarketEntry) -> Instruction {
+    let ix = build(registry_accounts(authority, mint), instruction::ReplaceMarket { address, market });
+    with_reserves(ix, &market)
+}
+
+fn with_reserves(mut ix: Instruction, market: &MarketEntry) -> Instruction {
+    if market.kind == MarketKind::Cpmm {
+        ix.accounts.push(AccountMeta::new_readonly(market.token_reserve, false));
+        ix.accounts.push(AccountMeta::new_readonly(market.sol_reserve, false));
+    }
+    ix
+}
+
+pub fn initialize_extra_account_meta_list(payer: &Pubkey, mint: &Pubkey) -> Instruction {
+    let launch_pool = pda::launch_pool(mint).0;
+    build(
+        accounts::InitializeExtraAccountMetaList {
+            payer: *payer,
+            extra_account_meta_list: pda::extra_account_metas(mint).0,
+            mint: *mint,
+            launch_pool,
+            market_registry: pda::market_registry(&launch_pool).0,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeExtraAccountMetaList {},
+    )
+}
+
+/// The accounts Token-2022 appends to a transfer of a launch token so it can
+/// invoke the hook: the resolved extra metas, then the hook program and its
+/// validation account. `registry` supplies the Cpmm reserves.
+pub fn transfer_hook_accounts(
+    mint: &Pubkey,
+    source_owner: &Pubkey,
+    destination_owner: &Pubkey,
+    registry: &MarketRegistry,
+) -> Vec<AccountMeta> {
+    let launch_pool = pda::launch_pool(mint).0;
+    let mut metas = vec![
+        AccountMeta::new_readonly(launch_pool, false),
+        AccountMeta::new(pda::buyer_record(&launch_pool, source_owner).0, false),
+        AccountMeta::new_readonly(pda::market_registry(&launch_pool).0, false),
+        AccountMeta::new(pda::buyer_record(&launch_pool, destination_owner).0, false),
+        AccountMeta::new_readonly(pda::protocol_config().0, false),
+        AccountMeta::new_readonly(event_authority(), false),
+        AccountMeta::new_readonly(sames::ID, false),
+    ];
+    for market in registry.markets.iter().filter(|m| m.kind == MarketKind::Cpmm) {
+        metas.push(AccountMeta::new_readonly(market.token_reserve, false));
+        metas.push(AccountMeta::new_readonly(market.sol_reserve, false));
+    }
+    metas.push(AccountMeta::new_readonly(sames::ID, false));
+    metas.push(AccountMeta::new_readonly(pda::extra_account_metas(mint).0, false));
+    metas
+}
+
+/// A Token-2022 `transfer_checked` of a launch token between two owners'
+/// associated token accounts, with the hook's accounts attached.
+pub fn transfer(
+    owner: &Pubkey,
+    destination_owner: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+    decimals: u8,
+    registry: &MarketRegistry,
+) -> Instruction {
+    let mut ix = spl_token_2022::instruction::transfer_checked(
+        &token_2022::ID,
+        &pda::token_account(owner, mint),
+        mint,
+        &pda::token_account(destination_owner, mint),
+        owner,
+        &[],
+        amount,
+        decimals,
+    )
+    .expect("Token-2022 program id is valid");
+    ix.accounts.extend(transfer_hook_accounts(mint, owner, destination_owner, registry));
+    ix
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Protocol administration
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Signed by the program's upgrade authority.
+pub fn initialize_protocol_config(admin: &Pubkey, params: ProtocolParams) -> Instruction {
+    let program_data = Pubkey::find_program_address(&[sames::ID.as_ref()], &bpf_loader_upgradeable::ID).0;
+    build(
+        accounts::InitializeProtocolConfig {
+            admin: *admin,
+            protocol_config: pda::protocol_config().0,
+            program_data,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        instruction::InitializeProtocolConfig { params },
+    )
+}
+
+fn admin_accounts(admin: &Pubkey) -> accounts::UpdateProtocolConfig {
+    accounts::UpdateProtocolConfig {
+        admin: *admin,
+        protocol_config: pda::protocol_config().0,
+        event_authority: event_authority(),
+        program: sames::ID,
+    }
+}
+
+pub fn update_protocol_config(admin: &Pubkey, params: ProtocolParams) -> Instruction {
+    build(admin_accounts(admin), instruction::UpdateProtocolConfig { params })
+}
+
+pub fn update_protocol_bounds(admin: &Pubkey, bounds: ProtocolBounds) -> Instruction {
+    build(admin_accounts(admin), instruction::UpdateProtocolBounds { bounds })
+}
+
+pub fn propose_admin(admin: &Pubkey, new_admin: Pubkey) -> Instruction {
+    build(admin_accounts(admin), instruction::ProposeAdmin { new_admin })
+}
+
+pub fn accept_admin(new_admin: &Pubkey) -> Instruction {
+    build(
+        accounts::AcceptAdmin {
+            new_admin: *new_admin,
+            protocol_config: pda::protocol_config().0,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        instruction::AcceptAdmin {},
+    )
+}
+
+pub fn add_dex_program(admin: &Pubkey, program_id: Pubkey, authority_seeds: Vec<Vec<u8>>) -> Instruction {
+    build(admin_accounts(admin), instruction::AddDexProgram { program_id, authority_seeds })
+}
+
+pub fn remove_dex_program(admin: &Pubkey, program_id: Pubkey) -> Instruction {
+    build(admin_accounts(admin), instruction::RemoveDexProgram { program_id })
+}
+
+pub fn set_guardian(admin: &Pubkey, guardian: Pubkey) -> Instruction {
+    build(admin_accounts(admin), instruction::SetGuardian { guardian })
+}
+
+pub fn set_protocol_pause(guardian: &Pubkey, flags: u16) -> Instruction {
+    build(
+        accounts::SetProtocolPause {
+            guardian: *guardian,
+            protocol_config: pda::protocol_config().0,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        instruction::SetProtocolPause { flags },
+    )
+}
+
+pub fn set_launch_pause(guardian: &Pubkey, mint: &Pubkey, flags: u16) -> Instruction {
+    build(
+        accounts::SetLaunchPause {
+            guardian: *guardian,
+            protocol_config: pda::protocol_config().0,
+            launch_pool: pda::launch_pool(mint).0,
+            event_authority: event_authority(),
+            program: sames::ID,
+        },
+        instruction::SetLaunchPause { flags },
+    )
+}
@@ -0,0 +1,14 @@
+//! Off-chain client for the SAMES program.
+//!
+//! Builders wrap the Anchor-generated `sames::accounts` and
+//! `sames::instruction` types, so account order and argument encoding come
+//! from the program itself rather than a hand-maintained copy.
+
+pub mod accounts;
+pub mod instructions;
+pub mod pda;
+pub mod quote;
+
+pub use sames::events;
+pub use sames::state;
+pub use sames::ID as PROGRAM_ID;
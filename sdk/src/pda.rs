@@ -0,0 +1,53 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_2022;
+
+fn find(seeds: &[&[u8]]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(seeds, &sames::ID)
+}
+
+pub fn launch_pool(mint: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"launch_pool", mint.as_ref()])
+}
+
+/// SOL vault holding presale deposits and curve proceeds.
+pub fn vault(launch_pool: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"vault", launch_pool.as_ref()])
+}
+
+pub fn buyer_record(launch_pool: &Pubkey, buyer: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"buyer_record", launch_pool.as_ref(), buyer.as_ref()])
+}
+
+pub fn market_registry(launch_pool: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"market_registry", launch_pool.as_ref()])
+}
+
+/// The transfer hook's validation account for `mint`.
+pub fn extra_account_metas(mint: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"extra-account-metas", mint.as_ref()])
+}
+
+/// Token account holding vesting presale allocations.
+pub fn vesting_escrow(launch_pool: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"vesting_escrow", launch_pool.as_ref()])
+}
+
+/// Token account holding the creator allocation while it vests.
+pub fn creator_escrow(launch_pool: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"creator_escrow", launch_pool.as_ref()])
+}
+
+pub fn protocol_config() -> (Pubkey, u8) {
+    find(&[b"protocol_config"])
+}
+
+/// Signer of the program's `emit_cpi!` self-invocations.
+pub fn event_authority() -> (Pubkey, u8) {
+    find(&[b"__event_authority"])
+}
+
+/// `owner`'s Token-2022 associated token account for `mint`.
+pub fn token_account(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, &token_2022::ID)
+}
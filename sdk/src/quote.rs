@@ -0,0 +1,36 @@
+//! Off-chain quotes for a `LaunchPool`, computed by `sames-quote`, the same
+//! crate the program trades with.
+
+use sames::state::{bonding_curve_price, LaunchPool};
+
+pub use sames_quote::{BuyQuote, SellQuote};
+
+/// Current curve spot price.
+pub fn spot_price(pool: &LaunchPool) -> u64 {
+    bonding_curve_price(pool.price_lamports, pool.slope_scaled, pool.tokens_sold_curve)
+}
+
+/// What `buy_curve` with `sol_amount` would mint and charge.
+/// `None` if the buy would fail (zero tokens or overflow).
+pub fn buy(pool: &LaunchPool, sol_amount: u64) -> Option<BuyQuote> {
+    sames_quote::quote_buy(pool.price_lamports, pool.slope_scaled, pool.tokens_sold_curve, sol_amount)
+}
+
+/// What `sell_curve` of `token_amount` would pay, with the protocol's
+/// `platform_fee_bps`, for a seller whose `BuyerRecord.entry_price` is
+/// `entry_price` (0 for sellers without a record). `None` if the curve
+/// can't absorb the sale.
+pub fn sell(pool: &LaunchPool, platform_fee_bps: u16, token_amount: u64, entry_price: u128) -> Option<SellQuote> {
+    sames_quote::quote_sell(
+        pool.price_lamports, pool.slope_scaled, pool.tokens_sold_curve,
+        platform_fee_bps, token_amount, entry_price,
+    )
+}
+
+/// Tokens `finalize_launch` allocates to a buyer who deposited `sol_deposited`.
+pub fn presale_allocation(pool: &LaunchPool, sol_deposited: u64) -> Option<u64> {
+    let tokens = (sol_deposited as u128)
+        .checked_mul(pool.presale_supply() as u128)?
+        .checked_div(pool.total_sol_collected as u128)?;
+    u64::try_from(tokens).ok()
+}
@@ -0,0 +1,111 @@
+#[path = "../../programs/sames/tests/common/mod.rs"]
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountSerialize, Discriminator};
+use sames::hook::buyer_record_address;
+use sames::state::*;
+use sames_sdk::{accounts, instructions, pda, quote};
+
+fn launch_pool(mint: Pubkey) -> LaunchPool {
+    LaunchPool {
+        mint,
+        total_supply: 1_000_000_000,
+        price_lamports: 1_000,
+        slope_scaled: 1_000_000_000,
+        tokens_sold_curve: 50_000,
+        total_sol_collected: 10_000_000,
+        status: LaunchStatus::BondingCurve,
+        creator_allocation_bps: 1_000,
+        ..common::launch_pool()
+    }
+}
+
+#[test]
+fn pdas_match_the_program() {
+    let mint = Pubkey::new_unique();
+    let buyer = Pubkey::new_unique();
+    let launch_pool = pda::launch_pool(&mint).0;
+    assert_eq!(pda::buyer_record(&launch_pool, &buyer), buyer_record_address(&launch_pool, &buyer));
+    assert_eq!(
+        pda::extra_account_metas(&mint).0,
+        spl_transfer_hook_interface::get_extra_account_metas_address(&mint, &sames::ID),
+    );
+}
+
+#[test]
+fn builders_append_event_cpi_accounts() {
+    let buyer = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let ix = instructions::buy_curve(&buyer, &mint, 5_000);
+
+    assert_eq!(ix.program_id, sames::ID);
+    assert_eq!(&ix.data[..8], sames::instruction::BuyCurve::DISCRIMINATOR);
+    assert_eq!(ix.data[8..], 5_000u64.to_le_bytes());
+    assert!(ix.accounts[0].is_signer && ix.accounts[0].pubkey == buyer);
+    let n = ix.accounts.len();
+    assert_eq!(ix.accounts[n - 2].pubkey, pda::event_authority().0);
+    assert_eq!(ix.accounts[n - 1].pubkey, sames::ID);
+}
+
+#[test]
+fn transfer_carries_hook_accounts_for_each_cpmm_market() {
+    let mint = Pubkey::new_unique();
+    let mut registry = MarketRegistry {
+        launch_pool: pda::launch_pool(&mint).0,
+        authority: Pubkey::new_unique(),
+        markets: Vec::new(),
+        bump: 255,
+    };
+    registry.markets.push(MarketEntry {
+        address: Pubkey::new_unique(),
+        kind: MarketKind::Cpmm,
+        token_reserve: Pubkey::new_unique(),
+        sol_reserve: Pubkey::new_unique(),
+    });
+    registry.markets.push(MarketEntry {
+        address: Pubkey::new_unique(),
+        kind: MarketKind::BondingCurveVault,
+        token_reserve: Pubkey::default(),
+        sol_reserve: Pubkey::default(),
+    });
+
+    let ix = instructions::transfer(&Pubkey::new_unique(), &Pubkey::new_unique(), &mint, 10, 6, &registry);
+    // source, mint, destination, owner + 7 hook accounts + 2 reserves + program + meta list
+    assert_eq!(ix.accounts.len(), 4 + 7 + 2 + 2);
+    assert_eq!(ix.accounts.last().unwrap().pubkey, pda::extra_account_metas(&mint).0);
+}
+
+#[test]
+fn decodes_launch_pool() {
+    let pool = launch_pool(Pubkey::new_unique());
+    let mut data = Vec::new();
+    pool.try_serialize(&mut data).unwrap();
+
+    let decoded = accounts::launch_pool(&data).unwrap();
+    assert_eq!(decoded.mint, pool.mint);
+    assert_eq!(decoded.tokens_sold_curve, 50_000);
+    assert!(accounts::buyer_record(&data).is_err());
+}
+
+#[test]
+fn quotes_follow_the_curve() {
+    let mut pool = launch_pool(Pubkey::new_unique());
+    let buy = quote::buy(&pool, 1_000_000_000).unwrap();
+    assert!(buy.sol_in <= 1_000_000_000);
+    assert!(buy.price_after > quote::spot_price(&pool));
+
+    // Selling straight back pays the same curve area, minus the fee.
+    pool.tokens_sold_curve += buy.tokens_out;
+    let sell = quote::sell(&pool, 100, buy.tokens_out, 0).unwrap();
+    assert_eq!(sell.gross, buy.sol_in);
+    assert_eq!(sell.fee, buy.sol_in / 100);
+    assert_eq!(sell.sol_out, sell.gross - sell.fee);
+    assert!(!sell.blocked_by_floor);
+    let above_spot = (sell.price_before as u128 + 1) * ENTRY_PRICE_SCALE;
+    assert!(quote::sell(&pool, 100, buy.tokens_out, above_spot).unwrap().blocked_by_floor);
+
+    assert!(quote::sell(&pool, 100, pool.tokens_sold_curve + 1, 0).is_none());
+    // 90% of supply goes to presale buyers; this one deposited a tenth.
+    assert_eq!(quote::presale_allocation(&pool, 1_000_000), Some(90_000_000));
+}
@@ -47,7 +47,11 @@ describe("sames", () => {
           "SAMES Token",       // token_name
           "SAMES",             // token_symbol
           new anchor.BN(1_000_000_000), // total_supply (1B tokens)
-          new anchor.BN(1_000_000)      // price_lamports (0.001 SOL)
+          new anchor.BN(1_000_000),     // price_lamports (0.001 SOL)
+          false,                        // floor_after_graduation
+          { cliffSeconds: 0, durationSeconds: 0 }, // presale_vesting (none)
+          0,                                       // creator_allocation_bps
+          { cliffSeconds: 0, durationSeconds: 0 }  // creator_vesting (none)
         )
         .accounts({
           creator: creator.publicKey,